@@ -2,11 +2,43 @@ use clap::{Parser, Subcommand};
 use futures_util::{SinkExt, StreamExt};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
-use tapo::{ApiClient, DiscoveryResult};
-use tokio::time::{interval, sleep};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tapo::requests::{
+    AlarmDuration, AlarmRingtone, AlarmVolume, BatchReadParams, DefaultPowerState,
+};
+use tapo::responses::{
+    ChildDeviceHubResult, ComponentListResult, FirmwareUpdateState, KE100Result, LedRule,
+    NextEventResult, OvercurrentStatus, OverheatStatus, PowerProtectionStatus, S200BLog, Status,
+    T100Log, TemperatureUnitKE100,
+};
+use tapo::{
+    local_broadcast_addresses, AnnouncementDiscovery, ApiClient, AutoOffExt, ComponentListExt,
+    CountdownExt, DeviceInfoExt, DeviceTimeExt, DiscoveryEvent, DiscoveryResult, DiscoveryWatcher,
+    FirmwareExt, GenericDeviceHandler, HubDevice, HubHandler, KE100Handler, LedExt, OnOffExt,
+    PlugEnergyMonitoringHandler, RetryPolicy, S200BHandler, ScannedHost, ScheduleExt, SubnetScan,
+    T100Handler,
+};
+use tokio::sync::OnceCell;
+use tokio::time::{interval, sleep, MissedTickBehavior};
 use tokio_tungstenite::{connect_async, tungstenite::Message};
 
+// How often device clocks are compared with ours, and how far they may drift before being set
+const CLOCK_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+const MAX_CLOCK_DRIFT_SECS: i64 = 60;
+
+// How often device settings are compared with [devices.settings] and put back if changed
+const SETTINGS_CHECK_INTERVAL: Duration = Duration::from_secs(900);
+
+// How long `tapo-agent firmware --update` waits for a device to finish updating
+const FIRMWARE_UPDATE_TIMEOUT: Duration = Duration::from_secs(600);
+
+// How often button and motion sensor trigger logs are read, button presses should act quickly
+const TRIGGER_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const TRIGGER_LOG_PAGE_SIZE: u64 = 20;
+const DEFAULT_PAUSE_MIN: u64 = 10;
+
 #[derive(Parser)]
 #[command(name = "tapo-agent")]
 #[command(about = "Tapo smart plug sensor data collection agent")]
@@ -39,9 +71,13 @@ enum Commands {
         #[arg(long)]
         password: String,
 
-        /// Broadcast address for discovery (default: 192.168.1.255)
-        #[arg(long, default_value = "192.168.1.255")]
-        broadcast: String,
+        /// Broadcast address for discovery (default: the broadcast addresses of the local networks)
+        #[arg(long)]
+        broadcast: Option<String>,
+
+        /// Probe every host of a network instead of broadcasting, e.g. 10.20.0.0/24 for a VLAN that blocks broadcasts
+        #[arg(long, conflicts_with = "broadcast")]
+        scan: Option<String>,
 
         /// Discovery timeout in seconds
         #[arg(long, default_value = "10")]
@@ -53,6 +89,51 @@ enum Commands {
     },
     /// Run the agent (default if no subcommand)
     Run,
+    /// Report devices with outdated firmware and optionally update them one at a time
+    Firmware {
+        /// Download and install available updates (default: only report)
+        #[arg(long)]
+        update: bool,
+
+        /// Only check the device with this name
+        #[arg(long)]
+        device: Option<String>,
+    },
+    /// Set up a factory-reset device in setup mode without the Tapo app.
+    /// Join the device's own Wi-Fi network (e.g. Tapo_Plug_XXXX) first.
+    /// Without --ssid, only lists the networks the device can see.
+    Onboard {
+        /// Device address on its setup network
+        #[arg(long, default_value = "192.168.0.1")]
+        ip: String,
+
+        /// Tapo account email to bind to the device
+        #[arg(long)]
+        email: String,
+
+        /// Tapo account password to bind to the device
+        #[arg(long)]
+        password: String,
+
+        /// Wi-Fi network the device should join
+        #[arg(long)]
+        ssid: Option<String>,
+
+        /// Wi-Fi network password (omit for open networks)
+        #[arg(long, default_value = "")]
+        wifi_password: String,
+    },
+    /// Compare config device names with the nicknames stored on the devices.
+    /// Without --push or --pull, only reports the devices that differ.
+    SyncNames {
+        /// Set each device's nickname to its name in the config
+        #[arg(long, conflicts_with = "pull")]
+        push: bool,
+
+        /// Rename devices in the config after their nicknames (rewrites the config file)
+        #[arg(long)]
+        pull: bool,
+    },
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -62,6 +143,8 @@ struct Config {
     poll_interval_secs: u64,
     #[serde(default)]
     command_url: Option<String>, // HTTP URL for command polling (e.g., http://localhost:3905/api/outputs/commands)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    watch_broadcast: Vec<String>, // Broadcast addresses to watch for devices changing address or dropping off Wi-Fi
     devices: Vec<DeviceConfig>,
 }
 
@@ -73,6 +156,131 @@ struct DeviceConfig {
     device_type: String,
     tapo_email: String,
     tapo_password: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_state: Option<DefaultPowerState>, // Same as settings.default_state, kept for older configs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    power_protection_watts: Option<u16>, // Same as settings.power_protection_watts, kept for older configs
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    critical: bool, // Never updated by `tapo-agent firmware --update` while switched on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mac: Option<String>, // Recognizes the device when it answers from another address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    child: Option<String>, // KE100, S200B and T100 only, device id or nickname on the hub at ip (defaults to name)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    triggers: Vec<TriggerConfig>, // S200B and T100 only, local actions run on their events
+    #[serde(default, skip_serializing_if = "AlarmConfig::is_empty")]
+    alarm: AlarmConfig, // H100 siren defaults, a TOML table like settings
+    #[serde(default, skip_serializing_if = "DeviceSettings::is_empty")]
+    settings: DeviceSettings, // Must stay last, TOML tables go after plain values
+}
+
+impl DeviceConfig {
+    // [devices.settings] with the older top-level options filled in
+    fn desired_settings(&self) -> DeviceSettings {
+        let mut settings = self.settings.clone();
+        settings.default_state = settings.default_state.or(self.default_state);
+        settings.power_protection_watts = settings
+            .power_protection_watts
+            .or(self.power_protection_watts);
+        settings
+    }
+}
+
+// Settings pinned on a device, put back whenever they differ (e.g. after a change in the Tapo app)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+struct DeviceSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default_state: Option<DefaultPowerState>, // State after a power cut: "on", "off" or "last_state"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    led: Option<LedRule>, // "always", "never" or "night_mode"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_off_min: Option<u64>, // Turn off after this many minutes on, 0 disables auto-off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    power_protection_watts: Option<u16>, // P110/P115 turn off above this load, 0 disables protection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nickname: Option<String>, // Name shown in the Tapo app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    time_zone: Option<TimeZoneSetting>,
+}
+
+impl DeviceSettings {
+    fn is_empty(&self) -> bool {
+        self.default_state.is_none()
+            && self.led.is_none()
+            && self.auto_off_min.is_none()
+            && self.power_protection_watts.is_none()
+            && self.nickname.is_none()
+            && self.time_zone.is_none()
+    }
+}
+
+// A local action run when a button or motion sensor logs an event, also while the server is unreachable
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct TriggerConfig {
+    event: String, // "single_click", "double_click", "rotation", "low_battery" (S200B) or "motion" (T100)
+    action: TriggerAction,
+    targets: Vec<String>, // Config names of the plugs to act on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    minutes: Option<u64>, // pause only, how long the targets stay off (default 10)
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum TriggerAction {
+    On,
+    Off,
+    Toggle,
+    Pause, // Switch the targets that are on off, and back on after `minutes`
+}
+
+// How an H100 sounds its siren for set_state commands and for alarm commands that leave these out
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+struct AlarmConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ringtone: Option<AlarmRingtone>, // e.g. "Alarm 1" or "Phone Ring", the hub's default when left out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    volume: Option<AlarmVolume>, // "mute", "low", "normal" or "high"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    duration_secs: Option<u32>, // Stop after this many seconds, plays until stopped when left out
+}
+
+impl AlarmConfig {
+    fn is_empty(&self) -> bool {
+        self.ringtone.is_none() && self.volume.is_none() && self.duration_secs.is_none()
+    }
+
+    fn duration(&self) -> AlarmDuration {
+        self.duration_secs
+            .filter(|&secs| secs > 0)
+            .map(AlarmDuration::Seconds)
+            .unwrap_or(AlarmDuration::Continuous)
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+struct TimeZoneSetting {
+    region: String,      // e.g. "Europe/London"
+    utc_offset_min: i32, // Offset of local time from UTC, e.g. 60 for CET
+}
+
+// A setting found different from [devices.settings]
+#[derive(Debug, Serialize)]
+struct SettingDrift {
+    setting: &'static str,
+    found: String,
+    desired: String,
+    // Why the device was left as found, None when it was set back
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unsupported: Option<String>,
+}
+
+impl SettingDrift {
+    fn unsupported(self, reason: String) -> Self {
+        Self {
+            unsupported: Some(reason),
+            ..self
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -110,75 +318,218 @@ struct ServerResponse {
     error: Option<String>,
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn discover_and_create_config(
     server: String,
     key: String,
     email: String,
     password: String,
-    broadcast: String,
+    broadcast: Option<String>,
+    scan: Option<String>,
     timeout: u64,
     output: String,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Discovering Tapo devices on {} ({}s timeout)...", broadcast, timeout);
+    // Collect the hosts first without logging in, devices bound to another account still show up
+    let mut hosts: Vec<ScannedHost> = Vec::new();
+    let mut watch_broadcast = Vec::new();
+
+    if let Some(cidr) = scan {
+        println!("Scanning {} for Tapo devices...", cidr);
+
+        let mut scan = SubnetScan::new(&cidr)?.start().await?;
+        while let Some(host) = scan.next().await {
+            match host {
+                Ok(host) => hosts.push(host),
+                Err(e) => debug!("Ignoring scan response: {}", e),
+            }
+        }
+    } else {
+        watch_broadcast = match broadcast {
+            Some(broadcast) => vec![broadcast],
+            None => local_broadcast_addresses()?
+                .iter()
+                .map(|ip| ip.to_string())
+                .collect(),
+        };
+        if watch_broadcast.is_empty() {
+            return Err("No local network found, pass --broadcast or --scan".into());
+        }
+        println!(
+            "Discovering Tapo devices on {} ({}s timeout)...",
+            watch_broadcast.join(", "),
+            timeout
+        );
 
-    let api_client = ApiClient::new(&email, &password);
-    let mut discovery = api_client.discover_devices(&broadcast, timeout).await?;
+        for target in &watch_broadcast {
+            let mut discovery = AnnouncementDiscovery::new(target, timeout).await?;
+            while let Some(announcement) = discovery.next().await {
+                match announcement {
+                    Ok(announcement) if !hosts.iter().any(|h| h.ip == announcement.ip) => hosts
+                        .push(ScannedHost {
+                            ip: announcement.ip,
+                            announcement: Some(announcement),
+                        }),
+                    Ok(_) => {}
+                    Err(e) => debug!("Ignoring discovery response: {}", e),
+                }
+            }
+        }
+    }
 
     let mut devices = Vec::new();
 
-    while let Some(discovery_result) = discovery.next().await {
-        if let Ok(device) = discovery_result {
-            match device {
-                DiscoveryResult::Plug { device_info, .. } => {
-                    println!(
-                        "  Found Plug: {} ({}) at {}",
-                        device_info.nickname, device_info.model, device_info.ip
-                    );
-                    devices.push(DeviceConfig {
-                        ip: device_info.ip,
-                        name: device_info.nickname.replace(" ", "-").to_lowercase(),
-                        device_type: "P100".to_string(),
-                        tapo_email: email.clone(),
-                        tapo_password: password.clone(),
-                    });
+    for host in hosts {
+        let mut children = Vec::new();
+        // Hosts found by the HTTP probe have no announcement, log in to find out what they are
+        let model = host
+            .announcement
+            .as_ref()
+            .map(|a| a.model.as_str())
+            .unwrap_or("Unknown device");
+        if host.announcement.is_some()
+            && !matches!(
+                model.split('(').next().unwrap_or_default(),
+                "P100" | "P105" | "P110" | "P110M" | "P115" | "H100"
+            )
+        {
+            // Light bulbs and other devices - skip for now
+            println!("  Found {} at {} - skipping", model, host.ip);
+            continue;
+        }
+
+        let (device_type, nickname, model, ip, mac) =
+            match host.connect(ApiClient::new(&email, &password)).await {
+                Ok(DiscoveryResult::Plug { device_info, .. }) => (
+                    "P100",
+                    device_info.nickname,
+                    device_info.model,
+                    device_info.ip,
+                    device_info.mac,
+                ),
+                Ok(DiscoveryResult::PlugEnergyMonitoring { device_info, .. }) => (
+                    "P110",
+                    device_info.nickname,
+                    device_info.model,
+                    device_info.ip,
+                    device_info.mac,
+                ),
+                Ok(DiscoveryResult::Hub {
+                    device_info,
+                    handler,
+                }) => {
+                    match handler.get_child_device_list().await {
+                        Ok(list) => {
+                            children = list
+                                .into_iter()
+                                .filter_map(|c| match c {
+                                    ChildDeviceHubResult::KE100(valve) => {
+                                        Some(("KE100", "Valve", valve.nickname, valve.device_id))
+                                    }
+                                    ChildDeviceHubResult::S200B(button) => {
+                                        Some(("S200B", "Button", button.nickname, button.device_id))
+                                    }
+                                    ChildDeviceHubResult::T100(sensor) => Some((
+                                        "T100",
+                                        "Motion Sensor",
+                                        sensor.nickname,
+                                        sensor.device_id,
+                                    )),
+                                    _ => None,
+                                })
+                                .collect()
+                        }
+                        Err(e) => println!(
+                            "  Cannot list the devices paired with {}: {}",
+                            device_info.nickname, e
+                        ),
+                    }
+                    (
+                        "H100",
+                        device_info.nickname,
+                        device_info.model,
+                        device_info.ip,
+                        device_info.mac,
+                    )
                 }
-                DiscoveryResult::PlugEnergyMonitoring { device_info, .. } => {
-                    println!(
-                        "  Found Energy Plug: {} ({}) at {}",
-                        device_info.nickname, device_info.model, device_info.ip
-                    );
-                    devices.push(DeviceConfig {
-                        ip: device_info.ip,
-                        name: device_info.nickname.replace(" ", "-").to_lowercase(),
-                        device_type: "P110".to_string(),
-                        tapo_email: email.clone(),
-                        tapo_password: password.clone(),
-                    });
+                Ok(_) => {
+                    println!("  Found {} at {} - skipping", model, host.ip);
+                    continue;
                 }
-                DiscoveryResult::GenericDevice { device_info, .. } => {
+                Err(e) => {
                     println!(
-                        "  Found Unknown Device: {:?} ({}) at {} - skipping",
-                        device_info.nickname, device_info.model, device_info.ip
+                        "  Found {} at {} - cannot log in, bound to another account? {}",
+                        model, host.ip, e
                     );
+                    continue;
                 }
-                _ => {
-                    // Light bulbs and other devices - skip for now
-                }
-            }
+            };
+
+        println!(
+            "  Found {}: {} ({}) at {}",
+            match device_type {
+                "P110" => "Energy Plug",
+                "H100" => "Hub",
+                _ => "Plug",
+            },
+            nickname,
+            model,
+            ip
+        );
+        devices.push(DeviceConfig {
+            ip: ip.clone(),
+            name: config_name(&nickname),
+            device_type: device_type.to_string(),
+            tapo_email: email.clone(),
+            tapo_password: password.clone(),
+            default_state: None,
+            power_protection_watts: None,
+            critical: false,
+            mac: Some(mac.clone()),
+            child: None,
+            triggers: Vec::new(),
+            alarm: AlarmConfig::default(),
+            settings: DeviceSettings::default(),
+        });
+
+        // Hub children are reached through the hub and follow its address
+        for (child_type, label, child_nickname, device_id) in children {
+            println!(
+                "    Found {}: {} ({}) on {}",
+                label,
+                child_nickname,
+                child_type,
+                config_name(&nickname)
+            );
+            devices.push(DeviceConfig {
+                ip: ip.clone(),
+                name: config_name(&child_nickname),
+                device_type: child_type.to_string(),
+                tapo_email: email.clone(),
+                tapo_password: password.clone(),
+                default_state: None,
+                power_protection_watts: None,
+                critical: false,
+                mac: Some(mac.clone()),
+                child: Some(device_id),
+                triggers: Vec::new(),
+                alarm: AlarmConfig::default(),
+                settings: DeviceSettings::default(),
+            });
         }
     }
 
     if devices.is_empty() {
-        return Err("No plugs discovered. Check your broadcast address and ensure devices are on the same network.".into());
+        return Err("No plugs or hubs discovered. Check your broadcast address, or pass --scan with the network of the devices.".into());
     }
 
-    println!("\nDiscovered {} plug(s)", devices.len());
+    println!("\nDiscovered {} device(s)", devices.len());
 
     let config = Config {
         server_url: server,
         api_key: key,
         poll_interval_secs: 60,
         command_url: None,
+        watch_broadcast,
         devices,
     };
 
@@ -191,15 +542,227 @@ async fn discover_and_create_config(
     Ok(())
 }
 
-async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
+// Config names are derived from device nicknames: "Grow Light" becomes "grow-light"
+fn config_name(nickname: &str) -> String {
+    nickname.replace(" ", "-").to_lowercase()
+}
+
+// Returns whether the config was changed and needs to be written back
+async fn sync_names(config: &mut Config, push: bool, pull: bool) -> bool {
+    let mut changed = false;
+
+    for i in 0..config.devices.len() {
+        let device = &config.devices[i];
+        let name = device.name.clone();
+
+        if config.devices.iter().filter(|d| d.name == name).count() > 1 {
+            println!(
+                "{}: conflict, name is used by several devices in the config",
+                name
+            );
+            continue;
+        }
+
+        let nickname = match device_nickname(device).await {
+            Ok(nickname) => nickname,
+            Err(e) => {
+                println!("{}: failed: {}", name, e);
+                continue;
+            }
+        };
+
+        if nickname == name || config_name(&nickname) == name {
+            println!("{}: in sync", name);
+            continue;
+        }
+
+        if push {
+            match set_device_nickname(device, &name).await {
+                Ok(()) => println!("{}: device renamed from \"{}\"", name, nickname),
+                Err(e) => println!(
+                    "{}: failed to rename device from \"{}\": {}",
+                    name, nickname, e
+                ),
+            }
+        } else if pull {
+            let new_name = config_name(&nickname);
+            if config.devices.iter().any(|d| d.name == new_name) {
+                println!("{}: conflict, device nickname \"{}\" is already used by another device in the config", name, nickname);
+                continue;
+            }
+
+            println!("{}: renamed to {} in config", name, new_name);
+            rename_device(config, i, new_name);
+            changed = true;
+        } else {
+            println!("{}: conflict, device nickname is \"{}\"", name, nickname);
+        }
+    }
+
+    changed
+}
+
+// Rename a device in the config, along with the triggers that act on it
+fn rename_device(config: &mut Config, i: usize, new_name: String) {
+    let name = std::mem::replace(&mut config.devices[i].name, new_name.clone());
+
+    // Hub children found by their config name keep being found by the old one
+    if is_hub_child(&config.devices[i]) {
+        config.devices[i].child.get_or_insert(name.clone());
+    }
+
+    for target in config
+        .devices
+        .iter_mut()
+        .flat_map(|d| &mut d.triggers)
+        .flat_map(|t| &mut t.targets)
+        .filter(|target| **target == name)
+    {
+        *target = new_name.clone();
+    }
+}
+
+// Hub children are named on their hub, everything else on the device itself
+async fn device_nickname(device: &DeviceConfig) -> Result<String, tapo::Error> {
+    if is_hub_child(device) {
+        let (_, child) = find_hub_child(device).await?;
+        return Ok(child.nickname().unwrap_or_default().to_string());
+    }
+    generic_handler(device)
+        .await?
+        .get_device_info()
+        .await
+        .map(|info| info.nickname)
+}
+
+async fn set_device_nickname(device: &DeviceConfig, nickname: &str) -> Result<(), tapo::Error> {
+    match device.device_type.as_str() {
+        "KE100" => valve_handler(device).await?.set_nickname(nickname).await,
+        "S200B" | "T100" => match TriggerSource::connect(device).await? {
+            TriggerSource::Button(button) => button.set_nickname(nickname).await,
+            TriggerSource::Motion(sensor) => sensor.set_nickname(nickname).await,
+        },
+        _ => generic_handler(device).await?.set_nickname(nickname).await,
+    }
+}
+
+async fn generic_handler(device: &DeviceConfig) -> Result<GenericDeviceHandler, tapo::Error> {
+    ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default())
+        .generic_device(&device.ip)
+        .await
+}
+
+// Credential errors won't go away on their own, transient ones are retried on the next poll
+fn log_connect_error(device: &DeviceConfig, e: &tapo::Error) {
+    if e.is_auth() {
+        error!(
+            "Authentication failed for {} {}: {} (check tapo_email/tapo_password)",
+            device.device_type, device.name, e
+        );
+    } else if e.is_retryable() {
+        warn!(
+            "Failed to connect to {} {}: {} (retrying on next poll)",
+            device.device_type, device.name, e
+        );
+    } else {
+        error!(
+            "Failed to connect to {} {}: {}",
+            device.device_type, device.name, e
+        );
+    }
+}
+
+// Seconds until the next rule fires as value, with its time and action, or null if none is scheduled
+fn next_event_reading(device: &DeviceConfig, event: &NextEventResult) -> Reading {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+
+    let (value, data) = match event.start_time() {
+        Some(at) => (
+            Some((at.timestamp() - now).max(0) as f64),
+            serde_json::json!({
+                "id": event.id,
+                "at": at.timestamp(),
+                "action": event.desired_states.as_ref()
+                    .and_then(|s| s.on)
+                    .map(|on| if on { "on" } else { "off" })
+            }),
+        ),
+        None => (None, serde_json::Value::Null),
+    };
+
+    Reading {
+        device: device.name.clone(),
+        device_type: device.device_type.clone(),
+        channel: "next_event".to_string(),
+        value,
+        data: Some(data),
+    }
+}
+
+// Whether to poll a component, polls everything when the device did not report its components
+fn polls(components: Option<&ComponentListResult>, component: &str) -> bool {
+    components.is_none_or(|c| c.components.is_empty() || c.supports(component))
+}
+
+// Reads the components a device supports once and keeps them for the next polls
+async fn device_components<'a, H: ComponentListExt>(
+    device: &DeviceConfig,
+    handler: &H,
+    components: &'a mut HashMap<String, ComponentListResult>,
+) -> Option<&'a ComponentListResult> {
+    if !components.contains_key(&device.name) {
+        match handler.get_component_list().await {
+            Ok(list) => {
+                debug!(
+                    "{} supports components {:?}",
+                    device.name,
+                    list.components.keys().collect::<Vec<_>>()
+                );
+                components.insert(device.name.clone(), list);
+            }
+            Err(e) => debug!("get_component_list failed for {}: {}", device.name, e),
+        }
+    }
+    components.get(&device.name)
+}
+
+async fn collect_device_data(
+    device: &DeviceConfig,
+    components: &mut HashMap<String, ComponentListResult>,
+) -> Vec<Reading> {
     let mut readings = Vec::new();
-    let client = ApiClient::new(&device.tapo_email, &device.tapo_password);
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
 
     match device.device_type.as_str() {
         "P110" => {
             match client.p110(&device.ip).await {
                 Ok(plug) => {
-                    if let Ok(info) = plug.get_device_info().await {
+                    // Read everything the device supports in a single multipleRequest round trip
+                    let supported = device_components(device, &plug, components).await;
+                    let mut params = BatchReadParams::new().device_info();
+                    if polls(supported, "energy_monitoring") {
+                        params = params.current_power().energy_usage().emeter_data();
+                    }
+                    if polls(supported, "countdown") {
+                        params = params.countdown_rules();
+                    }
+                    if polls(supported, "schedule") {
+                        params = params.schedule_rules().next_event();
+                    }
+                    let batch = match plug.get_batch(params).await {
+                        Ok(batch) => batch,
+                        Err(e) => {
+                            log_connect_error(device, &e);
+                            return readings;
+                        }
+                    };
+
+                    if let Some(Ok(info)) = batch.device_info {
                         readings.push(Reading {
                             device: device.name.clone(),
                             device_type: device.device_type.clone(),
@@ -231,10 +794,39 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                             value: Some(info.rssi as f64),
                             data: None,
                         });
+                        // Protection statuses (0 = normal, 1 = tripped), raw status in data
+                        let statuses = [
+                            (
+                                "overcurrent_status",
+                                info.overcurrent_status == OvercurrentStatus::Normal,
+                                serde_json::to_value(&info.overcurrent_status),
+                            ),
+                            (
+                                "overheat_status",
+                                info.overheat_status
+                                    .as_ref()
+                                    .is_none_or(|s| *s == OverheatStatus::Normal),
+                                serde_json::to_value(&info.overheat_status),
+                            ),
+                            (
+                                "power_protection_status",
+                                info.power_protection_status == PowerProtectionStatus::Normal,
+                                serde_json::to_value(&info.power_protection_status),
+                            ),
+                        ];
+                        for (channel, normal, status) in statuses {
+                            readings.push(Reading {
+                                device: device.name.clone(),
+                                device_type: device.device_type.clone(),
+                                channel: channel.to_string(),
+                                value: Some(if normal { 0.0 } else { 1.0 }),
+                                data: status.ok(),
+                            });
+                        }
                     }
 
                     // Current power in watts (API returns milliwatts)
-                    if let Ok(energy) = plug.get_current_power().await {
+                    if let Some(Ok(energy)) = batch.current_power {
                         readings.push(Reading {
                             device: device.name.clone(),
                             device_type: device.device_type.clone(),
@@ -244,7 +836,29 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                         });
                     }
 
-                    if let Ok(usage) = plug.get_energy_usage().await {
+                    // Voltage in volts and current in amps (API returns mV and mA)
+                    match batch.emeter_data {
+                        Some(Ok(emeter)) => {
+                            readings.push(Reading {
+                                device: device.name.clone(),
+                                device_type: device.device_type.clone(),
+                                channel: "voltage".to_string(),
+                                value: Some(emeter.voltage_mv as f64 / 1000.0),
+                                data: None,
+                            });
+                            readings.push(Reading {
+                                device: device.name.clone(),
+                                device_type: device.device_type.clone(),
+                                channel: "current".to_string(),
+                                value: Some(emeter.current_ma as f64 / 1000.0),
+                                data: None,
+                            });
+                        }
+                        Some(Err(e)) => debug!("get_emeter_data failed for {}: {}", device.name, e),
+                        None => {}
+                    }
+
+                    if let Some(Ok(usage)) = batch.energy_usage {
                         // Today's energy in Wh
                         readings.push(Reading {
                             device: device.name.clone(),
@@ -280,12 +894,12 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                     }
 
                     // Countdown timer - return full data or null if none
-                    match plug.get_countdown_rules().await {
-                        Ok(countdown) => {
+                    match batch.countdown_rules {
+                        Some(Ok(countdown)) => {
                             let active = countdown.rules.iter().find(|r| r.enable);
                             readings.push(Reading {
                                 device: device.name.clone(),
-                            device_type: device.device_type.clone(),
+                                device_type: device.device_type.clone(),
                                 channel: "countdown".to_string(),
                                 value: None,
                                 data: Some(if let Some(rule) = active {
@@ -300,29 +914,45 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                                 }),
                             });
                         }
-                        Err(e) => debug!("get_countdown_rules failed for {}: {}", device.name, e),
+                        Some(Err(e)) => {
+                            debug!("get_countdown_rules failed for {}: {}", device.name, e)
+                        }
+                        None => {}
                     }
 
                     // Schedule rules - return full schedule list
-                    match plug.get_schedule_rules().await {
-                        Ok(schedules) => {
+                    match batch.schedule_rules {
+                        Some(Ok(schedules)) => {
                             readings.push(Reading {
                                 device: device.name.clone(),
-                            device_type: device.device_type.clone(),
+                                device_type: device.device_type.clone(),
                                 channel: "schedules".to_string(),
                                 value: None,
-                                data: Some(serde_json::to_value(&schedules.rules).unwrap_or_default()),
+                                data: Some(
+                                    serde_json::to_value(&schedules.rules).unwrap_or_default(),
+                                ),
                             });
                         }
-                        Err(e) => debug!("get_schedule_rules failed for {}: {}", device.name, e),
+                        Some(Err(e)) => {
+                            debug!("get_schedule_rules failed for {}: {}", device.name, e)
+                        }
+                        None => {}
+                    }
+
+                    // Next scheduled switch
+                    match batch.next_event {
+                        Some(Ok(event)) => readings.push(next_event_reading(device, &event)),
+                        Some(Err(e)) => debug!("get_next_event failed for {}: {}", device.name, e),
+                        None => {}
                     }
                 }
-                Err(e) => error!("Failed to connect to P110 {}: {}", device.name, e),
+                Err(e) => log_connect_error(device, &e),
             }
         }
         "P100" | "P105" => {
             match client.p100(&device.ip).await {
                 Ok(plug) => {
+                    let supported = device_components(device, &plug, components).await;
                     if let Ok(info) = plug.get_device_info().await {
                         readings.push(Reading {
                             device: device.name.clone(),
@@ -356,41 +986,55 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                             data: None,
                         });
                     }
-                    
+
                     // Countdown rules
-                    match plug.get_countdown_rules().await {
-                        Ok(countdown) => {
-                            let active = countdown.rules.iter().find(|r| r.enable);
-                            readings.push(Reading {
-                                device: device.name.clone(),
-                                device_type: device.device_type.clone(),
-                                channel: "countdown".to_string(),
-                                value: None,
-                                data: Some(if let Some(rule) = active {
-                                    serde_json::json!({
-                                        "remain": rule.remain,
-                                        "action": if rule.desired_states.as_ref().and_then(|s| s.on).unwrap_or(false) { "on" } else { "off" }
-                                    })
-                                } else {
-                                    serde_json::Value::Null
-                                }),
-                            });
+                    if polls(supported, "countdown") {
+                        match plug.get_countdown_rules().await {
+                            Ok(countdown) => {
+                                let active = countdown.rules.iter().find(|r| r.enable);
+                                readings.push(Reading {
+                                    device: device.name.clone(),
+                                    device_type: device.device_type.clone(),
+                                    channel: "countdown".to_string(),
+                                    value: None,
+                                    data: Some(if let Some(rule) = active {
+                                        serde_json::json!({
+                                            "remain": rule.remain,
+                                            "action": if rule.desired_states.as_ref().and_then(|s| s.on).unwrap_or(false) { "on" } else { "off" }
+                                        })
+                                    } else {
+                                        serde_json::Value::Null
+                                    }),
+                                });
+                            }
+                            Err(e) => {
+                                debug!("get_countdown_rules failed for {}: {}", device.name, e)
+                            }
                         }
-                        Err(e) => debug!("get_countdown_rules failed for {}: {}", device.name, e),
                     }
 
-                    // Schedule rules
-                    match plug.get_schedule_rules().await {
-                        Ok(schedules) => {
-                            readings.push(Reading {
-                                device: device.name.clone(),
-                                device_type: device.device_type.clone(),
-                                channel: "schedules".to_string(),
-                                value: None,
-                                data: Some(serde_json::to_value(&schedules.rules).unwrap_or_default()),
-                            });
+                    // Schedule rules and next scheduled switch
+                    if polls(supported, "schedule") {
+                        match plug.get_schedule_rules().await {
+                            Ok(schedules) => {
+                                readings.push(Reading {
+                                    device: device.name.clone(),
+                                    device_type: device.device_type.clone(),
+                                    channel: "schedules".to_string(),
+                                    value: None,
+                                    data: Some(
+                                        serde_json::to_value(&schedules.rules).unwrap_or_default(),
+                                    ),
+                                });
+                            }
+                            Err(e) => {
+                                debug!("get_schedule_rules failed for {}: {}", device.name, e)
+                            }
+                        }
+                        match plug.get_next_event().await {
+                            Ok(event) => readings.push(next_event_reading(device, &event)),
+                            Err(e) => debug!("get_next_event failed for {}: {}", device.name, e),
                         }
-                        Err(e) => debug!("get_schedule_rules failed for {}: {}", device.name, e),
                     }
 
                     match plug.get_device_usage().await {
@@ -398,9 +1042,71 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
                         Err(e) => warn!("Failed to get P100 usage for {}: {}", device.name, e),
                     }
                 }
-                Err(e) => error!("Failed to connect to P100 {}: {}", device.name, e),
+                Err(e) => log_connect_error(device, &e),
+            }
+        }
+        "H100" => {
+            match hub_handler(device).await {
+                Ok(hub) => match hub.get_device_info().await {
+                    Ok(info) => {
+                        // Siren playing (1) or silent (0), with what set it off
+                        readings.push(Reading {
+                            device: device.name.clone(),
+                            device_type: device.device_type.clone(),
+                            channel: "alarm".to_string(),
+                            value: Some(if info.in_alarm { 1.0 } else { 0.0 }),
+                            data: info
+                                .in_alarm
+                                .then(|| serde_json::json!({ "source": info.in_alarm_source })),
+                        });
+                        readings.push(Reading {
+                            device: device.name.clone(),
+                            device_type: device.device_type.clone(),
+                            channel: "signal_level".to_string(),
+                            value: Some(info.signal_level as f64),
+                            data: None,
+                        });
+                        readings.push(Reading {
+                            device: device.name.clone(),
+                            device_type: device.device_type.clone(),
+                            channel: "rssi".to_string(),
+                            value: Some(info.rssi as f64),
+                            data: None,
+                        });
+                    }
+                    Err(e) => error!("Failed to get H100 info for {}: {}", device.name, e),
+                },
+                Err(e) => log_connect_error(device, &e),
             }
         }
+        "KE100" => match find_valve(device).await {
+            Ok((_, info)) => readings.extend(valve_readings(device, &info)),
+            Err(e) => log_connect_error(device, &e),
+        },
+        // Events are read by watch_triggers, only the battery and hub link are polled
+        "S200B" | "T100" => match find_hub_child(device).await {
+            Ok((_, child)) => {
+                let (low_battery, online) = match &child {
+                    ChildDeviceHubResult::S200B(info) => {
+                        (info.at_low_battery, info.status == Status::Online)
+                    }
+                    ChildDeviceHubResult::T100(info) => {
+                        (info.at_low_battery, info.status == Status::Online)
+                    }
+                    _ => (false, false),
+                };
+                for (channel, on) in [("low_battery", low_battery), ("connected", online)] {
+                    readings.push(Reading {
+                        device: device.name.clone(),
+                        device_type: device.device_type.clone(),
+                        channel: channel.to_string(),
+                        value: Some(if on { 1.0 } else { 0.0 }),
+                        data: None,
+                    });
+                }
+            }
+            Err(e) => log_connect_error(device, &e),
+        },
         _ => {
             warn!("Unknown device type: {}", device.device_type);
         }
@@ -409,10 +1115,134 @@ async fn collect_device_data(device: &DeviceConfig) -> Vec<Reading> {
     readings
 }
 
+fn is_hub_child(device: &DeviceConfig) -> bool {
+    matches!(device.device_type.as_str(), "KE100" | "S200B" | "T100")
+}
+
+// One handler per hub (address and account), kept across polls and commands so that hub children
+// are found through its child index instead of listing every child each time
+// Each hub logs in once in its own cell, so an unreachable hub only holds up callers for that hub.
+type Hubs = Mutex<HashMap<(String, String), Arc<OnceCell<Arc<HubHandler>>>>>;
+static HUBS: LazyLock<Hubs> = LazyLock::new(Default::default);
+
+async fn hub_handler(device: &DeviceConfig) -> Result<Arc<HubHandler>, tapo::Error> {
+    let cell = HUBS
+        .lock()
+        .unwrap()
+        .entry(hub_key(device))
+        .or_default()
+        .clone();
+
+    cell.get_or_try_init(|| async {
+        let hub = ApiClient::new(&device.tapo_email, &device.tapo_password)
+            .with_retry_policy(RetryPolicy::default())
+            .h100(&device.ip)
+            .await?;
+        Ok(Arc::new(hub))
+    })
+    .await
+    .cloned()
+}
+
+fn hub_key(device: &DeviceConfig) -> (String, String) {
+    (device.ip.clone(), device.tapo_email.clone())
+}
+
+// child holds a device id (40 hex digits) or a nickname, the config name when left out
+fn hub_device(device: &DeviceConfig) -> HubDevice {
+    let child = device.child.as_deref().unwrap_or(&device.name);
+    if child.len() == 40 && child.chars().all(|c| c.is_ascii_hexdigit()) {
+        HubDevice::ByDeviceId(child.to_string())
+    } else {
+        HubDevice::ByNickname(child.to_string())
+    }
+}
+
+// A hub child is reached through its hub at ip. Its info is read from the child itself,
+// the hub only lists its children again when one is not in its index.
+async fn find_hub_child(
+    device: &DeviceConfig,
+) -> Result<(Arc<HubHandler>, ChildDeviceHubResult), tapo::Error> {
+    let hub = hub_handler(device).await?;
+    let child = async {
+        let identifier = hub_device(device);
+        Ok(match device.device_type.as_str() {
+            "KE100" => ChildDeviceHubResult::KE100(Box::new(
+                hub.ke100(identifier).await?.get_device_info().await?,
+            )),
+            "S200B" => ChildDeviceHubResult::S200B(Box::new(
+                hub.s200b(identifier).await?.get_device_info().await?,
+            )),
+            "T100" => ChildDeviceHubResult::T100(Box::new(
+                hub.t100(identifier).await?.get_device_info().await?,
+            )),
+            _ => return Err(tapo::Error::DeviceNotFound),
+        })
+    }
+    .await;
+
+    // Log in again on the next lookup, e.g. after the hub restarted
+    if child
+        .as_ref()
+        .is_err_and(|e| !matches!(e, tapo::Error::DeviceNotFound))
+    {
+        HUBS.lock().unwrap().remove(&hub_key(device));
+    }
+    Ok((hub, child?))
+}
+
+async fn find_valve(
+    device: &DeviceConfig,
+) -> Result<(Arc<HubHandler>, Box<KE100Result>), tapo::Error> {
+    match find_hub_child(device).await? {
+        (hub, ChildDeviceHubResult::KE100(info)) => Ok((hub, info)),
+        _ => Err(tapo::Error::DeviceNotFound),
+    }
+}
+
+async fn valve_handler(device: &DeviceConfig) -> Result<KE100Handler, tapo::Error> {
+    hub_handler(device).await?.ke100(hub_device(device)).await
+}
+
+fn valve_readings(device: &DeviceConfig, info: &KE100Result) -> Vec<Reading> {
+    let reading = |channel: &str, value: f64, data: Option<serde_json::Value>| Reading {
+        device: device.name.clone(),
+        device_type: device.device_type.clone(),
+        channel: channel.to_string(),
+        value: Some(value),
+        data,
+    };
+    let flag = |on: bool| if on { 1.0 } else { 0.0 };
+
+    let mut readings = vec![
+        reading("current_temperature", info.current_temperature as f64, None),
+        reading("target_temperature", info.target_temperature as f64, None),
+        // Valve opening to heat (1) or idle (0), with everything the valve reports it is doing
+        reading(
+            "heating",
+            flag(info.trv_states.iter().any(|s| s == "heating")),
+            Some(serde_json::json!(info.trv_states)),
+        ),
+        reading("frost_protection", flag(info.frost_protection_on), None),
+        reading("low_battery", flag(info.at_low_battery), None),
+        // Whether the valve is in touch with its hub
+        reading("connected", flag(info.status == Status::Online), None),
+        reading("signal_level", info.signal_level as f64, None),
+    ];
+    if let Some(battery) = info.battery_percentage {
+        readings.push(reading("battery", battery as f64, None));
+    }
+    readings
+}
+
 // Switch a device on or off
-async fn switch_device(device: &DeviceConfig, turn_on: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let client = ApiClient::new(&device.tapo_email, &device.tapo_password);
-    
+async fn switch_device(
+    device: &DeviceConfig,
+    turn_on: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+
     match device.device_type.as_str() {
         "P110" | "P115" => {
             let plug = client.p110(&device.ip).await?;
@@ -430,35 +1260,1067 @@ async fn switch_device(device: &DeviceConfig, turn_on: bool) -> Result<(), Box<d
                 plug.off().await?;
             }
         }
+        // The siren is the hub's output, sounded with the [devices.alarm] defaults
+        "H100" => {
+            if turn_on {
+                let alarm = &device.alarm;
+                return sound_alarm(
+                    device,
+                    alarm.ringtone.unwrap_or_default(),
+                    alarm.volume.unwrap_or_default(),
+                    alarm.duration(),
+                )
+                .await;
+            }
+            hub_handler(device).await?.stop_alarm().await?;
+        }
+        // Off holds the radiator in frost protection, on heats it to its target temperature again
+        "KE100" => {
+            valve_handler(device)
+                .await?
+                .set_frost_protection(!turn_on)
+                .await?;
+        }
         _ => {
             return Err(format!("Unknown device type: {}", device.device_type).into());
         }
     }
-    
-    info!("[Switch] Device {} turned {}", device.name, if turn_on { "ON" } else { "OFF" });
+
+    info!(
+        "[Switch] Device {} turned {}",
+        device.name,
+        if turn_on { "ON" } else { "OFF" }
+    );
     Ok(())
 }
 
-async fn run_agent(config: Config) -> Result<(), Box<dyn std::error::Error>> {
-    use tokio::sync::mpsc;
+// Heat a KE100 to this temperature (°C), within its min/max control temperatures
+async fn set_target_temperature(
+    device: &DeviceConfig,
+    celsius: u8,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    valve_handler(device)
+        .await?
+        .set_target_temperature(celsius, TemperatureUnitKE100::Celsius)
+        .await?;
+    info!(
+        "[Valve] {} target temperature set to {}°C",
+        device.name, celsius
+    );
+    Ok(())
+}
 
-    // Channel for readings from poller to sender
-    let (tx, mut rx) = mpsc::channel::<Vec<Reading>>(100);
+// Hold a KE100 at a frost-safe temperature instead of its target, or back to heating
+async fn set_frost_protection(
+    device: &DeviceConfig,
+    on: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    valve_handler(device)
+        .await?
+        .set_frost_protection(on)
+        .await?;
+    info!(
+        "[Valve] {} frost protection {}",
+        device.name,
+        if on { "on" } else { "off" }
+    );
+    Ok(())
+}
+
+// Ringtone, volume and duration of an "alarm" command, falling back to [devices.alarm] for the ones left out.
+// duration is in seconds, or "once" / "continuous"
+fn alarm_command(
+    cmd: &serde_json::Value,
+    defaults: &AlarmConfig,
+) -> Result<(AlarmRingtone, AlarmVolume, AlarmDuration), String> {
+    let ringtone = match cmd.get("ringtone") {
+        Some(v) => {
+            serde_json::from_value(v.clone()).map_err(|_| format!("unknown ringtone {}", v))?
+        }
+        None => defaults.ringtone.unwrap_or_default(),
+    };
+    let volume = match cmd.get("volume") {
+        Some(v) => {
+            serde_json::from_value(v.clone()).map_err(|_| format!("unknown volume {}", v))?
+        }
+        None => defaults.volume.unwrap_or_default(),
+    };
+    let duration = match cmd.get("duration") {
+        Some(v) if v.as_str() == Some("once") => AlarmDuration::Once,
+        Some(v) if v.as_str() == Some("continuous") => AlarmDuration::Continuous,
+        Some(v) => match v.as_u64() {
+            Some(secs) if secs > 0 => AlarmDuration::Seconds(secs.min(u32::MAX as u64) as u32),
+            _ => return Err(format!("invalid duration {}", v)),
+        },
+        None => defaults.duration(),
+    };
+    Ok((ringtone, volume, duration))
+}
+
+// Sound the siren of an H100, with the hub's default ringtone if it doesn't have the one asked for
+async fn sound_alarm(
+    device: &DeviceConfig,
+    ringtone: AlarmRingtone,
+    volume: AlarmVolume,
+    duration: AlarmDuration,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let hub = hub_handler(device).await?;
+
+    let mut ringtone = ringtone;
+    if ringtone != AlarmRingtone::Default {
+        let name = serde_json::to_value(ringtone)?;
+        match hub.get_supported_ringtone_list().await {
+            Ok(supported) if !supported.iter().any(|r| Some(r.as_str()) == name.as_str()) => {
+                warn!(
+                    "[Alarm] {} does not support ringtone {}, using its default (supported: {})",
+                    device.name,
+                    name,
+                    supported.join(", ")
+                );
+                ringtone = AlarmRingtone::Default;
+            }
+            Ok(_) => {}
+            Err(e) => debug!(
+                "get_supported_ringtone_list failed for {}: {}",
+                device.name, e
+            ),
+        }
+    }
+
+    hub.play_alarm(ringtone, volume, duration).await?;
+    info!(
+        "[Alarm] {} sounding {:?} at {:?} volume ({:?})",
+        device.name, ringtone, volume, duration
+    );
+    Ok(())
+}
+
+// Read back the settings of a device, put back the ones that differ from [devices.settings]
+// and report them as a "settings_drift" reading
+async fn reconcile_settings(device: &DeviceConfig, settings: &DeviceSettings) -> Option<Reading> {
+    match device_settings_drift(device, settings).await {
+        Ok(drift) => {
+            for d in &drift {
+                match &d.unsupported {
+                    Some(reason) => warn!(
+                        "[Settings] {} {} is {}, cannot set {}: {}",
+                        device.name, d.setting, d.found, d.desired, reason
+                    ),
+                    None => warn!(
+                        "[Settings] {} {} was {}, set back to {}",
+                        device.name, d.setting, d.found, d.desired
+                    ),
+                }
+            }
+            if drift.is_empty() {
+                debug!("[Settings] {} matches config", device.name);
+            }
+
+            Some(Reading {
+                device: device.name.clone(),
+                device_type: device.device_type.clone(),
+                channel: "settings_drift".to_string(),
+                value: Some(drift.len() as f64),
+                data: if drift.is_empty() {
+                    None
+                } else {
+                    serde_json::to_value(&drift).ok()
+                },
+            })
+        }
+        Err(e) => {
+            log_connect_error(device, &e);
+            None
+        }
+    }
+}
+
+async fn device_settings_drift(
+    device: &DeviceConfig,
+    settings: &DeviceSettings,
+) -> Result<Vec<SettingDrift>, tapo::Error> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+    let mut drift = Vec::new();
+
+    match device.device_type.as_str() {
+        "P110" | "P115" => {
+            let plug = client.p110(&device.ip).await?;
+            let info = plug.get_device_info().await?;
+
+            if let Some(desired) = settings.default_state {
+                let current = DefaultPowerState::from(&info.default_states);
+                if let Some(d) = setting_drift("default_state", current, desired) {
+                    plug.set_default_state(desired).await?;
+                    drift.push(d);
+                }
+            }
+            if let Some(watts) = settings.power_protection_watts {
+                drift.extend(reconcile_power_protection(device, &plug, watts).await?);
+            }
+            drift.extend(reconcile_common_settings(&plug, &info.nickname, settings).await?);
+        }
+        "P100" | "P105" => {
+            let plug = client.p100(&device.ip).await?;
+            let info = plug.get_device_info().await?;
+
+            if let Some(desired) = settings.default_state {
+                let current = DefaultPowerState::from(&info.default_states);
+                if let Some(d) = setting_drift("default_state", current, desired) {
+                    plug.set_default_state(desired).await?;
+                    drift.push(d);
+                }
+            }
+            if settings.power_protection_watts.is_some() {
+                warn!(
+                    "power_protection_watts is not supported for {} {}, ignoring",
+                    device.device_type, device.name
+                );
+            }
+            drift.extend(reconcile_common_settings(&plug, &info.nickname, settings).await?);
+        }
+        _ => {
+            warn!(
+                "[Settings] not supported for {} {}, ignoring",
+                device.device_type, device.name
+            );
+        }
+    }
+
+    Ok(drift)
+}
+
+// Settings every supported plug has: LED, auto-off, nickname and time zone
+async fn reconcile_common_settings<H>(
+    handler: &H,
+    nickname: &str,
+    settings: &DeviceSettings,
+) -> Result<Vec<SettingDrift>, tapo::Error>
+where
+    H: LedExt + AutoOffExt + DeviceInfoExt + DeviceTimeExt,
+{
+    let mut drift = Vec::new();
+
+    if let Some(desired) = settings.led {
+        let current = handler.get_led_info().await?.led_rule;
+        if let Some(d) = setting_drift("led", current, desired) {
+            handler.set_led_rule(desired).await?;
+            drift.push(d);
+        }
+    }
+
+    if let Some(desired) = settings.auto_off_min {
+        let current = handler.get_auto_off_config().await?;
+        if let Some(d) = auto_off_drift(current.enable, current.delay_min, desired) {
+            // Keep the device's delay when disabling, so re-enabling it in the app restores it
+            handler
+                .set_auto_off(
+                    desired > 0,
+                    if desired > 0 {
+                        desired
+                    } else {
+                        current.delay_min
+                    },
+                )
+                .await?;
+            drift.push(d);
+        }
+    }
+
+    if let Some(desired) = &settings.nickname {
+        if nickname != desired {
+            handler.set_nickname(desired).await?;
+            drift.push(SettingDrift {
+                setting: "nickname",
+                found: nickname.to_string(),
+                desired: desired.clone(),
+                unsupported: None,
+            });
+        }
+    }
+
+    if let Some(desired) = &settings.time_zone {
+        let current = handler.get_device_time().await?;
+        if let Some(d) = time_zone_drift(&current.region, current.time_diff, desired) {
+            // Only the time zone changes, the clock itself is checked by check_device_clock
+            handler
+                .set_device_time(current.timestamp, desired.utc_offset_min, &desired.region)
+                .await?;
+            drift.push(d);
+        }
+    }
+
+    Ok(drift)
+}
+
+// Make sure an energy plug turns itself off above the configured load
+async fn reconcile_power_protection(
+    device: &DeviceConfig,
+    plug: &PlugEnergyMonitoringHandler,
+    watts: u16,
+) -> Result<Option<SettingDrift>, tapo::Error> {
+    let current = plug.get_power_protection().await?;
+    let Some(drift) = power_protection_drift(current.enabled, current.protection_power, watts)
+    else {
+        return Ok(None);
+    };
+    let enabled = watts > 0;
+
+    if enabled {
+        let max_power = plug.get_max_power().await?.max_power;
+        if watts > max_power {
+            return Ok(Some(drift.unsupported(format!(
+                "{} is rated for {} W",
+                device.name, max_power
+            ))));
+        }
+    }
+
+    plug.set_power_protection(
+        enabled,
+        if enabled {
+            watts
+        } else {
+            current.protection_power
+        },
+    )
+    .await?;
+
+    Ok(Some(drift))
+}
+
+// A setting read back from a device compared with [devices.settings], None when they match
+fn setting_drift<T: PartialEq + std::fmt::Debug>(
+    setting: &'static str,
+    found: T,
+    desired: T,
+) -> Option<SettingDrift> {
+    (found != desired).then(|| SettingDrift {
+        setting,
+        found: format!("{:?}", found),
+        desired: format!("{:?}", desired),
+        unsupported: None,
+    })
+}
+
+// Disabled auto-off counts as 0 minutes, whatever delay the device keeps for it
+fn auto_off_drift(enable: bool, delay_min: u64, desired: u64) -> Option<SettingDrift> {
+    let found = if enable { delay_min } else { 0 };
+    (found != desired).then(|| SettingDrift {
+        setting: "auto_off_min",
+        found: found.to_string(),
+        desired: desired.to_string(),
+        unsupported: None,
+    })
+}
+
+fn time_zone_drift(
+    region: &str,
+    time_diff: i32,
+    desired: &TimeZoneSetting,
+) -> Option<SettingDrift> {
+    (region != desired.region || time_diff != desired.utc_offset_min).then(|| SettingDrift {
+        setting: "time_zone",
+        found: format!("{} ({:+} min)", region, time_diff),
+        desired: format!("{} ({:+} min)", desired.region, desired.utc_offset_min),
+        unsupported: None,
+    })
+}
+
+// Disabled protection counts as 0 W, whatever threshold the device keeps for it
+fn power_protection_drift(
+    enabled: bool,
+    protection_power: u16,
+    watts: u16,
+) -> Option<SettingDrift> {
+    let found = if enabled { protection_power } else { 0 };
+    (found != watts).then(|| SettingDrift {
+        setting: "power_protection_watts",
+        found: found.to_string(),
+        desired: watts.to_string(),
+        unsupported: None,
+    })
+}
+
+// Turn protection status readings into alert readings when they leave the normal state.
+// `tripped` remembers which statuses already alerted so each trip is reported once.
+fn protection_alerts(
+    readings: &[Reading],
+    tripped: &mut HashMap<(String, String), bool>,
+) -> Vec<Reading> {
+    let mut alerts = Vec::new();
+
+    for reading in readings {
+        if !matches!(
+            reading.channel.as_str(),
+            "overcurrent_status" | "overheat_status" | "power_protection_status"
+        ) {
+            continue;
+        }
+
+        let is_tripped = reading.value.unwrap_or(0.0) > 0.0;
+        let key = (reading.device.clone(), reading.channel.clone());
+        let was_tripped = tripped.insert(key, is_tripped).unwrap_or(false);
+
+        if is_tripped && !was_tripped {
+            warn!(
+                "[Alert] {} {} is {}",
+                reading.device,
+                reading.channel,
+                reading.data.clone().unwrap_or_default()
+            );
+            alerts.push(Reading {
+                device: reading.device.clone(),
+                device_type: reading.device_type.clone(),
+                channel: "alert".to_string(),
+                value: None,
+                data: Some(serde_json::json!({
+                    "source": reading.channel,
+                    "status": reading.data,
+                })),
+            });
+        } else if !is_tripped && was_tripped {
+            info!(
+                "[Alert] {} {} back to normal",
+                reading.device, reading.channel
+            );
+        }
+    }
+
+    alerts
+}
+
+// Set when the status LED of a plug is lit
+async fn set_led(
+    device: &DeviceConfig,
+    rule: LedRule,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+
+    match device.device_type.as_str() {
+        "P110" | "P115" => client.p110(&device.ip).await?.set_led_rule(rule).await?,
+        "P100" | "P105" => client.p100(&device.ip).await?.set_led_rule(rule).await?,
+        _ => {
+            return Err(format!("Unknown device type: {}", device.device_type).into());
+        }
+    }
+
+    info!("[LED] Device {} LED set to {:?}", device.name, rule);
+    Ok(())
+}
+
+// Turn a plug off automatically after it has been on for delay_min, 0 disables it
+async fn set_auto_off(
+    device: &DeviceConfig,
+    delay_min: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+    let enable = delay_min > 0;
+
+    match device.device_type.as_str() {
+        "P110" | "P115" => {
+            let plug = client.p110(&device.ip).await?;
+            let delay_min = if enable {
+                delay_min
+            } else {
+                plug.get_auto_off_config().await?.delay_min
+            };
+            plug.set_auto_off(enable, delay_min).await?;
+        }
+        "P100" | "P105" => {
+            let plug = client.p100(&device.ip).await?;
+            let delay_min = if enable {
+                delay_min
+            } else {
+                plug.get_auto_off_config().await?.delay_min
+            };
+            plug.set_auto_off(enable, delay_min).await?;
+        }
+        _ => {
+            return Err(format!("Unknown device type: {}", device.device_type).into());
+        }
+    }
+
+    if enable {
+        info!(
+            "[AutoOff] Device {} turns off after {} min",
+            device.name, delay_min
+        );
+    } else {
+        info!("[AutoOff] Device {} auto-off disabled", device.name);
+    }
+    Ok(())
+}
+
+// Compare a device clock with ours and set it if it drifted, e.g. after an outage without internet.
+// Returns the drift in seconds before any correction (positive = device ahead).
+async fn sync_device_clock(
+    device: &DeviceConfig,
+    handler: &impl DeviceTimeExt,
+) -> Result<i64, tapo::Error> {
+    let time = handler.get_device_time().await?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    let drift = time.timestamp - now;
+
+    if drift.abs() > MAX_CLOCK_DRIFT_SECS {
+        // Keep the device's own time zone, only the clock is wrong
+        handler
+            .set_device_time(now, time.time_diff, &time.region)
+            .await?;
+        warn!(
+            "[Clock] {} was {}s off, clock corrected",
+            device.name, drift
+        );
+    } else {
+        debug!("[Clock] {} is {}s off", device.name, drift);
+    }
+
+    Ok(drift)
+}
+
+async fn check_device_clock(device: &DeviceConfig) -> Option<Reading> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+
+    let result = match device.device_type.as_str() {
+        "P110" | "P115" => match client.p110(&device.ip).await {
+            Ok(plug) => sync_device_clock(device, &plug).await,
+            Err(e) => Err(e),
+        },
+        "P100" | "P105" => match client.p100(&device.ip).await {
+            Ok(plug) => sync_device_clock(device, &plug).await,
+            Err(e) => Err(e),
+        },
+        "H100" => match hub_handler(device).await {
+            Ok(hub) => sync_device_clock(device, hub.as_ref()).await,
+            Err(e) => Err(e),
+        },
+        _ => return None,
+    };
+
+    match result {
+        Ok(drift) => Some(Reading {
+            device: device.name.clone(),
+            device_type: device.device_type.clone(),
+            channel: "clock_drift".to_string(),
+            value: Some(drift as f64),
+            data: None,
+        }),
+        Err(e) => {
+            log_connect_error(device, &e);
+            None
+        }
+    }
+}
+
+// Current IP address of each device by name, when it differs from the config after a DHCP renewal
+type Addresses = Arc<RwLock<HashMap<String, String>>>;
+
+fn with_current_address(device: &DeviceConfig, addresses: &Addresses) -> DeviceConfig {
+    let mut device = device.clone();
+    if let Some(ip) = addresses.read().unwrap().get(&device.name) {
+        device.ip = ip.clone();
+    }
+    device
+}
+
+// MAC addresses are announced as AA-BB-CC-DD-EE-FF, accept any separator and case in the config
+fn same_mac(a: &str, b: &str) -> bool {
+    let normalize = |mac: &str| {
+        mac.chars()
+            .filter(|c| c.is_ascii_hexdigit())
+            .collect::<String>()
+            .to_uppercase()
+    };
+    normalize(a) == normalize(b)
+}
+
+// Turn a discovery event into readings for the configured device it belongs to, and follow its address
+fn discovery_event_readings(
+    devices: &[DeviceConfig],
+    addresses: &Addresses,
+    event: DiscoveryEvent,
+) -> Vec<Reading> {
+    let (announcement, known_ip) = match &event {
+        DiscoveryEvent::Appeared(announcement) | DiscoveryEvent::Disappeared(announcement) => {
+            (announcement, announcement.ip)
+        }
+        DiscoveryEvent::AddressChanged {
+            announcement,
+            previous_ip,
+        } => (announcement, *previous_ip),
+    };
+    // Devices without a MAC in the config are recognized by their address.
+    // Hub children (KE100) share the address of their hub and follow it too.
+    let matching: Vec<&DeviceConfig> = devices
+        .iter()
+        .filter(|d| match &d.mac {
+            Some(mac) => same_mac(mac, &announcement.mac),
+            None => with_current_address(d, addresses).ip == known_ip.to_string(),
+        })
+        .collect();
+    let mut readings = Vec::new();
+
+    for device in matching {
+        let reading =
+            |channel: &str, value: Option<f64>, data: Option<serde_json::Value>| Reading {
+                device: device.name.clone(),
+                device_type: device.device_type.clone(),
+                channel: channel.to_string(),
+                value,
+                data,
+            };
+
+        match event {
+            DiscoveryEvent::Appeared(_) | DiscoveryEvent::AddressChanged { .. } => {
+                let ip = announcement.ip.to_string();
+                let previous_ip = with_current_address(device, addresses).ip;
+                if previous_ip != ip {
+                    info!(
+                        "[Discovery] {} moved from {} to {}",
+                        device.name, previous_ip, ip
+                    );
+                    addresses
+                        .write()
+                        .unwrap()
+                        .insert(device.name.clone(), ip.clone());
+                    readings.push(reading(
+                        "address",
+                        None,
+                        Some(serde_json::json!({ "ip": ip, "previous_ip": previous_ip })),
+                    ));
+                }
+                if matches!(event, DiscoveryEvent::Appeared(_)) {
+                    readings.push(reading("online", Some(1.0), None));
+                }
+            }
+            DiscoveryEvent::Disappeared(_) => {
+                warn!(
+                    "[Alert] {} stopped answering discovery at {}",
+                    device.name, announcement.ip
+                );
+                readings.push(reading("online", Some(0.0), None));
+                readings.push(reading(
+                    "alert",
+                    None,
+                    Some(serde_json::json!({ "source": "online", "status": "disappeared" })),
+                ));
+            }
+        }
+    }
+
+    readings
+}
+
+// Plugs switched off by a pause trigger, and until when. set_state commands don't switch them on before that.
+type Paused = Arc<RwLock<PauseList>>;
+
+fn is_paused(device: &DeviceConfig, paused: &Paused) -> bool {
+    paused
+        .read()
+        .unwrap()
+        .is_paused(&device.name, Instant::now())
+}
+
+// When the pause of each paused plug ends
+#[derive(Debug, Default)]
+struct PauseList {
+    until: HashMap<String, Instant>,
+}
+
+impl PauseList {
+    fn is_paused(&self, name: &str, now: Instant) -> bool {
+        self.until.get(name).is_some_and(|until| *until > now)
+    }
+
+    // Overlapping pauses hold the plug off until the last one ends
+    fn pause(&mut self, name: &str, until: Instant) {
+        let end = self.until.entry(name.to_string()).or_insert(until);
+        *end = (*end).max(until);
+    }
+
+    // Ends the pause that lasts until `until`, false when a longer pause still holds the plug off
+    fn end(&mut self, name: &str, until: Instant) -> bool {
+        if self.until.get(name).is_some_and(|end| *end > until) {
+            return false;
+        }
+        self.until.remove(name);
+        true
+    }
+}
+
+// Follows a trigger log by event id, so that each event is acted on once
+#[derive(Debug, Default)]
+struct TriggerLogCursor {
+    last_id: Option<u64>,
+}
+
+impl TriggerLogCursor {
+    // The events logged since the last call, oldest first. The first call only records where the log
+    // stands, events logged before the agent started are not acted on.
+    fn new_events<'a>(&mut self, events: &'a [TriggerEvent]) -> Vec<&'a TriggerEvent> {
+        let newest = events.first().map(|e| e.id);
+        let Some(seen) = self.last_id else {
+            self.last_id = Some(newest.unwrap_or_default());
+            return Vec::new();
+        };
+
+        // The hub log may have been cleared, follow its ids either way
+        self.last_id = newest.or(self.last_id);
+        events.iter().rev().filter(|e| e.id > seen).collect()
+    }
+}
+
+// The button or motion sensor a trigger log is read from, kept connected between polls
+enum TriggerSource {
+    Button(S200BHandler),
+    Motion(T100Handler),
+}
+
+struct TriggerEvent {
+    id: u64,
+    timestamp: u64,
+    event: &'static str,
+    degrees: Option<i16>, // Rotations only, positive clockwise
+}
+
+impl TriggerSource {
+    async fn connect(device: &DeviceConfig) -> Result<Self, tapo::Error> {
+        let hub = hub_handler(device).await?;
+        match device.device_type.as_str() {
+            "S200B" => Ok(Self::Button(hub.s200b(hub_device(device)).await?)),
+            "T100" => Ok(Self::Motion(hub.t100(hub_device(device)).await?)),
+            _ => Err(tapo::Error::DeviceNotFound),
+        }
+    }
+
+    // Newest first, as the hub logs them
+    async fn events(&self) -> Result<Vec<TriggerEvent>, tapo::Error> {
+        let event = |id, timestamp, event, degrees| TriggerEvent {
+            id,
+            timestamp,
+            event,
+            degrees,
+        };
+        Ok(match self {
+            Self::Button(button) => button
+                .get_trigger_logs(TRIGGER_LOG_PAGE_SIZE, 0)
+                .await?
+                .logs
+                .into_iter()
+                .map(|log| match log {
+                    S200BLog::Rotation {
+                        id,
+                        timestamp,
+                        params,
+                    } => event(id, timestamp, "rotation", Some(params.rotation_degrees)),
+                    S200BLog::SingleClick { id, timestamp } => {
+                        event(id, timestamp, "single_click", None)
+                    }
+                    S200BLog::DoubleClick { id, timestamp } => {
+                        event(id, timestamp, "double_click", None)
+                    }
+                    S200BLog::LowBattery { id, timestamp } => {
+                        event(id, timestamp, "low_battery", None)
+                    }
+                })
+                .collect(),
+            Self::Motion(sensor) => sensor
+                .get_trigger_logs(TRIGGER_LOG_PAGE_SIZE, 0)
+                .await?
+                .logs
+                .into_iter()
+                .map(|log| match log {
+                    T100Log::Motion { id, timestamp } => event(id, timestamp, "motion", None),
+                })
+                .collect(),
+        })
+    }
+}
+
+// Read the trigger log of a button or motion sensor, run the matching [[devices.triggers]] and forward the events.
+// Runs apart from the server connection, so buttons keep working while the server is unreachable.
+async fn watch_triggers(
+    device: DeviceConfig,
+    devices: Vec<DeviceConfig>,
+    addresses: Addresses,
+    paused: Paused,
+    tx: tokio::sync::mpsc::Sender<Vec<Reading>>,
+) {
+    let mut poll = interval(TRIGGER_POLL_INTERVAL);
+    poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut source: Option<TriggerSource> = None;
+    let mut cursor = TriggerLogCursor::default();
+
+    loop {
+        poll.tick().await;
+
+        let Some(connected) = &source else {
+            let current = with_current_address(&device, &addresses);
+            match TriggerSource::connect(&current).await {
+                Ok(connected) => source = Some(connected),
+                Err(e) => {
+                    log_connect_error(&current, &e);
+                    sleep(Duration::from_secs(30)).await;
+                }
+            }
+            continue;
+        };
+
+        let events = match connected.events().await {
+            Ok(events) => events,
+            Err(e) => {
+                debug!("get_trigger_logs failed for {}: {}", device.name, e);
+                source = None;
+                continue;
+            }
+        };
+
+        let mut readings = Vec::new();
+        for event in cursor.new_events(&events) {
+            info!("[Trigger] {} {}", device.name, event.event);
+            readings.push(Reading {
+                device: device.name.clone(),
+                device_type: device.device_type.clone(),
+                channel: event.event.to_string(),
+                value: Some(event.degrees.map(f64::from).unwrap_or(1.0)),
+                data: Some(serde_json::json!({ "id": event.id, "timestamp": event.timestamp })),
+            });
+
+            for trigger in device.triggers.iter().filter(|t| t.event == event.event) {
+                let targets = trigger
+                    .targets
+                    .iter()
+                    .filter_map(|name| devices.iter().find(|d| &d.name == name))
+                    .map(|d| with_current_address(d, &addresses))
+                    .collect();
+                tokio::spawn(run_trigger(
+                    device.name.clone(),
+                    trigger.clone(),
+                    targets,
+                    paused.clone(),
+                ));
+            }
+        }
+
+        if !readings.is_empty() {
+            let _ = tx.try_send(readings);
+        }
+    }
+}
+
+async fn device_is_on(
+    device: &DeviceConfig,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+
+    match device.device_type.as_str() {
+        "P110" | "P115" => Ok(client
+            .p110(&device.ip)
+            .await?
+            .get_device_info()
+            .await?
+            .device_on),
+        "P100" | "P105" => Ok(client
+            .p100(&device.ip)
+            .await?
+            .get_device_info()
+            .await?
+            .device_on),
+        _ => Err(format!("Unknown device type: {}", device.device_type).into()),
+    }
+}
+
+async fn run_trigger(
+    source: String,
+    trigger: TriggerConfig,
+    targets: Vec<DeviceConfig>,
+    paused: Paused,
+) {
+    info!(
+        "[Trigger] {} {}: {:?} {}",
+        source,
+        trigger.event,
+        trigger.action,
+        trigger.targets.join(", ")
+    );
+
+    match trigger.action {
+        TriggerAction::On | TriggerAction::Off => {
+            for target in &targets {
+                if let Err(e) = switch_device(target, trigger.action == TriggerAction::On).await {
+                    error!("[Trigger] Failed to switch {}: {}", target.name, e);
+                }
+            }
+        }
+        TriggerAction::Toggle => {
+            for target in &targets {
+                let result = match device_is_on(target).await {
+                    Ok(on) => switch_device(target, !on).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    error!("[Trigger] Failed to toggle {}: {}", target.name, e);
+                }
+            }
+        }
+        TriggerAction::Pause => {
+            let pause = Duration::from_secs(trigger.minutes.unwrap_or(DEFAULT_PAUSE_MIN) * 60);
+            let until = Instant::now() + pause;
+            let mut resume = Vec::new();
+
+            for target in targets {
+                // Pausing again extends the pause of targets that are already paused
+                let was_paused = is_paused(&target, &paused);
+                let on = match device_is_on(&target).await {
+                    Ok(on) => on,
+                    Err(e) => {
+                        error!("[Trigger] Failed to pause {}: {}", target.name, e);
+                        continue;
+                    }
+                };
+                if !on && !was_paused {
+                    continue;
+                }
+
+                paused.write().unwrap().pause(&target.name, until);
+                if on {
+                    if let Err(e) = switch_device(&target, false).await {
+                        error!("[Trigger] Failed to pause {}: {}", target.name, e);
+                        paused.write().unwrap().end(&target.name, until);
+                        continue;
+                    }
+                }
+                resume.push(target);
+            }
+
+            sleep(pause).await;
+
+            for target in resume {
+                // Paused again in the meantime, the later pause switches it back on
+                if !paused.write().unwrap().end(&target.name, until) {
+                    continue;
+                }
+                if let Err(e) = switch_device(&target, true).await {
+                    error!("[Trigger] Failed to resume {}: {}", target.name, e);
+                }
+            }
+        }
+    }
+}
+
+async fn run_agent(config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    use tokio::sync::mpsc;
+
+    // Channel for readings from poller to sender
+    let (tx, mut rx) = mpsc::channel::<Vec<Reading>>(100);
 
     // Spawn device polling task - runs continuously regardless of connection
     let poll_interval_secs = config.poll_interval_secs;
-    let devices = config.devices.clone();
+    let configured_devices = config.devices.clone();
+    let addresses: Addresses = Arc::default();
+
+    // Follow devices across DHCP renewals and report the ones that drop off Wi-Fi
+    if !config.watch_broadcast.is_empty() {
+        let watcher = DiscoveryWatcher::new(config.watch_broadcast.clone())
+            .with_interval(Duration::from_secs(poll_interval_secs.max(30)));
+        let devices = config.devices.clone();
+        let addresses = addresses.clone();
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let mut events = match watcher.start().await {
+                Ok(events) => events,
+                Err(e) => {
+                    error!("Failed to start discovery watcher: {}", e);
+                    return;
+                }
+            };
+            while let Some(event) = events.next().await {
+                match event {
+                    Ok(event) => {
+                        let readings = discovery_event_readings(&devices, &addresses, event);
+                        if !readings.is_empty() {
+                            let _ = tx.try_send(readings);
+                        }
+                    }
+                    Err(e) => debug!("Ignoring discovery response: {}", e),
+                }
+            }
+        });
+    }
+
+    // Buttons and motion sensors run their local actions whether or not the server is reachable
+    let paused: Paused = Arc::default();
+    for device in config
+        .devices
+        .iter()
+        .filter(|d| matches!(d.device_type.as_str(), "S200B" | "T100"))
+    {
+        for target in device.triggers.iter().flat_map(|t| &t.targets) {
+            if !config.devices.iter().any(|d| &d.name == target) {
+                warn!(
+                    "[Trigger] {} has a trigger for unknown device {}",
+                    device.name, target
+                );
+            }
+        }
+        tokio::spawn(watch_triggers(
+            device.clone(),
+            config.devices.clone(),
+            addresses.clone(),
+            paused.clone(),
+            tx.clone(),
+        ));
+    }
+
+    let poll_addresses = addresses.clone();
     tokio::spawn(async move {
         let mut poll_interval = interval(Duration::from_secs(poll_interval_secs));
+        let mut tripped = HashMap::new();
+        let mut components = HashMap::new();
+        let mut last_clock_check: Option<Instant> = None;
+        let mut last_settings_check: Option<Instant> = None;
         loop {
             poll_interval.tick().await;
 
+            let devices: Vec<DeviceConfig> = configured_devices
+                .iter()
+                .map(|d| with_current_address(d, &poll_addresses))
+                .collect();
             let mut all_readings = Vec::new();
             for device in &devices {
-                let readings = collect_device_data(device).await;
+                let readings = collect_device_data(device, &mut components).await;
                 all_readings.extend(readings);
             }
 
+            // Device clocks drive on-device schedules, check them on the first poll and then hourly
+            if last_clock_check.is_none_or(|t| t.elapsed() >= CLOCK_CHECK_INTERVAL) {
+                last_clock_check = Some(Instant::now());
+                for device in &devices {
+                    all_readings.extend(check_device_clock(device).await);
+                }
+            }
+
+            // Put back settings changed behind our back, on the first poll and then periodically
+            if last_settings_check.is_none_or(|t| t.elapsed() >= SETTINGS_CHECK_INTERVAL) {
+                last_settings_check = Some(Instant::now());
+                for device in &devices {
+                    let settings = device.desired_settings();
+                    if !settings.is_empty() {
+                        all_readings.extend(reconcile_settings(device, &settings).await);
+                    }
+                }
+            }
+
+            let alerts = protection_alerts(&all_readings, &mut tripped);
+            all_readings.extend(alerts);
+
             if !all_readings.is_empty() {
                 info!("Collected {} readings from devices", all_readings.len());
                 // Group readings by device for cleaner output
@@ -504,17 +2366,13 @@ async fn run_agent(config: Config) -> Result<(), Box<dyn std::error::Error>> {
                 let auth_json = serde_json::to_string(&auth)?;
                 write.send(Message::Text(auth_json)).await?;
 
-                let authenticated = if let Some(Ok(msg)) = read.next().await {
-                    if let Message::Text(text) = msg {
-                        let response: ServerResponse = serde_json::from_str(&text)?;
-                        if response.msg_type == "auth" && response.success == Some(true) {
-                            info!("Authenticated successfully");
-                            true
-                        } else {
-                            error!("Authentication failed: {:?}", response.error);
-                            false
-                        }
+                let authenticated = if let Some(Ok(Message::Text(text))) = read.next().await {
+                    let response: ServerResponse = serde_json::from_str(&text)?;
+                    if response.msg_type == "auth" && response.success == Some(true) {
+                        info!("Authenticated successfully");
+                        true
                     } else {
+                        error!("Authentication failed: {:?}", response.error);
                         false
                     }
                 } else {
@@ -553,21 +2411,107 @@ async fn run_agent(config: Config) -> Result<(), Box<dyn std::error::Error>> {
                                             let device_name = cmd.get("device").and_then(|v| v.as_str()).unwrap_or("");
                                             let action = cmd.get("action").and_then(|v| v.as_str()).unwrap_or("");
                                             let value = cmd.get("value").and_then(|v| v.as_i64()).unwrap_or(0);
-                                            
+
                                             info!("[Command] Received: device={}, action={}, value={}", device_name, action, value);
-                                            
+
                                             // Find matching device in our config
                                             if let Some(device) = devices_for_commands.iter().find(|d| d.name == device_name) {
-                                                if action == "set_state" {
+                                                let device = &with_current_address(device, &addresses);
+                                                if action == "set_state" && value > 0 && is_paused(device, &paused) {
+                                                    // The server keeps re-sending on states, a pause trigger has the last word until it ends
+                                                    info!("[Command] {} is paused by a trigger, not switching it ON", device_name);
+                                                } else if action == "set_state" {
                                                     let turn_on = value > 0;
                                                     info!("[Command] Switching {} {}", device_name, if turn_on { "ON" } else { "OFF" });
-                                                    
+
                                                     let device_clone = device.clone();
                                                     tokio::spawn(async move {
                                                         if let Err(e) = switch_device(&device_clone, turn_on).await {
                                                             error!("[Command] Failed to switch {}: {}", device_clone.name, e);
                                                         }
                                                     });
+                                                } else if action == "set_led" {
+                                                    // 0 = never, 1 = always, 2 = night mode (keeps the device's night period)
+                                                    let rule = match value {
+                                                        0 => Some(LedRule::Never),
+                                                        1 => Some(LedRule::Always),
+                                                        2 => Some(LedRule::NightMode),
+                                                        _ => None,
+                                                    };
+                                                    match rule {
+                                                        Some(rule) => {
+                                                            info!("[Command] Setting {} LED to {:?}", device_name, rule);
+
+                                                            let device_clone = device.clone();
+                                                            tokio::spawn(async move {
+                                                                if let Err(e) = set_led(&device_clone, rule).await {
+                                                                    error!("[Command] Failed to set LED of {}: {}", device_clone.name, e);
+                                                                }
+                                                            });
+                                                        }
+                                                        None => warn!("[Command] Ignoring LED rule {} for {}", value, device_name),
+                                                    }
+                                                } else if action == "set_auto_off" {
+                                                    // Delay in minutes, 0 disables auto-off
+                                                    let delay_min = value.max(0) as u64;
+                                                    info!("[Command] Setting {} auto-off to {} min", device_name, delay_min);
+
+                                                    let device_clone = device.clone();
+                                                    tokio::spawn(async move {
+                                                        if let Err(e) = set_auto_off(&device_clone, delay_min).await {
+                                                            error!("[Command] Failed to set auto-off of {}: {}", device_clone.name, e);
+                                                        }
+                                                    });
+                                                } else if action == "set_target_temperature" || action == "set_frost_protection" {
+                                                    if device.device_type != "KE100" {
+                                                        warn!("[Command] {} is not a valve, ignoring {}", device_name, action);
+                                                    } else if action == "set_target_temperature" {
+                                                        // Target in °C, the valve rejects values outside its min/max control temperatures
+                                                        match u8::try_from(value) {
+                                                            Ok(celsius) => {
+                                                                info!("[Command] Setting {} target temperature to {}°C", device_name, celsius);
+
+                                                                let device_clone = device.clone();
+                                                                tokio::spawn(async move {
+                                                                    if let Err(e) = set_target_temperature(&device_clone, celsius).await {
+                                                                        error!("[Command] Failed to set target temperature of {}: {}", device_clone.name, e);
+                                                                    }
+                                                                });
+                                                            }
+                                                            Err(_) => warn!("[Command] Ignoring target temperature {} for {}", value, device_name),
+                                                        }
+                                                    } else {
+                                                        let on = value > 0;
+                                                        info!("[Command] Setting {} frost protection {}", device_name, if on { "on" } else { "off" });
+
+                                                        let device_clone = device.clone();
+                                                        tokio::spawn(async move {
+                                                            if let Err(e) = set_frost_protection(&device_clone, on).await {
+                                                                error!("[Command] Failed to set frost protection of {}: {}", device_clone.name, e);
+                                                            }
+                                                        });
+                                                    }
+                                                } else if action == "alarm" {
+                                                    // {"action": "alarm", "ringtone": "Alarm 1", "volume": "high", "duration": 30}, set_state 0 stops it
+                                                    if device.device_type != "H100" {
+                                                        warn!("[Command] {} has no siren, ignoring alarm", device_name);
+                                                    } else {
+                                                        match alarm_command(&cmd, &device.alarm) {
+                                                            Ok((ringtone, volume, duration)) => {
+                                                                info!("[Command] Sounding {} alarm", device_name);
+
+                                                                let device_clone = device.clone();
+                                                                tokio::spawn(async move {
+                                                                    if let Err(e) = sound_alarm(&device_clone, ringtone, volume, duration).await {
+                                                                        error!("[Command] Failed to sound alarm of {}: {}", device_clone.name, e);
+                                                                    }
+                                                                });
+                                                            }
+                                                            Err(e) => warn!("[Command] Ignoring alarm for {}: {}", device_name, e),
+                                                        }
+                                                    }
+                                                } else {
+                                                    warn!("[Command] Unknown action: {}", action);
                                                 }
                                             } else {
                                                 warn!("[Command] Unknown device: {}", device_name);
@@ -607,6 +2551,233 @@ async fn run_agent(config: Config) -> Result<(), Box<dyn std::error::Error>> {
     }
 }
 
+fn load_config(config_path: &str) -> Result<Config, Box<dyn std::error::Error>> {
+    let config_content = match std::fs::read_to_string(config_path) {
+        Ok(content) => content,
+        Err(e) => {
+            eprintln!("Failed to read config file {}: {}", config_path, e);
+            eprintln!();
+            eprintln!("Create config with device discovery:");
+            eprintln!("  ./tapo-agent init --server ws://SERVER:8080 --key YOUR_KEY --email tapo@email.com --password tapopass");
+            eprintln!();
+            eprintln!("Or specify broadcast address:");
+            eprintln!("  ./tapo-agent init --server ws://SERVER:8080 --key YOUR_KEY --email tapo@email.com --password tapopass --broadcast 192.168.0.255");
+            std::process::exit(1);
+        }
+    };
+
+    let config: Config =
+        toml::from_str(&config_content).map_err(|e| format!("Failed to parse config: {}", e))?;
+
+    Ok(config)
+}
+
+// Check every device for newer firmware, updating one device at a time when asked.
+// An update that fails or is never confirmed stops the rollout before the next device.
+async fn run_firmware(config: &Config, update: bool, only: Option<&str>) {
+    let devices: Vec<_> = config
+        .devices
+        .iter()
+        .filter(|d| only.is_none_or(|name| d.name == name))
+        .collect();
+    if devices.is_empty() {
+        eprintln!("No matching devices in config");
+        return;
+    }
+
+    for device in devices {
+        match device_firmware(device, update).await {
+            Ok(FirmwareOutcome::Failed(reason)) => {
+                println!("{}: {}, stopping the rollout", device.name, reason);
+                return;
+            }
+            Ok(FirmwareOutcome::Checked | FirmwareOutcome::Updated) => {}
+            Err(e) => println!("{}: failed: {}", device.name, e),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum FirmwareOutcome {
+    // Up to date, not asked to update, or skipped
+    Checked,
+    // Idle again and running the new firmware
+    Updated,
+    // Started but reported failed or never confirmed
+    Failed(String),
+}
+
+async fn device_firmware(
+    device: &DeviceConfig,
+    update: bool,
+) -> Result<FirmwareOutcome, tapo::Error> {
+    let client = ApiClient::new(&device.tapo_email, &device.tapo_password)
+        .with_retry_policy(RetryPolicy::default());
+
+    match device.device_type.as_str() {
+        "P110" | "P115" => {
+            let plug = client.p110(&device.ip).await?;
+            let info = plug.get_device_info().await?;
+            let installed_fw_ver = async || Ok(plug.get_device_info().await?.fw_ver);
+            check_firmware(
+                device,
+                &plug,
+                &info.fw_ver,
+                info.device_on,
+                update,
+                installed_fw_ver,
+            )
+            .await
+        }
+        "P100" | "P105" => {
+            let plug = client.p100(&device.ip).await?;
+            let info = plug.get_device_info().await?;
+            let installed_fw_ver = async || Ok(plug.get_device_info().await?.fw_ver);
+            check_firmware(
+                device,
+                &plug,
+                &info.fw_ver,
+                info.device_on,
+                update,
+                installed_fw_ver,
+            )
+            .await
+        }
+        _ => {
+            println!(
+                "{}: unsupported device type {}",
+                device.name, device.device_type
+            );
+            Ok(FirmwareOutcome::Checked)
+        }
+    }
+}
+
+async fn check_firmware(
+    device: &DeviceConfig,
+    handler: &impl FirmwareExt,
+    fw_ver: &str,
+    device_on: bool,
+    update: bool,
+    installed_fw_ver: impl AsyncFn() -> Result<String, tapo::Error>,
+) -> Result<FirmwareOutcome, tapo::Error> {
+    let latest = handler.get_latest_firmware().await?;
+    if !latest.need_to_upgrade {
+        println!("{}: {} (up to date)", device.name, fw_ver);
+        return Ok(FirmwareOutcome::Checked);
+    }
+
+    println!("{}: {} -> {} available", device.name, fw_ver, latest.fw_ver);
+    if !update {
+        return Ok(FirmwareOutcome::Checked);
+    }
+
+    // Updating switches the output off and reboots the plug
+    if device.critical && device_on {
+        println!(
+            "{}: skipped, critical device is on (switch it off to update)",
+            device.name
+        );
+        return Ok(FirmwareOutcome::Checked);
+    }
+
+    handler.firmware_download().await?;
+    println!("{}: update started", device.name);
+
+    // The device drops off the network while flashing and rebooting, so errors are expected for a while
+    let deadline = Instant::now() + FIRMWARE_UPDATE_TIMEOUT;
+    while Instant::now() < deadline {
+        sleep(Duration::from_secs(5)).await;
+
+        let state = match handler.get_firmware_download_state().await {
+            Ok(state) => state,
+            Err(e) => {
+                debug!("{}: waiting for device: {}", device.name, e);
+                continue;
+            }
+        };
+        match state.state() {
+            FirmwareUpdateState::Downloading => {
+                println!("{}: downloading {}%", device.name, state.download_progress);
+            }
+            FirmwareUpdateState::Flashing => println!("{}: flashing", device.name),
+            FirmwareUpdateState::Failed => {
+                return Ok(FirmwareOutcome::Failed(format!(
+                    "update failed (status {})",
+                    state.status
+                )));
+            }
+            // Idle both before the download starts and after the reboot, only the version tells them apart
+            FirmwareUpdateState::Idle => match installed_fw_ver().await {
+                Ok(installed) if installed == latest.fw_ver => {
+                    println!("{}: update finished, running {}", device.name, installed);
+                    return Ok(FirmwareOutcome::Updated);
+                }
+                Ok(installed) => debug!("{}: still running {}", device.name, installed),
+                Err(e) => debug!("{}: waiting for device: {}", device.name, e),
+            },
+            FirmwareUpdateState::Unknown => {}
+        }
+    }
+
+    Ok(FirmwareOutcome::Failed(format!(
+        "no confirmation after {:?}, check the device",
+        FIRMWARE_UPDATE_TIMEOUT
+    )))
+}
+
+async fn run_onboard(
+    ip: String,
+    email: String,
+    password: String,
+    ssid: Option<String>,
+    wifi_password: String,
+) -> Result<(), tapo::Error> {
+    let device = ApiClient::new(&email, &password).setup_mode(&ip).await?;
+
+    let info = device.get_device_info_json().await?;
+    println!(
+        "Found {} ({}) at {}",
+        info["model"].as_str().unwrap_or("unknown model"),
+        info["mac"].as_str().unwrap_or("unknown MAC"),
+        ip
+    );
+
+    let networks = device.scan_wifi().await?;
+
+    let Some(ssid) = ssid else {
+        println!("Networks seen by the device:");
+        for network in &networks {
+            println!(
+                "  {:<32} signal {}/3  {}",
+                network.ssid, network.signal_level, network.key_type
+            );
+        }
+        println!("Run again with --ssid and --wifi-password to onboard the device");
+        return Ok(());
+    };
+
+    match networks.iter().find(|network| network.ssid == ssid) {
+        Some(network) => device.onboard(network, &wifi_password).await?,
+        None => {
+            // Hidden networks don't show up in the scan, assume WPA2 unless no password was given
+            let key_type = if wifi_password.is_empty() {
+                "none"
+            } else {
+                "wpa2_psk"
+            };
+            warn!(
+                "{} not found in scan, joining it as a hidden {} network",
+                ssid, key_type
+            );
+            device.onboard_ssid(&ssid, key_type, &wifi_password).await?
+        }
+    }
+
+    println!("Device is joining {}. Reconnect to that network and run `tapo-agent init` to add it to the config", ssid);
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::init();
@@ -620,36 +2791,556 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             email,
             password,
             broadcast,
+            scan,
             timeout,
             output,
         }) => {
-            discover_and_create_config(server, key, email, password, broadcast, timeout, output).await?;
+            discover_and_create_config(
+                server, key, email, password, broadcast, scan, timeout, output,
+            )
+            .await?;
         }
         Some(Commands::Run) | None => {
-            let config_path = &cli.config;
-
-            let config_content = match std::fs::read_to_string(config_path) {
-                Ok(content) => content,
-                Err(e) => {
-                    eprintln!("Failed to read config file {}: {}", config_path, e);
-                    eprintln!();
-                    eprintln!("Create config with device discovery:");
-                    eprintln!("  ./tapo-agent init --server ws://SERVER:8080 --key YOUR_KEY --email tapo@email.com --password tapopass");
-                    eprintln!();
-                    eprintln!("Or specify broadcast address:");
-                    eprintln!("  ./tapo-agent init --server ws://SERVER:8080 --key YOUR_KEY --email tapo@email.com --password tapopass --broadcast 192.168.0.255");
-                    std::process::exit(1);
-                }
-            };
-
-            let config: Config = toml::from_str(&config_content)
-                .map_err(|e| format!("Failed to parse config: {}", e))?;
+            let config = load_config(&cli.config)?;
 
             info!("Tapo Agent starting with {} devices", config.devices.len());
 
             run_agent(config).await?;
         }
+        Some(Commands::Firmware { update, device }) => {
+            let config = load_config(&cli.config)?;
+
+            run_firmware(&config, update, device.as_deref()).await;
+        }
+        Some(Commands::Onboard {
+            ip,
+            email,
+            password,
+            ssid,
+            wifi_password,
+        }) => {
+            run_onboard(ip, email, password, ssid, wifi_password).await?;
+        }
+        Some(Commands::SyncNames { push, pull }) => {
+            let mut config = load_config(&cli.config)?;
+
+            if sync_names(&mut config, push, pull).await {
+                std::fs::write(&cli.config, toml::to_string_pretty(&config)?)?;
+                println!("✓ Config written to: {}", cli.config);
+                println!("Readings are now reported under the new names");
+            }
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tapo::DeviceAnnouncement;
+
+    #[test]
+    fn test_alarm_command_defaults() {
+        let (ringtone, volume, duration) =
+            alarm_command(&serde_json::json!({}), &AlarmConfig::default()).unwrap();
+        assert_eq!(ringtone, AlarmRingtone::Default);
+        assert_eq!(volume, AlarmVolume::Default);
+        assert!(matches!(duration, AlarmDuration::Continuous));
+
+        let defaults = AlarmConfig {
+            ringtone: Some(AlarmRingtone::Alarm1),
+            volume: Some(AlarmVolume::High),
+            duration_secs: Some(60),
+        };
+        let (ringtone, volume, duration) =
+            alarm_command(&serde_json::json!({}), &defaults).unwrap();
+        assert_eq!(ringtone, AlarmRingtone::Alarm1);
+        assert_eq!(volume, AlarmVolume::High);
+        assert!(matches!(duration, AlarmDuration::Seconds(60)));
+
+        // The command overrides [devices.alarm]
+        let cmd =
+            serde_json::json!({"ringtone": "Phone Ring", "volume": "low", "duration": "once"});
+        let (ringtone, volume, duration) = alarm_command(&cmd, &defaults).unwrap();
+        assert_eq!(ringtone, AlarmRingtone::PhoneRing);
+        assert_eq!(volume, AlarmVolume::Low);
+        assert!(matches!(duration, AlarmDuration::Once));
+    }
+
+    #[test]
+    fn test_alarm_command_invalid() {
+        let defaults = AlarmConfig::default();
+
+        for cmd in [
+            serde_json::json!({"ringtone": "Alarm 9"}),
+            serde_json::json!({"ringtone": 1}),
+            serde_json::json!({"volume": "loud"}),
+        ] {
+            assert!(alarm_command(&cmd, &defaults).is_err(), "{}", cmd);
+        }
+    }
+
+    #[test]
+    fn test_alarm_command_duration() {
+        let duration = |value: serde_json::Value| {
+            alarm_command(
+                &serde_json::json!({ "duration": value }),
+                &AlarmConfig::default(),
+            )
+            .map(|(_, _, duration)| duration)
+        };
+
+        assert!(matches!(
+            duration(serde_json::json!(1)),
+            Ok(AlarmDuration::Seconds(1))
+        ));
+        assert!(matches!(
+            duration(serde_json::json!(u64::MAX)),
+            Ok(AlarmDuration::Seconds(u32::MAX))
+        ));
+        assert!(matches!(
+            duration(serde_json::json!("continuous")),
+            Ok(AlarmDuration::Continuous)
+        ));
+        assert!(duration(serde_json::json!(0)).is_err());
+        assert!(duration(serde_json::json!(-5)).is_err());
+        assert!(duration(serde_json::json!(1.5)).is_err());
+        assert!(duration(serde_json::json!("forever")).is_err());
+
+        // A duration of 0 in [devices.alarm] plays until stopped
+        let defaults = AlarmConfig {
+            duration_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(matches!(defaults.duration(), AlarmDuration::Continuous));
+    }
+
+    #[test]
+    fn test_hub_device() {
+        let mut device: DeviceConfig = toml::from_str(
+            r#"
+            ip = "192.168.1.52"
+            name = "drying-room-radiator"
+            type = "KE100"
+            tapo_email = "your@email.com"
+            tapo_password = "your-tapo-password"
+            "#,
+        )
+        .unwrap();
+        assert!(
+            matches!(hub_device(&device), HubDevice::ByNickname(n) if n == "drying-room-radiator")
+        );
+
+        device.child = Some("802E2A9C3F0F1D5B5E2B8C1A6F7D9E0A1B2C3D4E".to_string());
+        assert!(matches!(hub_device(&device), HubDevice::ByDeviceId(_)));
+
+        device.child = Some("Drying Room Radiator".to_string());
+        assert!(
+            matches!(hub_device(&device), HubDevice::ByNickname(n) if n == "Drying Room Radiator")
+        );
+    }
+
+    fn trigger_log(ids: &[u64]) -> Vec<TriggerEvent> {
+        // Newest first, as the hub logs them
+        ids.iter()
+            .map(|&id| TriggerEvent {
+                id,
+                timestamp: 1_700_000_000 + id,
+                event: "single_click",
+                degrees: None,
+            })
+            .collect()
+    }
+
+    fn event_ids(events: Vec<&TriggerEvent>) -> Vec<u64> {
+        events.iter().map(|e| e.id).collect()
+    }
+
+    #[test]
+    fn test_trigger_log_cursor() {
+        let mut cursor = TriggerLogCursor::default();
+
+        // Events from before the agent started are skipped
+        assert!(cursor.new_events(&trigger_log(&[3, 2, 1])).is_empty());
+        assert!(cursor.new_events(&trigger_log(&[3, 2, 1])).is_empty());
+
+        assert_eq!(
+            event_ids(cursor.new_events(&trigger_log(&[5, 4, 3, 2]))),
+            [4, 5]
+        );
+        assert!(cursor.new_events(&trigger_log(&[5, 4, 3, 2])).is_empty());
+        assert_eq!(event_ids(cursor.new_events(&trigger_log(&[6, 5]))), [6]);
+    }
+
+    #[test]
+    fn test_trigger_log_cursor_empty_and_cleared_log() {
+        let mut cursor = TriggerLogCursor::default();
+
+        // The first event of a new button is acted on
+        assert!(cursor.new_events(&[]).is_empty());
+        assert_eq!(event_ids(cursor.new_events(&trigger_log(&[1]))), [1]);
+
+        // Once the hub log was cleared, its ids restart and are followed from the first one seen
+        assert_eq!(event_ids(cursor.new_events(&trigger_log(&[3, 2]))), [2, 3]);
+        assert!(cursor.new_events(&trigger_log(&[1])).is_empty());
+        assert_eq!(event_ids(cursor.new_events(&trigger_log(&[2, 1]))), [2]);
+    }
+
+    #[test]
+    fn test_pause_list() {
+        let now = Instant::now();
+        let minutes = |m: u64| now + Duration::from_secs(m * 60);
+        let mut paused = PauseList::default();
+
+        paused.pause("fan-plug", minutes(10));
+        assert!(paused.is_paused("fan-plug", now));
+        assert!(paused.is_paused("fan-plug", minutes(9)));
+        assert!(!paused.is_paused("fan-plug", minutes(10)));
+        assert!(!paused.is_paused("grow-light-plug", now));
+
+        assert!(paused.end("fan-plug", minutes(10)));
+        assert!(!paused.is_paused("fan-plug", now));
+    }
+
+    #[test]
+    fn test_pause_list_overlapping_pauses() {
+        let now = Instant::now();
+        let minutes = |m: u64| now + Duration::from_secs(m * 60);
+        let mut paused = PauseList::default();
+
+        // A second pause extends the first, which then leaves the plug off
+        paused.pause("fan-plug", minutes(10));
+        paused.pause("fan-plug", minutes(15));
+        assert!(!paused.end("fan-plug", minutes(10)));
+        assert!(paused.is_paused("fan-plug", minutes(12)));
+        assert!(paused.end("fan-plug", minutes(15)));
+
+        // A shorter pause during a longer one does not cut it short
+        paused.pause("fan-plug", minutes(30));
+        paused.pause("fan-plug", minutes(5));
+        assert!(!paused.end("fan-plug", minutes(5)));
+        assert!(paused.is_paused("fan-plug", minutes(20)));
+        assert!(paused.end("fan-plug", minutes(30)));
+    }
+
+    #[test]
+    fn test_rename_device_updates_trigger_targets() {
+        let mut config: Config = toml::from_str(
+            r#"
+            server_url = "ws://localhost:3900/ws"
+            api_key = "key"
+            poll_interval_secs = 60
+
+            [[devices]]
+            ip = "192.168.1.50"
+            name = "fan-plug"
+            type = "P110"
+            tapo_email = "your@email.com"
+            tapo_password = "your-tapo-password"
+
+            [[devices]]
+            ip = "192.168.1.52"
+            name = "tent-button"
+            type = "S200B"
+            tapo_email = "your@email.com"
+            tapo_password = "your-tapo-password"
+
+            [[devices.triggers]]
+            event = "double_click"
+            action = "pause"
+            targets = ["fan-plug", "grow-light-plug"]
+            "#,
+        )
+        .unwrap();
+
+        rename_device(&mut config, 0, "tent-fan".to_string());
+        assert_eq!(config.devices[0].name, "tent-fan");
+        assert_eq!(config.devices[0].child, None);
+        assert_eq!(
+            config.devices[1].triggers[0].targets,
+            ["tent-fan", "grow-light-plug"]
+        );
+
+        // A renamed button keeps being found on its hub by its old name
+        rename_device(&mut config, 1, "work-button".to_string());
+        assert_eq!(config.devices[1].child.as_deref(), Some("tent-button"));
+    }
+
+    #[test]
+    fn test_setting_drift() {
+        assert!(setting_drift("led", LedRule::Always, LedRule::Always).is_none());
+
+        let drift = setting_drift("led", LedRule::Always, LedRule::NightMode).unwrap();
+        assert_eq!(drift.setting, "led");
+        assert_eq!(drift.found, "Always");
+        assert_eq!(drift.desired, "NightMode");
+    }
+
+    #[test]
+    fn test_auto_off_drift() {
+        // Disabled with a delay kept on the device is the same as 0 minutes
+        assert!(auto_off_drift(false, 120, 0).is_none());
+        assert!(auto_off_drift(true, 120, 120).is_none());
+
+        let drift = auto_off_drift(false, 120, 120).unwrap();
+        assert_eq!((drift.found.as_str(), drift.desired.as_str()), ("0", "120"));
+        let drift = auto_off_drift(true, 120, 0).unwrap();
+        assert_eq!((drift.found.as_str(), drift.desired.as_str()), ("120", "0"));
+    }
+
+    #[test]
+    fn test_time_zone_drift() {
+        let desired = TimeZoneSetting {
+            region: "Europe/London".to_string(),
+            utc_offset_min: 60,
+        };
+
+        assert!(time_zone_drift("Europe/London", 60, &desired).is_none());
+        assert!(time_zone_drift("Europe/Paris", 60, &desired).is_some());
+
+        let drift = time_zone_drift("Europe/London", 0, &desired).unwrap();
+        assert_eq!(drift.found, "Europe/London (+0 min)");
+        assert_eq!(drift.desired, "Europe/London (+60 min)");
+    }
+
+    #[test]
+    fn test_power_protection_drift() {
+        assert!(power_protection_drift(true, 1500, 1500).is_none());
+        assert!(power_protection_drift(false, 1500, 0).is_none());
+        assert!(power_protection_drift(true, 1500, 2000).is_some());
+        assert!(power_protection_drift(true, 1500, 0).is_some());
+
+        let drift = power_protection_drift(false, 1500, 1500).unwrap();
+        assert_eq!(
+            (drift.found.as_str(), drift.desired.as_str()),
+            ("0", "1500")
+        );
+        assert!(serde_json::to_value(&drift).unwrap()["unsupported"].is_null());
+
+        // Above the plug's rating the drift is still reported, with why it was left in place
+        let drift = power_protection_drift(true, 1500, 4000)
+            .unwrap()
+            .unsupported("plug is rated for 3680 W".to_string());
+        assert_eq!(
+            serde_json::to_value(&drift).unwrap(),
+            serde_json::json!({
+                "setting": "power_protection_watts",
+                "found": "1500",
+                "desired": "4000",
+                "unsupported": "plug is rated for 3680 W",
+            })
+        );
+    }
+
+    fn status_reading(channel: &str, value: f64, status: &str) -> Reading {
+        Reading {
+            device: "fan-plug".to_string(),
+            device_type: "P110".to_string(),
+            channel: channel.to_string(),
+            value: Some(value),
+            data: Some(serde_json::json!(status)),
+        }
+    }
+
+    #[test]
+    fn test_protection_alerts() {
+        let mut tripped = HashMap::new();
+        let normal = [
+            status_reading("overcurrent_status", 0.0, "normal"),
+            status_reading("power_protection_status", 0.0, "normal"),
+            status_reading("power", 1200.0, "1200"),
+        ];
+        assert!(protection_alerts(&normal, &mut tripped).is_empty());
+
+        // Each trip is reported once, until the status is back to normal
+        let overloaded = [
+            status_reading("overcurrent_status", 0.0, "normal"),
+            status_reading("power_protection_status", 1.0, "overloaded"),
+        ];
+        let alerts = protection_alerts(&overloaded, &mut tripped);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].device, "fan-plug");
+        assert_eq!(alerts[0].channel, "alert");
+        assert_eq!(
+            alerts[0].data,
+            Some(serde_json::json!({
+                "source": "power_protection_status",
+                "status": "overloaded",
+            }))
+        );
+        assert!(protection_alerts(&overloaded, &mut tripped).is_empty());
+
+        assert!(protection_alerts(&normal, &mut tripped).is_empty());
+        assert_eq!(protection_alerts(&overloaded, &mut tripped).len(), 1);
+    }
+
+    fn plug() -> DeviceConfig {
+        toml::from_str(
+            r#"
+            ip = "192.168.1.50"
+            name = "fan-plug"
+            type = "P110"
+            tapo_email = "your@email.com"
+            tapo_password = "your-tapo-password"
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_next_event_reading() {
+        let event = |value: serde_json::Value| -> NextEventResult {
+            serde_json::from_value(value).unwrap()
+        };
+
+        let reading = next_event_reading(&plug(), &event(serde_json::json!({})));
+        assert_eq!(reading.channel, "next_event");
+        assert_eq!(reading.value, None);
+        assert_eq!(reading.data, Some(serde_json::Value::Null));
+
+        let at: i64 = 4_102_444_800; // 2100-01-01
+        let reading = next_event_reading(
+            &plug(),
+            &event(serde_json::json!({
+                "id": "S1",
+                "s_time": at,
+                "desired_states": {"on": false},
+            })),
+        );
+        assert!(reading.value.unwrap() > 0.0);
+        assert_eq!(
+            reading.data,
+            Some(serde_json::json!({ "id": "S1", "at": at, "action": "off" }))
+        );
+
+        // A rule that already fired is due now
+        let reading = next_event_reading(
+            &plug(),
+            &event(serde_json::json!({ "id": "S2", "s_time": 1_700_000_000 })),
+        );
+        assert_eq!(reading.value, Some(0.0));
+        assert_eq!(reading.data.unwrap()["action"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_config_name() {
+        assert_eq!(config_name("Grow Light"), "grow-light");
+        assert_eq!(config_name("fan-plug"), "fan-plug");
+        assert_eq!(config_name("Tent Fan 2"), "tent-fan-2");
+    }
+
+    #[test]
+    fn test_polls() {
+        // Devices that did not report their components are polled for everything
+        assert!(polls(None, "energy_monitoring"));
+        assert!(polls(
+            Some(&ComponentListResult::default()),
+            "energy_monitoring"
+        ));
+
+        let components = ComponentListResult {
+            components: [("device".to_string(), 2), ("schedule".to_string(), 3)].into(),
+        };
+        assert!(polls(Some(&components), "schedule"));
+        assert!(!polls(Some(&components), "energy_monitoring"));
+    }
+
+    #[test]
+    fn test_same_mac() {
+        assert!(same_mac("AA-BB-CC-DD-EE-FF", "AA-BB-CC-DD-EE-FF"));
+        assert!(same_mac("aa:bb:cc:dd:ee:ff", "AA-BB-CC-DD-EE-FF"));
+        assert!(same_mac("aabbccddeeff", "AA-BB-CC-DD-EE-FF"));
+        assert!(!same_mac("AA-BB-CC-DD-EE-00", "AA-BB-CC-DD-EE-FF"));
+    }
+
+    fn announcement(ip: &str, mac: &str) -> DeviceAnnouncement {
+        serde_json::from_value(serde_json::json!({
+            "ip": ip,
+            "mac": mac,
+            "device_id": "802E2A9C3F0F1D5B5E2B8C1A6F7D9E0A",
+            "device_model": "P110(EU)",
+            "device_type": "SMART.TAPOPLUG",
+        }))
+        .unwrap()
+    }
+
+    fn channels(readings: &[Reading]) -> Vec<(&str, &str)> {
+        readings
+            .iter()
+            .map(|r| (r.device.as_str(), r.channel.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_discovery_event_readings() {
+        let mut fan = plug();
+        fan.mac = Some("aa:bb:cc:dd:ee:ff".to_string());
+        let mut light = plug();
+        light.name = "grow-light-plug".to_string();
+        light.ip = "192.168.1.51".to_string();
+        let devices = [fan, light];
+        let addresses = Addresses::default();
+
+        let readings = discovery_event_readings(
+            &devices,
+            &addresses,
+            DiscoveryEvent::Appeared(announcement("192.168.1.50", "AA-BB-CC-DD-EE-FF")),
+        );
+        assert_eq!(channels(&readings), [("fan-plug", "online")]);
+        assert_eq!(readings[0].value, Some(1.0));
+
+        // The device is followed to its new address, and found there afterwards
+        let readings = discovery_event_readings(
+            &devices,
+            &addresses,
+            DiscoveryEvent::AddressChanged {
+                announcement: announcement("192.168.1.60", "AA-BB-CC-DD-EE-FF"),
+                previous_ip: "192.168.1.50".parse().unwrap(),
+            },
+        );
+        assert_eq!(channels(&readings), [("fan-plug", "address")]);
+        assert_eq!(
+            readings[0].data,
+            Some(serde_json::json!({ "ip": "192.168.1.60", "previous_ip": "192.168.1.50" }))
+        );
+        assert_eq!(
+            with_current_address(&devices[0], &addresses).ip,
+            "192.168.1.60"
+        );
+
+        // Devices without a MAC in the config are recognized by their previous address
+        let readings = discovery_event_readings(
+            &devices,
+            &addresses,
+            DiscoveryEvent::AddressChanged {
+                announcement: announcement("192.168.1.61", "11-22-33-44-55-66"),
+                previous_ip: "192.168.1.51".parse().unwrap(),
+            },
+        );
+        assert_eq!(channels(&readings), [("grow-light-plug", "address")]);
+        assert_eq!(
+            with_current_address(&devices[1], &addresses).ip,
+            "192.168.1.61"
+        );
+
+        let readings = discovery_event_readings(
+            &devices,
+            &addresses,
+            DiscoveryEvent::Disappeared(announcement("192.168.1.60", "AA-BB-CC-DD-EE-FF")),
+        );
+        assert_eq!(
+            channels(&readings),
+            [("fan-plug", "online"), ("fan-plug", "alert")]
+        );
+        assert_eq!(readings[0].value, Some(0.0));
+
+        // Unknown devices are ignored
+        assert!(discovery_event_readings(
+            &devices,
+            &addresses,
+            DiscoveryEvent::Appeared(announcement("192.168.1.70", "00-00-00-00-00-01")),
+        )
+        .is_empty());
+    }
+}
@@ -2,12 +2,12 @@ use std::{ops::Deref, sync::Arc};
 
 use chrono::{DateTime, NaiveDate, Utc};
 use pyo3::{prelude::*, types::PyDict};
-use tapo::PowerStripPlugEnergyMonitoringHandler;
 use tapo::requests::{EnergyDataInterval, PowerDataInterval};
 use tapo::responses::{
     CurrentPowerResult, DeviceUsageEnergyMonitoringResult, EnergyDataResult, EnergyUsageResult,
     PowerDataResult, PowerStripPlugEnergyMonitoringResult,
 };
+use tapo::{EnergyMonitoringExt as _, OnOffExt as _, PowerStripPlugEnergyMonitoringHandler};
 
 use crate::call_handler_method;
 use crate::requests::{PyEnergyDataInterval, PyPowerDataInterval};
@@ -5,7 +5,9 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use tapo::requests::Color;
 use tapo::responses::{DeviceInfoColorLightResult, DeviceUsageEnergyMonitoringResult};
-use tapo::{ColorLightHandler, DeviceManagementExt as _, HandlerExt};
+use tapo::{
+    BrightnessExt as _, ColorLightHandler, DeviceManagementExt as _, HandlerExt, OnOffExt as _,
+};
 use tokio::sync::RwLock;
 
 use crate::api::PyHandlerExt;
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use tapo::responses::{DeviceInfoLightResult, DeviceUsageEnergyMonitoringResult};
-use tapo::{DeviceManagementExt as _, LightHandler};
+use tapo::{BrightnessExt as _, DeviceManagementExt as _, LightHandler, OnOffExt as _};
 use tokio::sync::RwLock;
 
 use crate::call_handler_method;
@@ -9,7 +9,9 @@ use tapo::responses::{
     CurrentPowerResult, DeviceInfoPlugEnergyMonitoringResult, DeviceUsageEnergyMonitoringResult,
     EnergyDataResult, EnergyUsageResult, PowerDataResult,
 };
-use tapo::{DeviceManagementExt as _, PlugEnergyMonitoringHandler};
+use tapo::{
+    DeviceManagementExt as _, EnergyMonitoringExt as _, OnOffExt as _, PlugEnergyMonitoringHandler,
+};
 use tokio::sync::RwLock;
 
 use crate::call_handler_method;
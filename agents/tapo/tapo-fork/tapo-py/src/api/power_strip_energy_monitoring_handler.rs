@@ -4,7 +4,9 @@ use std::sync::Arc;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use tapo::responses::DeviceInfoPowerStripResult;
-use tapo::{DeviceManagementExt as _, Error, Plug, PowerStripEnergyMonitoringHandler};
+use tapo::{
+    ChildDevicesExt as _, DeviceManagementExt as _, Error, Plug, PowerStripEnergyMonitoringHandler,
+};
 use tokio::sync::RwLock;
 
 use crate::api::PyPowerStripPlugEnergyMonitoringHandler;
@@ -109,7 +111,8 @@ impl PyPowerStripEnergyMonitoringHandler {
         let handler = self.inner.clone();
         let result = call_handler_method!(
             handler.read().await.deref(),
-            PowerStripEnergyMonitoringHandler::get_child_device_list_json
+            PowerStripEnergyMonitoringHandler::get_child_device_list_json,
+            0
         )?;
         Python::attach(|py| tapo::python::serde_object_to_py_dict(py, &result))
     }
@@ -4,7 +4,7 @@ use std::sync::Arc;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyList};
 use tapo::responses::DeviceInfoPowerStripResult;
-use tapo::{DeviceManagementExt as _, Error, Plug, PowerStripHandler};
+use tapo::{ChildDevicesExt as _, DeviceManagementExt as _, Error, Plug, PowerStripHandler};
 use tokio::sync::RwLock;
 
 use crate::api::PyPowerStripPlugHandler;
@@ -109,7 +109,8 @@ impl PyPowerStripHandler {
         let handler = self.inner.clone();
         let result = call_handler_method!(
             handler.read().await.deref(),
-            PowerStripHandler::get_child_device_list_json
+            PowerStripHandler::get_child_device_list_json,
+            0
         )?;
         Python::attach(|py| tapo::python::serde_object_to_py_dict(py, &result))
     }
@@ -5,7 +5,9 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use tapo::requests::Color;
 use tapo::responses::{DeviceInfoRgbLightStripResult, DeviceUsageEnergyMonitoringResult};
-use tapo::{DeviceManagementExt as _, HandlerExt, RgbLightStripHandler};
+use tapo::{
+    BrightnessExt as _, DeviceManagementExt as _, HandlerExt, OnOffExt as _, RgbLightStripHandler,
+};
 use tokio::sync::RwLock;
 
 use crate::api::PyHandlerExt;
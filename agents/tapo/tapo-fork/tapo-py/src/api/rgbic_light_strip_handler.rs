@@ -6,7 +6,9 @@ use pyo3::prelude::*;
 use pyo3::types::PyDict;
 use tapo::requests::{Color, LightingEffect, LightingEffectPreset};
 use tapo::responses::{DeviceInfoRgbicLightStripResult, DeviceUsageEnergyMonitoringResult};
-use tapo::{DeviceManagementExt as _, HandlerExt, RgbicLightStripHandler};
+use tapo::{
+    BrightnessExt as _, DeviceManagementExt as _, HandlerExt, OnOffExt as _, RgbicLightStripHandler,
+};
 use tokio::sync::RwLock;
 
 use crate::api::PyHandlerExt;
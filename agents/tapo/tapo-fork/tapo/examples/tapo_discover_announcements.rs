@@ -0,0 +1,63 @@
+/// Discover devices on the local network without credentials Example
+use std::env;
+
+use log::{error, info};
+use tapo::{AnnouncementDiscovery, ApiClient, StreamExt};
+
+mod common;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    common::setup_logger();
+
+    let target = env::var("TAPO_DISCOVERY_TARGET").unwrap_or_else(|_| "192.168.1.255".to_string());
+    let timeout_s = env::var("TAPO_DISCOVERY_TIMEOUT")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse::<u64>()
+        .unwrap_or(10);
+    // Only devices of this model are logged into, when credentials are given.
+    let connect_model = env::var("TAPO_CONNECT_MODEL").unwrap_or_else(|_| "P110".to_string());
+
+    info!("Discovering Tapo devices on target: {target} for {timeout_s} seconds...");
+
+    let mut discovery = AnnouncementDiscovery::new(target, timeout_s).await?;
+
+    while let Some(announcement) = discovery.next().await {
+        let announcement = match announcement {
+            Ok(announcement) => announcement,
+            Err(e) => {
+                error!("Error discovering device: {e:?}");
+                continue;
+            }
+        };
+
+        info!(
+            "Found {:?} of type {:?} at IP address {:?} with MAC {:?} and encryption {:?}.",
+            announcement.model,
+            announcement.device_type,
+            announcement.ip,
+            announcement.mac,
+            announcement
+                .encryption
+                .as_ref()
+                .and_then(|e| e.encrypt_type)
+        );
+
+        if let (Ok(tapo_username), Ok(tapo_password)) =
+            (env::var("TAPO_USERNAME"), env::var("TAPO_PASSWORD"))
+            && announcement.model.starts_with(&connect_model)
+        {
+            let client = ApiClient::new(tapo_username, tapo_password);
+            match announcement.connect_any(client).await {
+                Ok(device) => info!(
+                    "Connected to {} with capabilities {:?}.",
+                    device.model(),
+                    device.capabilities()
+                ),
+                Err(e) => error!("Error connecting to {}: {e:?}", announcement.ip),
+            }
+        }
+    }
+
+    Ok(())
+}
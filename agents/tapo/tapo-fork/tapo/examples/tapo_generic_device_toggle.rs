@@ -2,7 +2,7 @@
 use std::env;
 
 use log::{info, warn};
-use tapo::ApiClient;
+use tapo::{ApiClient, OnOffExt};
 
 mod common;
 
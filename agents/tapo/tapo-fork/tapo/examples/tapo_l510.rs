@@ -2,7 +2,7 @@
 use std::{env, thread, time::Duration};
 
 use log::info;
-use tapo::ApiClient;
+use tapo::{ApiClient, BrightnessExt, OnOffExt};
 
 mod common;
 
@@ -2,7 +2,7 @@
 use std::{env, thread, time::Duration};
 
 use log::info;
-use tapo::{ApiClient, requests::Color};
+use tapo::{ApiClient, BrightnessExt, OnOffExt, requests::Color};
 
 mod common;
 
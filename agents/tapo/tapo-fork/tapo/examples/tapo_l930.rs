@@ -2,8 +2,8 @@
 use std::{env, thread, time::Duration};
 
 use log::info;
-use tapo::ApiClient;
 use tapo::requests::{Color, LightingEffect, LightingEffectPreset, LightingEffectType};
+use tapo::{ApiClient, BrightnessExt, OnOffExt};
 
 mod common;
 
@@ -3,8 +3,8 @@ use std::{env, thread, time::Duration};
 
 use chrono::{Datelike as _, NaiveDate, Utc};
 use log::info;
-use tapo::ApiClient;
 use tapo::requests::{EnergyDataInterval, PowerDataInterval};
+use tapo::{ApiClient, EnergyMonitoringExt, OnOffExt};
 
 mod common;
 
@@ -2,7 +2,7 @@
 use std::{env, thread, time::Duration};
 
 use log::info;
-use tapo::{ApiClient, Plug};
+use tapo::{ApiClient, OnOffExt, Plug};
 
 mod common;
 
@@ -4,7 +4,7 @@ use std::{env, thread, time::Duration};
 use chrono::{Datelike as _, NaiveDate, Utc};
 use log::info;
 use tapo::{
-    ApiClient, Plug,
+    ApiClient, EnergyMonitoringExt, OnOffExt, Plug,
     requests::{EnergyDataInterval, PowerDataInterval},
 };
 
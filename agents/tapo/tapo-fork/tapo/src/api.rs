@@ -1,3 +1,4 @@
+mod any_device;
 mod api_client;
 mod capabilities;
 mod child_devices;
@@ -13,9 +14,13 @@ mod plug_handler;
 mod power_strip_energy_monitoring_handler;
 mod power_strip_handler;
 mod protocol;
+mod retry_policy;
 mod rgb_light_strip_handler;
 mod rgbic_light_strip_handler;
+mod setup_mode_handler;
+mod transport;
 
+pub use any_device::*;
 pub use api_client::*;
 pub use capabilities::*;
 pub use child_devices::*;
@@ -30,5 +35,8 @@ pub use plug_energy_monitoring_handler::*;
 pub use plug_handler::*;
 pub use power_strip_energy_monitoring_handler::*;
 pub use power_strip_handler::*;
+pub use retry_policy::*;
 pub use rgb_light_strip_handler::*;
 pub use rgbic_light_strip_handler::*;
+pub use setup_mode_handler::*;
+pub use transport::*;
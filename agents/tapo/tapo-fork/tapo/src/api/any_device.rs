@@ -0,0 +1,370 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::{RwLock, RwLockReadGuard};
+
+use crate::api::{ApiClient, ApiClientExt};
+use crate::error::Error;
+use crate::responses::ComponentListResult;
+
+use super::{
+    BrightnessExt, ChildDevicesExt, ComponentListExt, CountdownExt, DeviceInfoExt,
+    DeviceManagementExt, DeviceTimeExt, EnergyMonitoringExt, FirmwareExt, HandlerExt, OnOffExt,
+    ScheduleExt,
+};
+
+/// A capability that a device may support, as reported by [`AnyDevice::supports`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    /// Turning the device *on* and *off*. See [`OnOffExt`].
+    OnOff,
+    /// Measuring the power drawn by the connected appliance. See [`EnergyMonitoringExt`].
+    EnergyMonitoring,
+    /// Countdown timers. See [`CountdownExt`].
+    Countdown,
+    /// Schedules. See [`ScheduleExt`].
+    Schedule,
+    /// Dimming. See [`BrightnessExt`].
+    Brightness,
+    /// Child devices, like the sockets of a power strip or the sensors paired to a hub. See [`ChildDevicesExt`].
+    ChildDevices,
+}
+
+/// Handler for any Tapo device, returned by [`ApiClient::connect`] when the device model is not known in advance.
+///
+/// The capabilities of the device are detected when connecting, from its device info and the components
+/// its firmware reports. Use [`AnyDevice::supports`] to check for a capability, or one of the `as_*` methods
+/// to get a view of the device that implements the matching capability trait, such as [`OnOffView`].
+/// The traits every device supports, like [`DeviceInfoExt`] and [`FirmwareExt`], are implemented by
+/// [`AnyDevice`] itself.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tapo::{ApiClient, Capability, EnergyMonitoringExt, OnOffExt};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+///         .connect("192.168.1.100")
+///         .await?;
+///
+///     println!("Connected to {}", device.model());
+///
+///     if let Some(energy) = device.as_energy_monitoring() {
+///         let current_power = energy.get_current_power().await?;
+///         println!("Current power: {current_power:?}");
+///     }
+///
+///     if device.supports(Capability::OnOff) {
+///         device.as_on_off().unwrap().off().await?;
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AnyDevice {
+    client: Arc<RwLock<ApiClient>>,
+    model: String,
+    device_type: String,
+    components: ComponentListResult,
+    capabilities: Vec<Capability>,
+}
+
+impl AnyDevice {
+    pub(crate) fn new(
+        client: Arc<RwLock<ApiClient>>,
+        device_info: &serde_json::Value,
+        components: ComponentListResult,
+    ) -> Self {
+        let model = device_info["model"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let device_type = device_info["type"].as_str().unwrap_or_default().to_string();
+        let capabilities = detect_capabilities(device_info, &device_type, &model, &components);
+
+        Self {
+            client,
+            model,
+            device_type,
+            components,
+            capabilities,
+        }
+    }
+
+    /// Refreshes the authentication session.
+    pub async fn refresh_session(&mut self) -> Result<&mut Self, Error> {
+        self.client.write().await.refresh_session().await?;
+        Ok(self)
+    }
+
+    /// Returns the model of the device, e.g. `P110`.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Returns the type of the device, e.g. `SMART.TAPOPLUG`.
+    pub fn device_type(&self) -> &str {
+        &self.device_type
+    }
+
+    /// Returns the components reported by the device when connecting.
+    /// Empty if the device does not support component negotiation.
+    /// Use [`ComponentListExt::get_component_list`] to read them again.
+    pub fn components(&self) -> &ComponentListResult {
+        &self.components
+    }
+
+    /// Returns the capabilities detected when connecting.
+    pub fn capabilities(&self) -> &[Capability] {
+        &self.capabilities
+    }
+
+    /// Returns whether the device supports the given capability.
+    pub fn supports(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+
+    /// Returns the device as [`OnOffView`] if it can be turned *on* and *off*.
+    pub fn as_on_off(&self) -> Option<OnOffView<'_>> {
+        self.view(Capability::OnOff, OnOffView)
+    }
+
+    /// Returns the device as [`EnergyMonitoringView`] if it monitors energy.
+    pub fn as_energy_monitoring(&self) -> Option<EnergyMonitoringView<'_>> {
+        self.view(Capability::EnergyMonitoring, EnergyMonitoringView)
+    }
+
+    /// Returns the device as [`CountdownView`] if it has a countdown timer.
+    pub fn as_countdown(&self) -> Option<CountdownView<'_>> {
+        self.view(Capability::Countdown, CountdownView)
+    }
+
+    /// Returns the device as [`ScheduleView`] if it runs schedules.
+    pub fn as_schedule(&self) -> Option<ScheduleView<'_>> {
+        self.view(Capability::Schedule, ScheduleView)
+    }
+
+    /// Returns the device as [`BrightnessView`] if it is dimmable.
+    pub fn as_brightness(&self) -> Option<BrightnessView<'_>> {
+        self.view(Capability::Brightness, BrightnessView)
+    }
+
+    /// Returns the device as [`ChildDevicesView`] if it has child devices.
+    pub fn as_child_devices(&self) -> Option<ChildDevicesView<'_>> {
+        self.view(Capability::ChildDevices, ChildDevicesView)
+    }
+
+    fn view<'a, V>(&'a self, capability: Capability, view: fn(&'a AnyDevice) -> V) -> Option<V> {
+        self.supports(capability).then(|| view(self))
+    }
+
+    /// Returns *device info* as [`serde_json::Value`].
+    /// It contains all the properties returned from the Tapo API.
+    pub async fn get_device_info_json(&self) -> Result<serde_json::Value, Error> {
+        self.client.read().await.get_device_info().await
+    }
+}
+
+// Components reported by `component_nego` that map to a capability.
+const COMPONENT_CAPABILITIES: [(&str, Capability); 6] = [
+    ("energy_monitoring", Capability::EnergyMonitoring),
+    ("countdown", Capability::Countdown),
+    ("schedule", Capability::Schedule),
+    ("brightness", Capability::Brightness),
+    ("child_device", Capability::ChildDevices),
+    ("control_child", Capability::ChildDevices),
+];
+
+fn detect_capabilities(
+    device_info: &serde_json::Value,
+    device_type: &str,
+    model: &str,
+    components: &ComponentListResult,
+) -> Vec<Capability> {
+    let mut capabilities = Vec::new();
+
+    if device_info["device_on"].is_boolean() {
+        capabilities.push(Capability::OnOff);
+    }
+
+    if components.components.is_empty() {
+        // Older firmware without component negotiation, fall back to what the model is known to support.
+        let model_capabilities: &[Capability] = match device_type {
+            "SMART.TAPOPLUG" if model.starts_with("P110") || model.starts_with("P115") => &[
+                Capability::EnergyMonitoring,
+                Capability::Countdown,
+                Capability::Schedule,
+            ],
+            "SMART.TAPOPLUG" => &[Capability::Countdown, Capability::Schedule],
+            "SMART.TAPOBULB" => &[Capability::Brightness],
+            "SMART.TAPOHUB" => &[Capability::ChildDevices],
+            _ => &[],
+        };
+        capabilities.extend_from_slice(model_capabilities);
+    } else {
+        for (component, capability) in COMPONENT_CAPABILITIES {
+            if components.supports(component) && !capabilities.contains(&capability) {
+                capabilities.push(capability);
+            }
+        }
+    }
+
+    capabilities
+}
+
+#[async_trait]
+impl HandlerExt for AnyDevice {
+    async fn get_client(&self) -> RwLockReadGuard<'_, dyn ApiClientExt> {
+        RwLockReadGuard::map(
+            self.client.read().await,
+            |client: &ApiClient| -> &dyn ApiClientExt { client },
+        )
+    }
+}
+
+impl DeviceManagementExt for AnyDevice {}
+
+impl DeviceInfoExt for AnyDevice {}
+
+impl DeviceTimeExt for AnyDevice {}
+
+impl FirmwareExt for AnyDevice {}
+
+impl ComponentListExt for AnyDevice {}
+
+// Defines a view of an `AnyDevice` that implements a single capability trait,
+// so the trait is only reachable once the capability was detected.
+macro_rules! capability_view {
+    ($view:ident, $ext:ident, $as_view:ident) => {
+        #[doc = concat!("An [`AnyDevice`] that supports [`", stringify!($ext), "`], returned by [`AnyDevice::", stringify!($as_view), "`].")]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $view<'a>(&'a AnyDevice);
+
+        #[async_trait]
+        impl HandlerExt for $view<'_> {
+            async fn get_client(&self) -> RwLockReadGuard<'_, dyn ApiClientExt> {
+                self.0.get_client().await
+            }
+        }
+
+        impl $ext for $view<'_> {}
+    };
+}
+
+capability_view!(OnOffView, OnOffExt, as_on_off);
+capability_view!(
+    EnergyMonitoringView,
+    EnergyMonitoringExt,
+    as_energy_monitoring
+);
+capability_view!(CountdownView, CountdownExt, as_countdown);
+capability_view!(ScheduleView, ScheduleExt, as_schedule);
+capability_view!(BrightnessView, BrightnessExt, as_brightness);
+capability_view!(ChildDevicesView, ChildDevicesExt, as_child_devices);
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::api::protocol::SimulatedDevice;
+
+    use super::*;
+
+    fn components(ids: &[&str]) -> ComponentListResult {
+        ComponentListResult {
+            components: ids.iter().map(|id| (id.to_string(), 1)).collect(),
+        }
+    }
+
+    #[test]
+    fn test_detect_capabilities_from_components() {
+        let device_info = json!({"device_on": true});
+
+        assert_eq!(
+            detect_capabilities(
+                &device_info,
+                "SMART.TAPOPLUG",
+                "P100",
+                &components(&["device", "energy_monitoring", "countdown", "led"])
+            ),
+            [
+                Capability::OnOff,
+                Capability::EnergyMonitoring,
+                Capability::Countdown
+            ]
+        );
+        assert_eq!(
+            detect_capabilities(
+                &json!({}),
+                "SMART.TAPOHUB",
+                "H100",
+                &components(&["child_device", "control_child"])
+            ),
+            [Capability::ChildDevices]
+        );
+    }
+
+    #[test]
+    fn test_detect_capabilities_from_model() {
+        assert_eq!(
+            detect_capabilities(
+                &json!({"device_on": false}),
+                "SMART.TAPOBULB",
+                "L510",
+                &components(&[])
+            ),
+            [Capability::OnOff, Capability::Brightness]
+        );
+        assert_eq!(
+            detect_capabilities(&json!({}), "SMART.TAPOSENSOR", "T310", &components(&[])),
+            []
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect() {
+        let device = Arc::new(SimulatedDevice::new("username", "password").with_responder(
+            |request| match request["method"].as_str().unwrap() {
+                "get_device_info" => json!({
+                    "error_code": 0,
+                    "result": {"model": "P110", "type": "SMART.TAPOPLUG", "device_on": true},
+                }),
+                "component_nego" => json!({
+                    "error_code": 0,
+                    "result": {"component_list": [
+                        {"id": "device", "ver_code": 2},
+                        {"id": "energy_monitoring", "ver_code": 2},
+                        {"id": "schedule", "ver_code": 3},
+                    ]},
+                }),
+                "set_device_info" => json!({"error_code": 0}),
+                method => panic!("Unexpected method {method}"),
+            },
+        ));
+
+        let any_device = ApiClient::new("username", "password")
+            .with_transport(device.clone())
+            .connect("192.168.1.100")
+            .await
+            .unwrap();
+
+        assert_eq!(any_device.model(), "P110");
+        assert_eq!(any_device.device_type(), "SMART.TAPOPLUG");
+        assert!(any_device.as_on_off().is_some());
+        assert!(any_device.as_energy_monitoring().is_some());
+        assert!(any_device.as_schedule().is_some());
+        assert!(any_device.as_countdown().is_none());
+        assert!(any_device.as_brightness().is_none());
+        assert_eq!(any_device.components().version("schedule"), Some(3));
+
+        any_device.as_on_off().unwrap().off().await.unwrap();
+        let requests = device.requests.lock().unwrap().clone();
+        assert_eq!(
+            requests.last().unwrap()["params"]["device_on"],
+            json!(false)
+        );
+    }
+}
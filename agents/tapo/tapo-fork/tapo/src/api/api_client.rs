@@ -1,36 +1,55 @@
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
 use log::debug;
-use reqwest::Client;
 use serde::de::DeserializeOwned;
 use tokio::sync::RwLock;
 
 use crate::error::{Error, TapoResponseError};
 use crate::requests::{
-    AddCountdownRuleParams, ControlChildParams, DeviceRebootParams, EditCountdownRuleParams,
+    AddCountdownRuleParams, AntitheftRuleParams, BatchRead, BatchReadParams, ChildDeviceListParams,
+    ControlChildParams, DeviceRebootParams, EditAntitheftRuleParams, EditCountdownRuleParams,
     EmptyParams, EnergyDataInterval, GetChildDeviceListParams, GetEnergyDataParams,
-    GetPowerDataParams, GetRulesParams, LightingEffect, MultipleRequestParams, PlayAlarmParams,
-    PowerDataInterval, TapoParams, TapoRequest,
+    GetPowerDataParams, GetRulesParams, GetWirelessScanInfoParams, LightingEffect,
+    MultipleRequestParams, PlayAlarmParams, PowerDataInterval, QsTime, RemoveRulesParams,
+    SetProtectionPowerParams, SetQsInfoParams, TapoParams, TapoRequest,
 };
 use crate::responses::{
-    ControlChildResult, CountdownRulesResult, CurrentPowerResult, DecodableResultExt,
-    EnergyDataResult, EnergyDataResultRaw, EnergyUsageResult, PowerDataResult,
-    PowerDataResultRaw, ScheduleRulesResult, SupportedAlarmTypeListResult, TapoMultipleResponse,
-    TapoResponseExt, TapoResult, validate_response,
+    AntitheftRulesResult, AutoOffConfigResult, BatchReadResult, ChildComponentListResult,
+    ChildDeviceScanResult, ComponentListResult, ControlChildResult, CountdownRulesResult,
+    CurrentPowerResult, DecodableResultExt, DeviceTimeResult, EmeterDataResult, EnergyDataResult,
+    EnergyDataResultRaw, EnergyUsageResult, FirmwareDownloadStateResult, LatestFirmwareResult,
+    LedInfoResult, MaxPowerResult, NextEventResult, PowerDataResult, PowerDataResultRaw,
+    PowerProtectionResult, ScheduleRulesResult, SupportedAlarmTypeListResult,
+    TapoMultipleMethodResult, TapoMultipleResponse, TapoResponseExt, TapoResult, WirelessNetwork,
+    WirelessScanInfoResult, validate_response,
 };
 
+#[cfg(doc)]
+use super::discovery::AnnouncementDiscovery;
 use super::discovery::DeviceDiscovery;
-use super::protocol::{TapoProtocol, TapoProtocolExt};
+use super::protocol::{SessionOptions, TapoProtocol, TapoProtocolExt};
 use super::{
-    ColorLightHandler, GenericDeviceHandler, HubHandler, LightHandler, PlugEnergyMonitoringHandler,
-    PlugHandler, PowerStripEnergyMonitoringHandler, PowerStripHandler, RgbLightStripHandler,
-    RgbicLightStripHandler,
+    AnyDevice, ColorLightHandler, GenericDeviceHandler, HttpTransport, HubHandler, LightHandler,
+    PlugEnergyMonitoringHandler, PlugHandler, PowerStripEnergyMonitoringHandler, PowerStripHandler,
+    RetryPolicy, RgbLightStripHandler, RgbicLightStripHandler, SetupModeHandler, Transport,
 };
 
 const TERMINAL_UUID: &str = "00-00-00-00-00-00";
+// The credentials that a factory-reset device in setup mode accepts.
+const SETUP_MODE_USERNAME: &str = "test@tp-link.net";
+const SETUP_MODE_PASSWORD: &str = "test";
+// Some firmware rejects `multipleRequest` batches with more requests than this.
+const MULTIPLE_REQUEST_BATCH_SIZE: usize = 5;
+
+fn parse_batch_response<T: DeserializeOwned>(
+    response: Result<serde_json::Value, Error>,
+) -> Result<T, Error> {
+    Ok(serde_json::from_value(response?)?)
+}
 
 /// Implemented by all ApiClient implementations.
 #[async_trait]
@@ -41,6 +60,54 @@ pub trait ApiClientExt: std::fmt::Debug + Send + Sync {
     async fn device_reboot(&self, delay_s: u16) -> Result<(), Error>;
     /// Hardware resets the device.
     async fn device_reset(&self) -> Result<(), Error>;
+    /// Gets the status LED settings.
+    async fn get_led_info(&self) -> Result<LedInfoResult, Error>;
+    /// Sets the status LED settings by sending the given parameters.
+    async fn set_led_info(&self, led_info_params: serde_json::Value) -> Result<(), Error>;
+    /// Gets the auto-off settings.
+    async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error>;
+    /// Sets the auto-off settings by sending the given parameters.
+    async fn set_auto_off_config(&self, auto_off_params: serde_json::Value) -> Result<(), Error>;
+    /// Gets the device time.
+    async fn get_device_time(&self) -> Result<DeviceTimeResult, Error>;
+    /// Sets the device time by sending the given parameters.
+    async fn set_device_time(&self, device_time_params: serde_json::Value) -> Result<(), Error>;
+    /// Gets the latest firmware available.
+    async fn get_latest_firmware(&self) -> Result<LatestFirmwareResult, Error>;
+    /// Starts the firmware update.
+    async fn firmware_download(&self) -> Result<(), Error>;
+    /// Gets the progress of the firmware update.
+    async fn get_firmware_download_state(&self) -> Result<FirmwareDownloadStateResult, Error>;
+    /// Gets the current power.
+    async fn get_current_power(&self) -> Result<CurrentPowerResult, Error>;
+    /// Gets the energy usage.
+    async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error>;
+    /// Gets the energy data for the given interval.
+    async fn get_energy_data(
+        &self,
+        interval: EnergyDataInterval,
+    ) -> Result<EnergyDataResult, Error>;
+    /// Gets the power data for the given interval.
+    async fn get_power_data(&self, interval: PowerDataInterval) -> Result<PowerDataResult, Error>;
+    /// Gets the real-time voltage, current and power.
+    async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error>;
+    /// Gets the countdown rules.
+    async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error>;
+    /// Adds a countdown rule, or replaces the existing one.
+    async fn add_countdown_rule(&self, delay: u64, turn_on: bool) -> Result<(), Error>;
+    /// Gets the schedule rules.
+    async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error>;
+    /// Gets the rule that fires next.
+    async fn get_next_event(&self) -> Result<NextEventResult, Error>;
+    /// Gets a page of the child device list.
+    async fn get_child_device_list_json(
+        &self,
+        start_index: u64,
+    ) -> Result<serde_json::Value, Error>;
+    /// Gets the child device component list.
+    async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error>;
+    /// Gets the components supported by the device.
+    async fn get_component_list(&self) -> Result<ComponentListResult, Error>;
 }
 
 /// Tapo API Client. See [examples](https://github.com/mihai-dinculescu/tapo/tree/main/tapo/examples).
@@ -48,7 +115,7 @@ pub trait ApiClientExt: std::fmt::Debug + Send + Sync {
 /// # Example
 ///
 /// ```rust,no_run
-/// use tapo::ApiClient;
+/// use tapo::{ApiClient, OnOffExt};
 ///
 /// #[tokio::main]
 /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -66,6 +133,11 @@ pub struct ApiClient {
     tapo_username: String,
     tapo_password: String,
     timeout: Option<Duration>,
+    session_options: SessionOptions,
+    retry_policy: Option<RetryPolicy>,
+    transport: Option<Arc<dyn Transport>>,
+    port: Option<u16>,
+    base_url: Option<String>,
     protocol: Option<TapoProtocol>,
 }
 
@@ -73,7 +145,7 @@ pub struct ApiClient {
 impl ApiClient {
     /// Returns a new instance of [`ApiClient`].
     /// It is cheaper to [`ApiClient::clone`] an existing instance than to create a new one when multiple devices need to be controller.
-    /// This is because [`ApiClient::clone`] reuses the underlying [`reqwest::Client`].
+    /// This is because [`ApiClient::clone`] reuses the underlying [`Transport`].
     ///
     /// # Arguments
     ///
@@ -82,16 +154,29 @@ impl ApiClient {
     ///
     /// Note: The default connection timeout is 30 seconds.
     /// Use [`ApiClient::with_timeout`] to change it.
+    ///
+    /// Note: The authentication session is refreshed automatically 20 minutes before it expires,
+    /// and a request that fails because the session has timed out is retried once after logging in again.
+    /// Use [`ApiClient::with_session_refresh`] and [`ApiClient::with_session_refresh_margin`] to change this behaviour.
+    ///
+    /// Note: Failed requests are not retried by default.
+    /// Use [`ApiClient::with_retry_policy`] to enable retries.
     pub fn new(tapo_username: impl Into<String>, tapo_password: impl Into<String>) -> ApiClient {
         Self {
             tapo_username: tapo_username.into(),
             tapo_password: tapo_password.into(),
             timeout: None,
+            session_options: SessionOptions::default(),
+            retry_policy: None,
+            transport: None,
+            port: None,
+            base_url: None,
             protocol: None,
         }
     }
 
     /// Changes the connection timeout from the default value to the given value.
+    /// Only applies to the default [`HttpTransport`].
     ///
     /// # Arguments
     ///
@@ -101,7 +186,80 @@ impl ApiClient {
         self
     }
 
+    /// Enables or disables the automatic refresh of the authentication session.
+    ///
+    /// When enabled, the session is refreshed shortly before it expires and
+    /// a request that fails with [`TapoResponseError::SessionTimeout`] is retried once after logging in again.
+    /// When disabled, the session has to be refreshed manually by calling `refresh_session` on the device handler.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - whether the session should be refreshed automatically.
+    pub fn with_session_refresh(mut self, enabled: bool) -> ApiClient {
+        self.session_options.auto_refresh = enabled;
+        self
+    }
+
+    /// Changes how long before the session expiry the session is refreshed.
+    /// Only applies when the automatic session refresh is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `margin` - The time before the session expiry at which it is refreshed.
+    pub fn with_session_refresh_margin(mut self, margin: Duration) -> ApiClient {
+        self.session_options.refresh_margin = margin;
+        self
+    }
+
+    /// Retries idempotent requests that fail with a transient error according to the given policy.
+    /// Requests that must not be repeated, such as `device_reboot` and `device_reset`, are never retried.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - the number of attempts, backoff and retryable errors. See [`RetryPolicy`].
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> ApiClient {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Sends the requests through the given [`Transport`] instead of the default [`HttpTransport`].
+    /// Can be used to talk to a simulated device in tests, or to trace and record the requests
+    /// with [`HookTransport`] and [`RecordingTransport`].
+    ///
+    /// # Arguments
+    ///
+    /// * `transport` - the transport used by all the devices created from this [`ApiClient`].
+    pub fn with_transport(mut self, transport: impl Transport + 'static) -> ApiClient {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
+    /// Changes the port the device is reached at from the default HTTP port,
+    /// e.g. for devices behind a port forward.
+    ///
+    /// # Arguments
+    ///
+    /// * `port` - the port of the device.
+    pub fn with_port(mut self, port: u16) -> ApiClient {
+        self.port = Some(port);
+        self
+    }
+
+    /// Reaches the device at the given URL instead of `http://{ip_address}/app`,
+    /// e.g. for a simulator running at `http://localhost:8080/app`.
+    /// Takes precedence over [`ApiClient::with_port`].
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - the URL of the `app` endpoint of the device.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> ApiClient {
+        self.base_url = Some(base_url.into().trim_end_matches('/').to_string());
+        self
+    }
+
     /// Discovers one or more devices located at a specified unicast or broadcast IP address.
+    /// Every device that responds is logged into with the credentials of this client;
+    /// use [`AnnouncementDiscovery`] to list the devices without logging in.
     ///
     /// # Arguments
     /// * `target` - The IP address at which the discovery will take place.
@@ -136,7 +294,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -164,7 +322,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -189,7 +347,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -214,7 +372,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -239,7 +397,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -264,7 +422,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -289,7 +447,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -314,7 +472,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -342,7 +500,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -370,7 +528,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -398,7 +556,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -423,7 +581,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -448,7 +606,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -478,7 +636,7 @@ impl ApiClient {
     /// # Example
     ///
     /// ```rust,no_run
-    /// # use tapo::ApiClient;
+    /// # use tapo::{ApiClient, OnOffExt};
     /// # #[tokio::main]
     /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
@@ -639,12 +797,102 @@ impl ApiClient {
 
         Ok(HubHandler::new(Arc::new(RwLock::new(self))))
     }
+
+    /// Connects to a device of any model and returns an [`AnyDevice`], whose capabilities
+    /// are detected from the device info and the components reported by the device.
+    /// Useful when the model is not known in advance, or to write code that works across models.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip_address` - the IP address of the device
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use tapo::{ApiClient, OnOffExt};
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+    ///     .connect("192.168.1.100")
+    ///     .await?;
+    ///
+    /// if let Some(on_off) = device.as_on_off() {
+    ///     on_off.on().await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn connect(mut self, ip_address: impl Into<String>) -> Result<AnyDevice, Error> {
+        self.login(ip_address).await?;
+
+        let device_info: serde_json::Value = self.get_device_info().await?;
+        let components = match self.get_component_list().await {
+            Ok(components) => components,
+            Err(e) => {
+                debug!("Component negotiation failed, detecting capabilities from the model: {e}");
+                ComponentListResult::default()
+            }
+        };
+
+        Ok(AnyDevice::new(
+            Arc::new(RwLock::new(self)),
+            &device_info,
+            components,
+        ))
+    }
+
+    /// Connects to a factory-reset device in setup mode and returns a [`SetupModeHandler`]
+    /// that can onboard it without the Tapo app.
+    ///
+    /// A device in setup mode broadcasts its own Wi-Fi network (e.g. `Tapo_Plug_XXXX`)
+    /// and only accepts its default credentials, which are used to log in.
+    /// The credentials of the [`ApiClient`] are the ones bound to the device when onboarding.
+    ///
+    /// # Arguments
+    ///
+    /// * `ip_address` - the IP address of the device, usually `192.168.0.1`
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use tapo::ApiClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+    ///     .setup_mode("192.168.0.1")
+    ///     .await?;
+    ///
+    /// let networks = device.scan_wifi().await?;
+    /// println!("Networks: {networks:?}");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn setup_mode(
+        self,
+        ip_address: impl Into<String>,
+    ) -> Result<SetupModeHandler, Error> {
+        let tapo_username = self.tapo_username.clone();
+        let tapo_password = self.tapo_password.clone();
+
+        let mut client = ApiClient {
+            tapo_username: SETUP_MODE_USERNAME.to_string(),
+            tapo_password: SETUP_MODE_PASSWORD.to_string(),
+            ..self
+        };
+        client.login(ip_address).await?;
+
+        Ok(SetupModeHandler::new(
+            Arc::new(RwLock::new(client)),
+            tapo_username,
+            tapo_password,
+        ))
+    }
 }
 
 /// Tapo API Client private methods.
 impl ApiClient {
     pub(crate) async fn login(&mut self, ip_address: impl Into<String>) -> Result<(), Error> {
-        let url = format!("http://{}/app", ip_address.into());
+        let url = self.get_url(&ip_address.into());
         debug!("Device url: {url}");
 
         let tapo_username = self.tapo_username.clone();
@@ -695,6 +943,67 @@ impl ApiClient {
         Ok(())
     }
 
+    pub(crate) async fn get_wireless_scan_info(&self) -> Result<Vec<WirelessNetwork>, Error> {
+        debug!("Get Wireless scan info...");
+        let mut networks = Vec::new();
+
+        loop {
+            let request =
+                TapoRequest::GetWirelessScanInfo(TapoParams::new(GetWirelessScanInfoParams {
+                    start_index: networks.len(),
+                }));
+
+            let result = self
+                .get_protocol()?
+                .execute_request::<WirelessScanInfoResult>(request, true)
+                .await?
+                .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))?
+                .decode()?;
+
+            let page_is_empty = result.ap_list.is_empty();
+            networks.extend(result.ap_list);
+
+            if page_is_empty || networks.len() >= result.sum {
+                break;
+            }
+        }
+
+        Ok(networks)
+    }
+
+    pub(crate) async fn set_qs_info(
+        &self,
+        tapo_username: &str,
+        tapo_password: &str,
+        ssid: &str,
+        wifi_password: &str,
+        key_type: &str,
+        time: QsTime,
+    ) -> Result<(), Error> {
+        debug!("Set QS info...");
+        let params = SetQsInfoParams::new(
+            tapo_username,
+            tapo_password,
+            ssid,
+            wifi_password,
+            key_type,
+            time,
+        )?;
+        let request = TapoRequest::SetQsInfo(Box::new(TapoParams::new(params)));
+
+        match self
+            .get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await
+        {
+            // The device leaves setup mode to join the network, which can drop the connection
+            // before the response is sent. Failing to reach the device means nothing was sent.
+            Ok(_) => Ok(()),
+            Err(Error::Http(e)) if !e.is_connect() && !e.is_builder() => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
     pub(crate) async fn get_device_info<R>(&self) -> Result<R, Error>
     where
         R: fmt::Debug + DeserializeOwned + TapoResponseExt + DecodableResultExt,
@@ -741,19 +1050,9 @@ impl ApiClient {
         Ok(())
     }
 
-    pub(crate) async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
-        debug!("Get Energy usage...");
-        let request = TapoRequest::GetEnergyUsage(TapoParams::new(EmptyParams));
-
-        self.get_protocol()?
-            .execute_request(request, true)
-            .await?
-            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
-    }
-
-    pub(crate) async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
-        debug!("Get Current power...");
-        let request = TapoRequest::GetCurrentPower(TapoParams::new(EmptyParams));
+    pub(crate) async fn get_power_protection(&self) -> Result<PowerProtectionResult, Error> {
+        debug!("Get Power protection...");
+        let request = TapoRequest::GetProtectionPower(TapoParams::new(EmptyParams));
 
         self.get_protocol()?
             .execute_request(request, true)
@@ -761,34 +1060,30 @@ impl ApiClient {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    pub(crate) async fn get_energy_data(
+    pub(crate) async fn set_power_protection(
         &self,
-        interval: EnergyDataInterval,
-    ) -> Result<EnergyDataResult, Error> {
-        debug!("Get Energy data...");
-        let params = GetEnergyDataParams::new(interval);
-        let request = TapoRequest::GetEnergyData(TapoParams::new(params));
+        enabled: bool,
+        protection_power: u16,
+    ) -> Result<(), Error> {
+        debug!("Set Power protection...");
+        let params = SetProtectionPowerParams::new(enabled, protection_power)?;
+        let request = TapoRequest::SetProtectionPower(TapoParams::new(params));
 
         self.get_protocol()?
-            .execute_request::<EnergyDataResultRaw>(request, true)
-            .await?
-            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
-            .map(|result| result.try_into())?
+            .execute_request::<TapoResult>(request, true)
+            .await?;
+
+        Ok(())
     }
 
-    pub(crate) async fn get_power_data(
-        &self,
-        interval: PowerDataInterval,
-    ) -> Result<PowerDataResult, Error> {
-        debug!("Get Power data...");
-        let params = GetPowerDataParams::new(interval);
-        let request = TapoRequest::GetPowerData(TapoParams::new(params));
+    pub(crate) async fn get_max_power(&self) -> Result<MaxPowerResult, Error> {
+        debug!("Get Max power...");
+        let request = TapoRequest::GetMaxPower(TapoParams::new(EmptyParams));
 
         self.get_protocol()?
-            .execute_request::<PowerDataResultRaw>(request, true)
+            .execute_request(request, true)
             .await?
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
-            .map(|result| result.try_into())?
     }
 
     pub(crate) async fn get_child_device_list<R>(&self, start_index: u64) -> Result<R, Error>
@@ -821,6 +1116,94 @@ impl ApiClient {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))?
     }
 
+    pub(crate) async fn get_child_component_list(
+        &self,
+        device_id: &str,
+    ) -> Result<ComponentListResult, Error> {
+        self.get_child_device_component_list::<ChildComponentListResult>()
+            .await?
+            .child_component_list
+            .into_iter()
+            .find(|child| child.device_id == device_id)
+            .map(|child| child.components)
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    pub(crate) async fn begin_scanning_child_device(&self) -> Result<(), Error> {
+        debug!("Begin scanning child devices...");
+        let request = TapoRequest::BeginScanningChildDevice(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn get_scan_child_device_list(&self) -> Result<ChildDeviceScanResult, Error> {
+        debug!("Get scanned child device list...");
+        let request = TapoRequest::GetScanChildDeviceList(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request::<ChildDeviceScanResult>(request, true)
+            .await?
+            .map(|result| result.decode())
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))?
+    }
+
+    pub(crate) async fn end_scanning_child_device(&self) -> Result<(), Error> {
+        debug!("End scanning child devices...");
+        let request = TapoRequest::EndScanningChildDevice(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn add_child_device_list(
+        &self,
+        params: ChildDeviceListParams,
+    ) -> Result<(), Error> {
+        debug!("Add child device list...");
+        let request = TapoRequest::AddChildDeviceList(Box::new(TapoParams::new(params)));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn remove_child_device_list(
+        &self,
+        params: ChildDeviceListParams,
+    ) -> Result<(), Error> {
+        debug!("Remove child device list...");
+        let request = TapoRequest::RemoveChildDeviceList(Box::new(TapoParams::new(params)));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn set_child_device_info(
+        &self,
+        device_id: &str,
+        json: serde_json::Value,
+    ) -> Result<(), Error> {
+        debug!("Set child device info...");
+        let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
+
+        self.control_child::<serde_json::Value>(device_id.to_string(), request)
+            .await?;
+
+        Ok(())
+    }
+
     pub(crate) async fn control_child<R>(
         &self,
         device_id: String,
@@ -855,10 +1238,129 @@ impl ApiClient {
         Ok(response.result)
     }
 
-    /// Gets countdown timer rules.
-    pub(crate) async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
-        debug!("Get Countdown rules...");
-        let request = TapoRequest::GetCountdownRules(TapoParams::new(GetRulesParams::default()));
+    pub(crate) async fn get_batch<I, U>(
+        &self,
+        params: BatchReadParams,
+    ) -> Result<BatchReadResult<I, U>, Error>
+    where
+        I: fmt::Debug + DeserializeOwned + TapoResponseExt + DecodableResultExt,
+        U: fmt::Debug + DeserializeOwned + TapoResponseExt,
+    {
+        debug!("Get Batch...");
+        let reads = params.reads();
+        let requests = reads.iter().map(|read| read.to_request()).collect();
+        let responses = self.execute_multiple_requests(requests).await?;
+
+        let mut result = BatchReadResult::default();
+
+        for (read, response) in reads.into_iter().zip(responses) {
+            match read {
+                BatchRead::DeviceInfo => {
+                    result.device_info = Some(parse_batch_response(response).and_then(I::decode))
+                }
+                BatchRead::DeviceUsage => {
+                    result.device_usage = Some(parse_batch_response(response))
+                }
+                BatchRead::CurrentPower => {
+                    result.current_power = Some(parse_batch_response(response))
+                }
+                BatchRead::EnergyUsage => {
+                    result.energy_usage = Some(parse_batch_response(response))
+                }
+                BatchRead::EmeterData => result.emeter_data = Some(parse_batch_response(response)),
+                BatchRead::CountdownRules => {
+                    result.countdown_rules = Some(parse_batch_response(response))
+                }
+                BatchRead::ScheduleRules => {
+                    result.schedule_rules = Some(parse_batch_response(response))
+                }
+                BatchRead::NextEvent => result.next_event = Some(parse_batch_response(response)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Sends the requests in as few `multipleRequest` round trips as possible and
+    /// returns the result of each request, in order.
+    /// The responses are matched to the requests by their `method`, so each request must use a different method.
+    /// Falls back to sending the requests one by one if the device rejects `multipleRequest`.
+    pub(crate) async fn execute_multiple_requests(
+        &self,
+        requests: Vec<TapoRequest>,
+    ) -> Result<Vec<Result<serde_json::Value, Error>>, Error> {
+        let mut results = Vec::with_capacity(requests.len());
+
+        for chunk in requests.chunks(MULTIPLE_REQUEST_BATCH_SIZE) {
+            let params = MultipleRequestParams::new(chunk.to_vec());
+            let request = TapoRequest::MultipleRequest(Box::new(TapoParams::new(params)));
+
+            let responses = match self
+                .get_protocol()?
+                .execute_request::<TapoMultipleMethodResult<serde_json::Value>>(request, true)
+                .await
+            {
+                Ok(Some(result)) => result.responses,
+                Ok(None) => return Err(Error::Tapo(TapoResponseError::InvalidResponse)),
+                Err(Error::Tapo(
+                    TapoResponseError::InvalidRequest
+                    | TapoResponseError::MalformedRequest
+                    | TapoResponseError::UnsupportedMethod,
+                )) => {
+                    debug!(
+                        "The device rejected multipleRequest, sending the requests one by one..."
+                    );
+                    for request in chunk {
+                        let result = self
+                            .get_protocol()?
+                            .execute_request::<serde_json::Value>(request.clone(), true)
+                            .await
+                            .and_then(|result| {
+                                result.ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+                            });
+                        results.push(result);
+                    }
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
+
+            let mut responses_by_method = HashMap::with_capacity(responses.len());
+            for response in responses {
+                if responses_by_method
+                    .insert(response.method.clone(), response.response)
+                    .is_some()
+                {
+                    debug!(
+                        "Duplicate response for {} in multipleRequest",
+                        response.method
+                    );
+                    return Err(Error::Tapo(TapoResponseError::InvalidResponse));
+                }
+            }
+
+            for request in chunk {
+                let method = request.method()?;
+                let Some(response) = responses_by_method.remove(&method) else {
+                    debug!("No response for {method} in multipleRequest");
+                    return Err(Error::Tapo(TapoResponseError::InvalidResponse));
+                };
+
+                results.push(validate_response(&response).and_then(|_| {
+                    response
+                        .result
+                        .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+                }));
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Gets anti-theft rules.
+    pub(crate) async fn get_antitheft_rules(&self) -> Result<AntitheftRulesResult, Error> {
+        debug!("Get Anti-theft rules...");
+        let request = TapoRequest::GetAntitheftRules(TapoParams::new(GetRulesParams::default()));
 
         self.get_protocol()?
             .execute_request(request, true)
@@ -866,40 +1368,45 @@ impl ApiClient {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Gets schedule rules.
-    pub(crate) async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
-        debug!("Get Schedule rules...");
-        let request = TapoRequest::GetScheduleRules(TapoParams::new(GetRulesParams::default()));
+    /// Adds an anti-theft rule.
+    pub(crate) async fn add_antitheft_rule(
+        &self,
+        params: AntitheftRuleParams,
+    ) -> Result<(), Error> {
+        params.validate()?;
+        debug!("Add Anti-theft rule: {params:?}");
+        let request = TapoRequest::AddAntitheftRule(TapoParams::new(params));
 
         self.get_protocol()?
-            .execute_request(request, true)
-            .await?
-            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+        Ok(())
     }
-    /// Adds or updates a countdown rule.
-    pub(crate) async fn add_countdown_rule(&self, delay: u64, turn_on: bool) -> Result<(), Error> {
-        // Check if a countdown rule already exists
-        let existing = self.get_countdown_rules().await.ok();
-        
-        if let Some(countdown) = existing {
-            if let Some(rule) = countdown.rules.first() {
-                // Edit existing rule
-                debug!("Edit Countdown rule: id={}, delay={}, turn_on={}", rule.id, delay, turn_on);
-                let request = TapoRequest::EditCountdownRule(TapoParams::new(
-                    EditCountdownRuleParams::new(rule.id.clone(), delay, turn_on),
-                ));
-                self.get_protocol()?
-                    .execute_request::<serde_json::Value>(request, true)
-                    .await?;
-                return Ok(());
-            }
-        }
-        
-        // No existing rule, add new one
-        debug!("Add Countdown rule: delay={}, turn_on={}", delay, turn_on);
-        let request = TapoRequest::AddCountdownRule(TapoParams::new(
-            AddCountdownRuleParams::new(delay, turn_on),
-        ));
+
+    /// Replaces an existing anti-theft rule.
+    pub(crate) async fn edit_antitheft_rule(
+        &self,
+        id: String,
+        params: AntitheftRuleParams,
+    ) -> Result<(), Error> {
+        params.validate()?;
+        debug!("Edit Anti-theft rule: id={id}, {params:?}");
+        let request = TapoRequest::EditAntitheftRule(TapoParams::new(EditAntitheftRuleParams {
+            id,
+            rule: params,
+        }));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes an anti-theft rule.
+    pub(crate) async fn remove_antitheft_rule(&self, id: String) -> Result<(), Error> {
+        debug!("Remove Anti-theft rule: id={id}");
+        let request =
+            TapoRequest::RemoveAntitheftRules(TapoParams::new(RemoveRulesParams::new(id)));
 
         self.get_protocol()?
             .execute_request::<serde_json::Value>(request, true)
@@ -907,15 +1414,26 @@ impl ApiClient {
         Ok(())
     }
 
+    fn get_url(&self, ip_address: &str) -> String {
+        match (&self.base_url, self.port) {
+            (Some(base_url), _) => base_url.clone(),
+            (None, Some(port)) => format!("http://{ip_address}:{port}/app"),
+            (None, None) => format!("http://{ip_address}/app"),
+        }
+    }
+
     fn get_protocol_mut(&mut self) -> Result<&mut TapoProtocol, Error> {
         if self.protocol.is_none() {
-            let timeout = self.timeout.unwrap_or_else(|| Duration::from_secs(30));
-
-            let client = Client::builder()
-                .http1_title_case_headers()
-                .timeout(timeout)
-                .build()?;
-            let protocol = TapoProtocol::new(client);
+            let transport = match &self.transport {
+                Some(transport) => transport.clone(),
+                None => {
+                    let timeout = self.timeout.unwrap_or_else(|| Duration::from_secs(30));
+                    Arc::new(HttpTransport::new(timeout)?)
+                }
+            };
+
+            let protocol =
+                TapoProtocol::new(transport, self.session_options, self.retry_policy.clone());
             self.protocol.replace(protocol);
         }
 
@@ -974,4 +1492,308 @@ impl ApiClientExt for ApiClient {
 
         Ok(())
     }
+
+    async fn get_led_info(&self) -> Result<LedInfoResult, Error> {
+        debug!("Get LED info...");
+        let request = TapoRequest::GetLedInfo(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn set_led_info(&self, led_info_params: serde_json::Value) -> Result<(), Error> {
+        debug!("LED info will change to: {led_info_params:?}");
+        let request = TapoRequest::SetLedInfo(Box::new(TapoParams::new(led_info_params)));
+
+        self.get_protocol()?
+            .execute_request::<TapoResult>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+        debug!("Get Auto-off config...");
+        let request = TapoRequest::GetAutoOffConfig(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn set_auto_off_config(&self, auto_off_params: serde_json::Value) -> Result<(), Error> {
+        debug!("Auto-off config will change to: {auto_off_params:?}");
+        let request = TapoRequest::SetAutoOffConfig(Box::new(TapoParams::new(auto_off_params)));
+
+        self.get_protocol()?
+            .execute_request::<TapoResult>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_device_time(&self) -> Result<DeviceTimeResult, Error> {
+        debug!("Get Device time...");
+        let request = TapoRequest::GetDeviceTime(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn set_device_time(&self, device_time_params: serde_json::Value) -> Result<(), Error> {
+        debug!("Device time will change to: {device_time_params:?}");
+        let request = TapoRequest::SetDeviceTime(Box::new(TapoParams::new(device_time_params)));
+
+        self.get_protocol()?
+            .execute_request::<TapoResult>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_latest_firmware(&self) -> Result<LatestFirmwareResult, Error> {
+        debug!("Get Latest firmware...");
+        let request = TapoRequest::GetLatestFirmware(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn firmware_download(&self) -> Result<(), Error> {
+        debug!("Firmware download...");
+        let request = TapoRequest::FirmwareDownload(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_firmware_download_state(&self) -> Result<FirmwareDownloadStateResult, Error> {
+        debug!("Get Firmware download state...");
+        let request = TapoRequest::GetFirmwareDownloadState(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
+        debug!("Get Energy usage...");
+        let request = TapoRequest::GetEnergyUsage(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
+        debug!("Get Current power...");
+        let request = TapoRequest::GetCurrentPower(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error> {
+        debug!("Get Emeter data...");
+        let request = TapoRequest::GetEmeterData(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_energy_data(
+        &self,
+        interval: EnergyDataInterval,
+    ) -> Result<EnergyDataResult, Error> {
+        debug!("Get Energy data...");
+        let params = GetEnergyDataParams::new(interval);
+        let request = TapoRequest::GetEnergyData(TapoParams::new(params));
+
+        self.get_protocol()?
+            .execute_request::<EnergyDataResultRaw>(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+            .map(|result| result.try_into())?
+    }
+
+    async fn get_power_data(&self, interval: PowerDataInterval) -> Result<PowerDataResult, Error> {
+        debug!("Get Power data...");
+        let params = GetPowerDataParams::new(interval);
+        let request = TapoRequest::GetPowerData(TapoParams::new(params));
+
+        self.get_protocol()?
+            .execute_request::<PowerDataResultRaw>(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+            .map(|result| result.try_into())?
+    }
+
+    async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
+        debug!("Get Countdown rules...");
+        let request = TapoRequest::GetCountdownRules(TapoParams::new(GetRulesParams::default()));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
+        debug!("Get Schedule rules...");
+        let request = TapoRequest::GetScheduleRules(TapoParams::new(GetRulesParams::default()));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn add_countdown_rule(&self, delay: u64, turn_on: bool) -> Result<(), Error> {
+        // Check if a countdown rule already exists
+        let existing = self.get_countdown_rules().await.ok();
+
+        if let Some(countdown) = existing
+            && let Some(rule) = countdown.rules.first()
+        {
+            // Edit existing rule
+            debug!(
+                "Edit Countdown rule: id={}, delay={}, turn_on={}",
+                rule.id, delay, turn_on
+            );
+            let request = TapoRequest::EditCountdownRule(TapoParams::new(
+                EditCountdownRuleParams::new(rule.id.clone(), delay, turn_on),
+            ));
+            self.get_protocol()?
+                .execute_request::<serde_json::Value>(request, true)
+                .await?;
+            return Ok(());
+        }
+
+        // No existing rule, add new one
+        debug!("Add Countdown rule: delay={}, turn_on={}", delay, turn_on);
+        let request = TapoRequest::AddCountdownRule(TapoParams::new(AddCountdownRuleParams::new(
+            delay, turn_on,
+        )));
+
+        self.get_protocol()?
+            .execute_request::<serde_json::Value>(request, true)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_next_event(&self) -> Result<NextEventResult, Error> {
+        debug!("Get Next event...");
+        let request = TapoRequest::GetNextEvent(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn get_child_device_list_json(
+        &self,
+        start_index: u64,
+    ) -> Result<serde_json::Value, Error> {
+        self.get_child_device_list(start_index).await
+    }
+
+    async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
+        self.get_child_device_component_list().await
+    }
+
+    async fn get_component_list(&self) -> Result<ComponentListResult, Error> {
+        debug!("Get Component list...");
+        let request = TapoRequest::ComponentNegotiation(TapoParams::new(EmptyParams));
+
+        self.get_protocol()?
+            .execute_request(request, true)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::api::protocol::SimulatedDevice;
+
+    use super::*;
+
+    async fn multiple_requests(
+        responses: serde_json::Value,
+    ) -> Result<Vec<Result<serde_json::Value, Error>>, Error> {
+        let device = Arc::new(SimulatedDevice::new("username", "password").with_responder(
+            move |request| {
+                assert_eq!(request["method"], "multipleRequest");
+                json!({"error_code": 0, "result": {"responses": responses}})
+            },
+        ));
+
+        let mut client = ApiClient::new("username", "password").with_transport(device);
+        client.login("192.168.1.100").await?;
+
+        client
+            .execute_multiple_requests(vec![
+                TapoRequest::GetDeviceInfo(TapoParams::new(EmptyParams)),
+                TapoRequest::GetDeviceUsage(TapoParams::new(EmptyParams)),
+            ])
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_execute_multiple_requests_matches_responses_by_method() {
+        let results = multiple_requests(json!([
+            {"method": "get_device_usage", "error_code": -1008},
+            {"method": "get_device_info", "error_code": 0, "result": {"device_on": true}},
+        ]))
+        .await
+        .unwrap();
+
+        assert_eq!(results[0].as_ref().unwrap(), &json!({"device_on": true}));
+        assert!(matches!(
+            results[1],
+            Err(Error::Tapo(TapoResponseError::InvalidParameters))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_execute_multiple_requests_rejects_missing_and_duplicate_methods() {
+        let missing = multiple_requests(json!([
+            {"method": "get_device_info", "error_code": 0, "result": {}},
+        ]))
+        .await;
+        assert!(matches!(
+            missing,
+            Err(Error::Tapo(TapoResponseError::InvalidResponse))
+        ));
+
+        let duplicate = multiple_requests(json!([
+            {"method": "get_device_info", "error_code": 0, "result": {}},
+            {"method": "get_device_info", "error_code": 0, "result": {}},
+            {"method": "get_device_usage", "error_code": 0, "result": {}},
+        ]))
+        .await;
+        assert!(matches!(
+            duplicate,
+            Err(Error::Tapo(TapoResponseError::InvalidResponse))
+        ));
+    }
 }
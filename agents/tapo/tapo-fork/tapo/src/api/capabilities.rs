@@ -1,3 +1,27 @@
+mod auto_off_ext;
+mod brightness_ext;
+mod child_devices_ext;
+mod component_list_ext;
+mod countdown_ext;
+mod device_info_ext;
 mod device_management_ext;
+mod device_time_ext;
+mod energy_monitoring_ext;
+mod firmware_ext;
+mod led_ext;
+mod on_off_ext;
+mod schedule_ext;
 
+pub use auto_off_ext::*;
+pub use brightness_ext::*;
+pub use child_devices_ext::*;
+pub use component_list_ext::*;
+pub use countdown_ext::*;
+pub use device_info_ext::*;
 pub use device_management_ext::*;
+pub use device_time_ext::*;
+pub use energy_monitoring_ext::*;
+pub use firmware_ext::*;
+pub use led_ext::*;
+pub use on_off_ext::*;
+pub use schedule_ext::*;
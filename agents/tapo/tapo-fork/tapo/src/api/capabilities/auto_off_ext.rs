@@ -0,0 +1,30 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::SetAutoOffConfigParams;
+use crate::responses::AutoOffConfigResult;
+
+/// Extension trait for devices that can turn themselves *off* after a delay, like plugs.
+#[async_trait]
+pub trait AutoOffExt: HandlerExt {
+    /// Returns the *auto-off* settings as [`AutoOffConfigResult`].
+    async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+        self.get_client().await.get_auto_off_config().await
+    }
+
+    /// Enables or disables *auto-off*.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable` - whether the device turns itself *off* after being *on* for `delay_min`.
+    /// * `delay_min` - the delay in minutes. It must be greater than 0 when `enable` is true.
+    async fn set_auto_off(&self, enable: bool, delay_min: u64) -> Result<(), Error> {
+        let params = SetAutoOffConfigParams::new(enable, delay_min)?;
+
+        self.get_client()
+            .await
+            .set_auto_off_config(serde_json::to_value(params)?)
+            .await
+    }
+}
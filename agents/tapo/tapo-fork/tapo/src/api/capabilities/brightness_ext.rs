@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::LightSetDeviceInfoParams;
+
+/// Extension trait for dimmable devices, like light bulbs and light strips.
+#[async_trait]
+pub trait BrightnessExt: HandlerExt {
+    /// Sets the *brightness* and turns *on* the device.
+    ///
+    /// # Arguments
+    ///
+    /// * `brightness` - between 1 and 100
+    async fn set_brightness(&self, brightness: u8) -> Result<(), Error> {
+        LightSetDeviceInfoParams::new(self.get_client().await)
+            .brightness(brightness)
+            .send()
+            .await
+    }
+}
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+
+/// Extension trait for devices with child devices, like hubs and power strips.
+/// The typed child device lists and handlers are available on [`crate::HubHandler`],
+/// [`crate::PowerStripHandler`] and [`crate::PowerStripEnergyMonitoringHandler`].
+#[async_trait]
+pub trait ChildDevicesExt: HandlerExt {
+    /// Returns *child device list* as [`serde_json::Value`].
+    /// It contains all the properties returned from the Tapo API.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_index` - the index to start fetching the child device list.
+    ///   It should be `0` for the first page, `10` for the second, and so on.
+    async fn get_child_device_list_json(
+        &self,
+        start_index: u64,
+    ) -> Result<serde_json::Value, Error> {
+        self.get_client()
+            .await
+            .get_child_device_list_json(start_index)
+            .await
+    }
+
+    /// Returns *child device component list* as [`serde_json::Value`].
+    /// This information is useful in debugging or when investigating new functionality to add.
+    async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
+        self.get_client()
+            .await
+            .get_child_device_component_list_json()
+            .await
+    }
+}
@@ -0,0 +1,15 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::responses::ComponentListResult;
+
+/// Extension trait for reading the components supported by the device firmware,
+/// to decide what to use based on what the device actually supports rather than its model.
+#[async_trait]
+pub trait ComponentListExt: HandlerExt {
+    /// Returns the components supported by the device and their versions as [`ComponentListResult`].
+    async fn get_component_list(&self) -> Result<ComponentListResult, Error> {
+        self.get_client().await.get_component_list().await
+    }
+}
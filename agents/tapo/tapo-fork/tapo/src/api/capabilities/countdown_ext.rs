@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::responses::CountdownRulesResult;
+
+/// Extension trait for devices with a countdown timer, which turns them *on* or *off* after a delay.
+#[async_trait]
+pub trait CountdownExt: HandlerExt {
+    /// Returns *countdown rules* as [`CountdownRulesResult`].
+    async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
+        self.get_client().await.get_countdown_rules().await
+    }
+
+    /// Sets the countdown rule, replacing the existing one.
+    ///
+    /// # Arguments
+    ///
+    /// * `delay` - seconds until the action.
+    /// * `turn_on` - `true` to turn the device *on*, `false` to turn it *off* when the countdown completes.
+    async fn set_countdown(&self, delay: u64, turn_on: bool) -> Result<(), Error> {
+        self.get_client()
+            .await
+            .add_countdown_rule(delay, turn_on)
+            .await
+    }
+}
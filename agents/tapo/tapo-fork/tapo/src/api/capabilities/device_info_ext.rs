@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::GenericSetDeviceInfoParams;
+
+/// Extension trait for the properties that identify the device in the Tapo app:
+/// its nickname, avatar and location.
+#[async_trait]
+pub trait DeviceInfoExt: HandlerExt {
+    /// Sets the *nickname* of the device, as shown in the Tapo app.
+    ///
+    /// # Arguments
+    ///
+    /// * `nickname` - the new name of the device. Must not be empty.
+    async fn set_nickname(&self, nickname: &str) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::nickname(nickname)?)?;
+        self.get_client().await.set_device_info(json).await
+    }
+
+    /// Sets the *avatar* (icon) of the device, as shown in the Tapo app.
+    ///
+    /// # Arguments
+    ///
+    /// * `avatar` - the avatar identifier used by the Tapo app, e.g. `plug`, `fan` or `table_lamp`.
+    async fn set_avatar(&self, avatar: &str) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::avatar(avatar)?)?;
+        self.get_client().await.set_device_info(json).await
+    }
+
+    /// Sets the *location* of the device, which drives its sunrise and sunset schedules.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - between -90 and 90 degrees.
+    /// * `longitude` - between -180 and 180 degrees.
+    async fn set_location(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+        let json =
+            serde_json::to_value(GenericSetDeviceInfoParams::location(latitude, longitude)?)?;
+        self.get_client().await.set_device_info(json).await
+    }
+}
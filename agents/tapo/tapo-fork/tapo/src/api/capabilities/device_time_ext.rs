@@ -0,0 +1,37 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::SetDeviceTimeParams;
+use crate::responses::DeviceTimeResult;
+
+/// Extension trait for reading and setting the clock of the device,
+/// which drives its schedules and the `local_time` of its energy results.
+#[async_trait]
+pub trait DeviceTimeExt: HandlerExt {
+    /// Returns the *device time* as [`DeviceTimeResult`].
+    async fn get_device_time(&self) -> Result<DeviceTimeResult, Error> {
+        self.get_client().await.get_device_time().await
+    }
+
+    /// Sets the *device time*.
+    ///
+    /// # Arguments
+    ///
+    /// * `timestamp` - UTC time as a Unix timestamp in seconds.
+    /// * `time_diff` - offset of the local time from UTC in minutes.
+    /// * `region` - time zone region, e.g. `Europe/London`.
+    async fn set_device_time(
+        &self,
+        timestamp: i64,
+        time_diff: i32,
+        region: &str,
+    ) -> Result<(), Error> {
+        let params = SetDeviceTimeParams::new(timestamp, time_diff, region)?;
+
+        self.get_client()
+            .await
+            .set_device_time(serde_json::to_value(params)?)
+            .await
+    }
+}
@@ -0,0 +1,48 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::{EnergyDataInterval, PowerDataInterval};
+use crate::responses::{
+    CurrentPowerResult, EmeterDataResult, EnergyDataResult, EnergyUsageResult, PowerDataResult,
+};
+
+/// Extension trait for devices that measure the power drawn by the connected appliance, like the P110 and P115 plugs.
+#[async_trait]
+pub trait EnergyMonitoringExt: HandlerExt {
+    /// Returns *current power* as [`CurrentPowerResult`].
+    async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
+        self.get_client().await.get_current_power().await
+    }
+
+    /// Returns *energy usage* as [`EnergyUsageResult`].
+    async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
+        self.get_client().await.get_energy_usage().await
+    }
+
+    /// Returns *energy data* as [`EnergyDataResult`].
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - the interval and time range, see [`EnergyDataInterval`].
+    async fn get_energy_data(
+        &self,
+        interval: EnergyDataInterval,
+    ) -> Result<EnergyDataResult, Error> {
+        self.get_client().await.get_energy_data(interval).await
+    }
+
+    /// Returns *power data* as [`PowerDataResult`].
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - the interval and time range, see [`PowerDataInterval`].
+    async fn get_power_data(&self, interval: PowerDataInterval) -> Result<PowerDataResult, Error> {
+        self.get_client().await.get_power_data(interval).await
+    }
+
+    /// Returns the real-time *voltage*, *current* and *power* as [`EmeterDataResult`].
+    async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error> {
+        self.get_client().await.get_emeter_data().await
+    }
+}
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::responses::{FirmwareDownloadStateResult, LatestFirmwareResult};
+
+/// Extension trait for checking and installing firmware updates.
+#[async_trait]
+pub trait FirmwareExt: HandlerExt {
+    /// Returns the *latest firmware* available for the device as [`LatestFirmwareResult`].
+    /// The installed version is the `fw_ver` of the device info.
+    async fn get_latest_firmware(&self) -> Result<LatestFirmwareResult, Error> {
+        self.get_client().await.get_latest_firmware().await
+    }
+
+    /// Starts downloading and installing the latest firmware.
+    ///
+    /// The device turns *off* its outputs and reboots once the firmware is flashed,
+    /// so the connection is lost for a while. Follow the progress with
+    /// [`FirmwareExt::get_firmware_download_state`]. The request is never retried.
+    async fn firmware_download(&self) -> Result<(), Error> {
+        self.get_client().await.firmware_download().await
+    }
+
+    /// Returns the progress of the firmware update as [`FirmwareDownloadStateResult`].
+    async fn get_firmware_download_state(&self) -> Result<FirmwareDownloadStateResult, Error> {
+        self.get_client().await.get_firmware_download_state().await
+    }
+}
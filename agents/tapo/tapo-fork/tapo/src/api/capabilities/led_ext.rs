@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::SetLedInfoParams;
+use crate::responses::{LedInfoResult, LedNightMode, LedRule};
+
+/// Extension trait for devices with a status LED, like plugs and power strips.
+#[async_trait]
+pub trait LedExt: HandlerExt {
+    /// Returns the *status LED* settings as [`LedInfoResult`].
+    async fn get_led_info(&self) -> Result<LedInfoResult, Error> {
+        self.get_client().await.get_led_info().await
+    }
+
+    /// Sets when the *status LED* is lit. The night mode period is left unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `led_rule` - one of [`LedRule`].
+    async fn set_led_rule(&self, led_rule: LedRule) -> Result<(), Error> {
+        let client = self.get_client().await;
+        let led_info = client.get_led_info().await?;
+        let params = SetLedInfoParams::new(led_info, led_rule, None)?;
+
+        client.set_led_info(serde_json::to_value(params)?).await
+    }
+
+    /// Turns the *status LED* off during the given period and on outside of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `night_mode` - the period, e.g. [`LedNightMode::custom`] or [`LedNightMode::sunrise_sunset`].
+    async fn set_led_night_mode(&self, night_mode: LedNightMode) -> Result<(), Error> {
+        let client = self.get_client().await;
+        let led_info = client.get_led_info().await?;
+        let params = SetLedInfoParams::new(led_info, LedRule::NightMode, Some(night_mode))?;
+
+        client.set_led_info(serde_json::to_value(params)?).await
+    }
+}
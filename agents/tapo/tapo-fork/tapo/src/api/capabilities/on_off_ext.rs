@@ -0,0 +1,21 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::requests::GenericSetDeviceInfoParams;
+
+/// Extension trait for devices that can be turned *on* and *off*, like plugs, light bulbs and light strips.
+#[async_trait]
+pub trait OnOffExt: HandlerExt {
+    /// Turns *on* the device.
+    async fn on(&self) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
+        self.get_client().await.set_device_info(json).await
+    }
+
+    /// Turns *off* the device.
+    async fn off(&self) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
+        self.get_client().await.set_device_info(json).await
+    }
+}
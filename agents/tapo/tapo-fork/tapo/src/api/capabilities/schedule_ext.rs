@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::Error;
+use crate::api::HandlerExt;
+use crate::responses::{NextEventResult, ScheduleRulesResult};
+
+/// Extension trait for devices that run schedules set up in the Tapo app.
+#[async_trait]
+pub trait ScheduleExt: HandlerExt {
+    /// Returns *schedule rules* as [`ScheduleRulesResult`].
+    async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
+        self.get_client().await.get_schedule_rules().await
+    }
+
+    /// Returns the *next event*, the rule that fires next and when, as [`NextEventResult`].
+    async fn get_next_event(&self) -> Result<NextEventResult, Error> {
+        self.get_client().await.get_next_event().await
+    }
+}
@@ -1,3 +1,55 @@
+// Generates the methods that every child device handler has into its `impl` block.
+// The handler needs the `client: Arc<RwLock<ApiClient>>` and `device_id: String` fields.
+macro_rules! child_device_info_methods {
+    () => {
+        /// Returns the components supported by the device and their versions
+        /// as [`crate::responses::ComponentListResult`], as reported by the parent device.
+        pub async fn get_component_list(
+            &self,
+        ) -> Result<$crate::responses::ComponentListResult, $crate::Error> {
+            self.client
+                .read()
+                .await
+                .get_child_component_list(&self.device_id)
+                .await
+        }
+
+        /// Sets the *nickname* of the device, as shown in the Tapo app.
+        ///
+        /// # Arguments
+        ///
+        /// * `nickname` - the new name of the device. Must not be empty.
+        pub async fn set_nickname(&self, nickname: &str) -> Result<(), $crate::Error> {
+            let json = serde_json::to_value(
+                $crate::requests::GenericSetDeviceInfoParams::nickname(nickname)?,
+            )?;
+
+            self.client
+                .read()
+                .await
+                .set_child_device_info(&self.device_id, json)
+                .await
+        }
+
+        /// Sets the *avatar* (icon) of the device, as shown in the Tapo app.
+        ///
+        /// # Arguments
+        ///
+        /// * `avatar` - the avatar identifier used by the Tapo app.
+        pub async fn set_avatar(&self, avatar: &str) -> Result<(), $crate::Error> {
+            let json = serde_json::to_value($crate::requests::GenericSetDeviceInfoParams::avatar(
+                avatar,
+            )?)?;
+
+            self.client
+                .read()
+                .await
+                .set_child_device_info(&self.device_id, json)
+                .await
+        }
+    };
+}
+
 mod ke100_handler;
 mod power_strip_plug_energy_monitoring_handler;
 mod power_strip_plug_handler;
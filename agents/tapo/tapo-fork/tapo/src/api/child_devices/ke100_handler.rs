@@ -46,6 +46,8 @@ impl KE100Handler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
+    child_device_info_methods!();
+
     /// Sets *child protection* on the device to *on* or *off*.
     ///     
     /// # Arguments
@@ -1,17 +1,19 @@
 use std::sync::Arc;
 
-use tokio::sync::RwLock;
+use async_trait::async_trait;
+use tokio::sync::{RwLock, RwLockReadGuard};
 
-use crate::api::ApiClient;
+use crate::api::{ApiClient, ApiClientExt, AutoOffExt, EnergyMonitoringExt, HandlerExt, OnOffExt};
 use crate::error::{Error, TapoResponseError};
 use crate::requests::{
-    EmptyParams, EnergyDataInterval, GenericSetDeviceInfoParams, GetEnergyDataParams,
-    GetPowerDataParams, PowerDataInterval, TapoParams, TapoRequest,
+    DefaultPowerState, EmptyParams, EnergyDataInterval, GenericSetDeviceInfoParams,
+    GetEnergyDataParams, GetPowerDataParams, PowerDataInterval, SetAutoOffConfigParams, TapoParams,
+    TapoRequest,
 };
 use crate::responses::{
-    CurrentPowerResult, DecodableResultExt, DeviceUsageEnergyMonitoringResult, EnergyDataResult,
-    EnergyDataResultRaw, EnergyUsageResult, PowerDataResult, PowerDataResultRaw,
-    PowerStripPlugEnergyMonitoringResult,
+    AutoOffConfigResult, CurrentPowerResult, DecodableResultExt, DeviceUsageEnergyMonitoringResult,
+    EmeterDataResult, EnergyDataResult, EnergyDataResultRaw, EnergyUsageResult, PowerDataResult,
+    PowerDataResultRaw, PowerStripPlugEnergyMonitoringResult,
 };
 
 /// Handler for the [P304M](https://www.tp-link.com/uk/search/?q=P304M) and
@@ -55,8 +57,71 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
+    child_device_info_methods!();
+
+    /// Sets the *location* of the device, which drives its sunrise and sunset schedules.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - between -90 and 90 degrees.
+    /// * `longitude` - between -180 and 180 degrees.
+    pub async fn set_location(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+        let json =
+            serde_json::to_value(GenericSetDeviceInfoParams::location(latitude, longitude)?)?;
+
+        self.client
+            .read()
+            .await
+            .set_child_device_info(&self.device_id, json)
+            .await
+    }
+
+    /// Sets the state the socket is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - one of [`DefaultPowerState`]
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::plug_default_state(state)?)?;
+        let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
+
+        self.client
+            .read()
+            .await
+            .control_child::<serde_json::Value>(self.device_id.clone(), request)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Returns *device usage* as [`DeviceUsageEnergyMonitoringResult`].
+    pub async fn get_device_usage(&self) -> Result<DeviceUsageEnergyMonitoringResult, Error> {
+        let request = TapoRequest::GetDeviceUsage(TapoParams::new(EmptyParams));
+
+        self.client
+            .read()
+            .await
+            .control_child(self.device_id.clone(), request)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+}
+
+// The child is controlled through the power strip, so the capability traits it implements
+// override every method to wrap its requests in `control_child`.
+#[async_trait]
+impl HandlerExt for PowerStripPlugEnergyMonitoringHandler {
+    async fn get_client(&self) -> RwLockReadGuard<'_, dyn ApiClientExt> {
+        RwLockReadGuard::map(
+            self.client.read().await,
+            |client: &ApiClient| -> &dyn ApiClientExt { client },
+        )
+    }
+}
+
+#[async_trait]
+impl OnOffExt for PowerStripPlugEnergyMonitoringHandler {
+    async fn on(&self) -> Result<(), Error> {
         let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
         let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
 
@@ -69,8 +134,7 @@ impl PowerStripPlugEnergyMonitoringHandler {
         Ok(())
     }
 
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
+    async fn off(&self) -> Result<(), Error> {
         let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
         let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
 
@@ -82,10 +146,12 @@ impl PowerStripPlugEnergyMonitoringHandler {
 
         Ok(())
     }
+}
 
-    /// Returns *current power* as [`CurrentPowerResult`].
-    pub async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
-        let request = TapoRequest::GetCurrentPower(TapoParams::new(EmptyParams));
+#[async_trait]
+impl AutoOffExt for PowerStripPlugEnergyMonitoringHandler {
+    async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+        let request = TapoRequest::GetAutoOffConfig(TapoParams::new(EmptyParams));
 
         self.client
             .read()
@@ -95,9 +161,24 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Returns *device usage* as [`DeviceUsageEnergyMonitoringResult`].
-    pub async fn get_device_usage(&self) -> Result<DeviceUsageEnergyMonitoringResult, Error> {
-        let request = TapoRequest::GetDeviceUsage(TapoParams::new(EmptyParams));
+    async fn set_auto_off(&self, enable: bool, delay_min: u64) -> Result<(), Error> {
+        let json = serde_json::to_value(SetAutoOffConfigParams::new(enable, delay_min)?)?;
+        let request = TapoRequest::SetAutoOffConfig(Box::new(TapoParams::new(json)));
+
+        self.client
+            .read()
+            .await
+            .control_child::<serde_json::Value>(self.device_id.clone(), request)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EnergyMonitoringExt for PowerStripPlugEnergyMonitoringHandler {
+    async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
+        let request = TapoRequest::GetCurrentPower(TapoParams::new(EmptyParams));
 
         self.client
             .read()
@@ -107,8 +188,7 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Returns *energy usage* as [`EnergyUsageResult`].
-    pub async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
+    async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
         let request = TapoRequest::GetEnergyUsage(TapoParams::new(EmptyParams));
 
         self.client
@@ -119,8 +199,7 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Returns *energy data* as [`EnergyDataResult`].
-    pub async fn get_energy_data(
+    async fn get_energy_data(
         &self,
         interval: EnergyDataInterval,
     ) -> Result<EnergyDataResult, Error> {
@@ -136,11 +215,7 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .map(|result| result.try_into())?
     }
 
-    /// Returns *power data* as [`PowerDataResult`].
-    pub async fn get_power_data(
-        &self,
-        interval: PowerDataInterval,
-    ) -> Result<PowerDataResult, Error> {
+    async fn get_power_data(&self, interval: PowerDataInterval) -> Result<PowerDataResult, Error> {
         let params = GetPowerDataParams::new(interval);
         let request = TapoRequest::GetPowerData(TapoParams::new(params));
 
@@ -152,4 +227,15 @@ impl PowerStripPlugEnergyMonitoringHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
             .map(|result| result.try_into())?
     }
+
+    async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error> {
+        let request = TapoRequest::GetEmeterData(TapoParams::new(EmptyParams));
+
+        self.client
+            .read()
+            .await
+            .control_child(self.device_id.clone(), request)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
 }
@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
-use tokio::sync::RwLock;
+use async_trait::async_trait;
+use tokio::sync::{RwLock, RwLockReadGuard};
 
-use crate::api::ApiClient;
+use crate::api::{ApiClient, ApiClientExt, AutoOffExt, HandlerExt, OnOffExt};
 use crate::error::{Error, TapoResponseError};
-use crate::requests::{EmptyParams, GenericSetDeviceInfoParams, TapoParams, TapoRequest};
-use crate::responses::{DecodableResultExt, PowerStripPlugResult};
+use crate::requests::{
+    DefaultPowerState, EmptyParams, GenericSetDeviceInfoParams, SetAutoOffConfigParams, TapoParams,
+    TapoRequest,
+};
+use crate::responses::{AutoOffConfigResult, DecodableResultExt, PowerStripPlugResult};
 
 /// Handler for the [P300](https://www.tp-link.com/en/search/?q=P300) and
 /// [P306](https://www.tp-link.com/us/search/?q=P306) child plugs.
@@ -48,8 +52,59 @@ impl PowerStripPlugHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
+    child_device_info_methods!();
+
+    /// Sets the *location* of the device, which drives its sunrise and sunset schedules.
+    ///
+    /// # Arguments
+    ///
+    /// * `latitude` - between -90 and 90 degrees.
+    /// * `longitude` - between -180 and 180 degrees.
+    pub async fn set_location(&self, latitude: f64, longitude: f64) -> Result<(), Error> {
+        let json =
+            serde_json::to_value(GenericSetDeviceInfoParams::location(latitude, longitude)?)?;
+
+        self.client
+            .read()
+            .await
+            .set_child_device_info(&self.device_id, json)
+            .await
+    }
+
+    /// Sets the state the socket is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - one of [`DefaultPowerState`]
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::plug_default_state(state)?)?;
+        let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
+
+        self.client
+            .read()
+            .await
+            .control_child::<serde_json::Value>(self.device_id.clone(), request)
+            .await?;
+
+        Ok(())
+    }
+}
+
+// The child is controlled through the power strip, so the capability traits it implements
+// override every method to wrap its requests in `control_child`.
+#[async_trait]
+impl HandlerExt for PowerStripPlugHandler {
+    async fn get_client(&self) -> RwLockReadGuard<'_, dyn ApiClientExt> {
+        RwLockReadGuard::map(
+            self.client.read().await,
+            |client: &ApiClient| -> &dyn ApiClientExt { client },
+        )
+    }
+}
+
+#[async_trait]
+impl OnOffExt for PowerStripPlugHandler {
+    async fn on(&self) -> Result<(), Error> {
         let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
         let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
 
@@ -62,8 +117,7 @@ impl PowerStripPlugHandler {
         Ok(())
     }
 
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
+    async fn off(&self) -> Result<(), Error> {
         let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
         let request = TapoRequest::SetDeviceInfo(Box::new(TapoParams::new(json)));
 
@@ -76,3 +130,30 @@ impl PowerStripPlugHandler {
         Ok(())
     }
 }
+
+#[async_trait]
+impl AutoOffExt for PowerStripPlugHandler {
+    async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+        let request = TapoRequest::GetAutoOffConfig(TapoParams::new(EmptyParams));
+
+        self.client
+            .read()
+            .await
+            .control_child(self.device_id.clone(), request)
+            .await?
+            .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
+    }
+
+    async fn set_auto_off(&self, enable: bool, delay_min: u64) -> Result<(), Error> {
+        let json = serde_json::to_value(SetAutoOffConfigParams::new(enable, delay_min)?)?;
+        let request = TapoRequest::SetAutoOffConfig(Box::new(TapoParams::new(json)));
+
+        self.client
+            .read()
+            .await
+            .control_child::<serde_json::Value>(self.device_id.clone(), request)
+            .await?;
+
+        Ok(())
+    }
+}
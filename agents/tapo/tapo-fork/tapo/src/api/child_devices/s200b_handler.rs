@@ -46,6 +46,8 @@ impl S200BHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
+    child_device_info_methods!();
+
     /// Returns a list of *trigger logs*.
     ///
     /// # Arguments
@@ -46,6 +46,8 @@ impl T100Handler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
+    child_device_info_methods!();
+
     /// Returns a list of *trigger logs*.
     ///
     /// # Arguments
@@ -4,9 +4,10 @@ use tokio::sync::RwLock;
 
 use crate::api::ApiClient;
 use crate::error::{Error, TapoResponseError};
-use crate::requests::{EmptyParams, TapoParams, TapoRequest};
+use crate::requests::{EmptyParams, T31XSetDeviceInfoParams, TapoParams, TapoRequest};
 use crate::responses::{
     DecodableResultExt, T31XResult, TemperatureHumidityRecords, TemperatureHumidityRecordsRaw,
+    TemperatureUnit,
 };
 
 /// Handler for the [T310](https://www.tapo.com/en/search/?q=T310) and [T315](https://www.tapo.com/en/search/?q=T315) devices.
@@ -47,6 +48,57 @@ impl T31XHandler {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))
     }
 
+    child_device_info_methods!();
+
+    /// Sets how often the device reports its readings to the hub.
+    ///
+    /// # Arguments
+    ///
+    /// * `report_interval` - the time in seconds between each report. Must be at least `1`.
+    pub async fn set_report_interval(&self, report_interval: u32) -> Result<(), Error> {
+        self.set_device_info(T31XSetDeviceInfoParams::new().report_interval(report_interval)?)
+            .await
+    }
+
+    /// Sets the *temperature unit* the device displays and reports temperatures in.
+    ///
+    /// # Arguments
+    ///
+    /// * `unit` - the temperature unit.
+    pub async fn set_temperature_unit(&self, unit: TemperatureUnit) -> Result<(), Error> {
+        self.set_device_info(T31XSetDeviceInfoParams::new().temperature_unit(unit)?)
+            .await
+    }
+
+    /// Sets the *temperature comfort zone*, which drives `current_temperature_exception`.
+    /// The temperature unit of the device is changed to `unit` as well.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - the lower bound of the comfort zone.
+    /// * `max` - the upper bound of the comfort zone. Must be higher than `min`.
+    /// * `unit` - the temperature unit of `min` and `max`.
+    pub async fn set_comfort_temperature(
+        &self,
+        min: i16,
+        max: i16,
+        unit: TemperatureUnit,
+    ) -> Result<(), Error> {
+        self.set_device_info(T31XSetDeviceInfoParams::new().comfort_temperature(min, max, unit)?)
+            .await
+    }
+
+    /// Sets the *humidity comfort zone*, which drives `current_humidity_exception`.
+    ///
+    /// # Arguments
+    ///
+    /// * `min` - the lower bound of the comfort zone, in percent.
+    /// * `max` - the upper bound of the comfort zone, in percent. Must be higher than `min` and at most `100`.
+    pub async fn set_comfort_humidity(&self, min: u8, max: u8) -> Result<(), Error> {
+        self.set_device_info(T31XSetDeviceInfoParams::new().comfort_humidity(min, max)?)
+            .await
+    }
+
     /// Returns *temperature and humidity records* from the last 24 hours at 15 minute intervals as [`TemperatureHumidityRecords`].
     pub async fn get_temperature_humidity_records(
         &self,
@@ -64,4 +116,14 @@ impl T31XHandler {
 
         Ok(result.try_into()?)
     }
+
+    async fn set_device_info(&self, params: T31XSetDeviceInfoParams) -> Result<(), Error> {
+        let json = serde_json::to_value(params)?;
+
+        self.client
+            .read()
+            .await
+            .set_child_device_info(&self.device_id, json)
+            .await
+    }
 }
@@ -1,6 +1,16 @@
 mod aes_discovery_query_generator;
+mod announcement_discovery;
+mod device_announcement;
 mod device_discovery;
 mod discovery_result;
+mod discovery_watcher;
+mod local_networks;
+mod subnet_scan;
 
+pub use announcement_discovery::*;
+pub use device_announcement::*;
 pub use device_discovery::*;
 pub use discovery_result::*;
+pub use discovery_watcher::*;
+pub use local_networks::*;
+pub use subnet_scan::*;
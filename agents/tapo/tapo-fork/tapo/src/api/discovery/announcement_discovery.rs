@@ -0,0 +1,101 @@
+use std::pin::Pin;
+use std::task::{Context as TaskContext, Poll};
+
+use log::{debug, trace};
+use tokio::sync::mpsc::Receiver;
+use tokio::time::Duration;
+use tokio_stream::Stream;
+
+use super::device_announcement::DeviceAnnouncement;
+use super::device_discovery::spawn_discovery;
+use crate::Error;
+
+/// Device discovery process for Tapo devices that does not log into the devices.
+///
+/// Yields the [`DeviceAnnouncement`] of every device that answers,
+/// whichever Tapo account it is bound to.
+pub struct AnnouncementDiscovery {
+    rx: Receiver<Option<Result<DeviceAnnouncement, Error>>>,
+}
+
+impl AnnouncementDiscovery {
+    /// Discovers one or more devices located at a specified unicast or broadcast IP address,
+    /// without credentials.
+    ///
+    /// # Arguments
+    /// * `target` - The IP address at which the discovery will take place.
+    ///   This address can be either a unicast (e.g. `192.168.1.10`) or a
+    ///   broadcast address (e.g. `192.168.1.255`, `255.255.255.255`, etc.).
+    /// * `timeout_s` - The maximum time to wait for a response from the device(s) in seconds.
+    ///   Must be between `1` and `60`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// use tapo::{AnnouncementDiscovery, ApiClient, StreamExt};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let mut discovery = AnnouncementDiscovery::new("192.168.1.255", 5).await?;
+    ///
+    ///     while let Some(announcement) = discovery.next().await {
+    ///         let announcement = announcement?;
+    ///         println!("{} ({}) at {}", announcement.model, announcement.mac, announcement.ip);
+    ///
+    ///         if announcement.model.starts_with("P110") {
+    ///             let client = ApiClient::new("tapo-username@example.com", "tapo-password");
+    ///             let device = announcement.connect_any(client).await?;
+    ///             println!("Connected to {}", device.model());
+    ///         }
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn new(target: impl Into<String>, timeout_s: u64) -> Result<Self, Error> {
+        if !(1..=60).contains(&timeout_s) {
+            return Err(Error::Validation {
+                field: "timeout_s".to_string(),
+                message: "Must be between 1 and 60 seconds".to_string(),
+            });
+        }
+
+        let rx = spawn_discovery(
+            target,
+            Duration::from_secs(timeout_s),
+            |addr, response, target, tx| {
+                tokio::spawn(async move {
+                    let _ = tx
+                        .send(Some(DeviceAnnouncement::parse(addr.ip(), &response)))
+                        .await;
+
+                    if addr.ip() == target.ip() {
+                        debug!("Target found, stopping discovery responses");
+                        let _ = tx.send(None).await;
+                    }
+                });
+            },
+        )
+        .await?;
+
+        Ok(Self { rx })
+    }
+}
+
+impl Stream for AnnouncementDiscovery {
+    type Item = Result<DeviceAnnouncement, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<DeviceAnnouncement, Error>>> {
+        match Pin::new(&mut self.rx).poll_recv(cx) {
+            Poll::Ready(Some(result)) => Poll::Ready(result),
+            Poll::Ready(None) => {
+                trace!("Discovery stream closed");
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use std::net::IpAddr;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{AnyDevice, ApiClient, Error};
+
+use super::discovery_result::DiscoveryResult;
+
+// The discovery response starts with a binary header, followed by the Json payload.
+const HEADER_SIZE: usize = 16;
+
+/// A device that answered a discovery query, as announced in its discovery response.
+///
+/// Announcements are parsed without logging into the device, so they are also returned for devices
+/// bound to another Tapo account or not bound to any account yet.
+/// Use [`DeviceAnnouncement::connect`] to log into a selected device afterwards.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceAnnouncement {
+    /// The IP address the discovery response came from.
+    pub ip: IpAddr,
+    /// The MAC address of the device, e.g. `AA-BB-CC-DD-EE-FF`.
+    pub mac: String,
+    /// The device id.
+    pub device_id: String,
+    /// The model of the device, e.g. `P110(EU)`.
+    #[serde(rename = "device_model")]
+    pub model: String,
+    /// The type of the device, e.g. `SMART.TAPOPLUG`.
+    pub device_type: String,
+    /// The hash of the Tapo account the device is bound to.
+    #[serde(default)]
+    pub owner: Option<String>,
+    /// The hardware version.
+    #[serde(default)]
+    pub hw_ver: Option<String>,
+    /// Whether the device is in its factory default state and has not been onboarded yet.
+    #[serde(default)]
+    pub factory_default: bool,
+    /// How the device expects to be talked to.
+    #[serde(rename = "mgt_encrypt_schm", default)]
+    pub encryption: Option<EncryptionScheme>,
+}
+
+/// The encryption scheme announced by a device.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptionScheme {
+    /// The protocol used to talk to the device. `None` for firmware that does not announce it.
+    #[serde(default)]
+    pub encrypt_type: Option<EncryptionType>,
+    /// Whether the device accepts HTTPS.
+    #[serde(default)]
+    pub is_support_https: bool,
+    /// The port the device listens on.
+    #[serde(default)]
+    pub http_port: Option<u16>,
+    /// The protocol version.
+    #[serde(default)]
+    pub lv: Option<u8>,
+}
+
+/// The protocol used to talk to a device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EncryptionType {
+    /// The KLAP protocol used by recent firmware.
+    #[serde(rename = "KLAP")]
+    Klap,
+    /// The older passthrough protocol.
+    #[serde(rename = "AES")]
+    Passthrough,
+    /// A protocol this library does not know.
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceAnnouncementResponse {
+    result: DeviceAnnouncement,
+}
+
+impl DeviceAnnouncement {
+    /// Parses a discovery response received from `ip_addr`.
+    pub(crate) fn parse(ip_addr: IpAddr, response: &[u8]) -> Result<Self, Error> {
+        let payload = response
+            .get(HEADER_SIZE..)
+            .context("Discovery response is shorter than its header")?;
+
+        let mut announcement =
+            serde_json::from_slice::<DeviceAnnouncementResponse>(payload)?.result;
+        // The address the response came from is what the device can be reached at.
+        announcement.ip = ip_addr;
+
+        Ok(announcement)
+    }
+
+    /// Logs into the device with the credentials of the given [`ApiClient`]
+    /// and returns the matching [`DiscoveryResult`].
+    pub async fn connect(&self, client: ApiClient) -> Result<DiscoveryResult, Error> {
+        DiscoveryResult::new(client, self.ip).await
+    }
+
+    /// Logs into the device with the credentials of the given [`ApiClient`]
+    /// and returns it as an [`AnyDevice`]. See [`ApiClient::connect`].
+    pub async fn connect_any(&self, client: ApiClient) -> Result<AnyDevice, Error> {
+        client.connect(self.ip.to_string()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn response(payload: serde_json::Value) -> Vec<u8> {
+        let mut response = vec![0; HEADER_SIZE];
+        response.extend_from_slice(&serde_json::to_vec(&payload).unwrap());
+        response
+    }
+
+    #[test]
+    fn test_parse() {
+        let response = response(json!({
+            "error_code": 0,
+            "result": {
+                "device_id": "80224D3B7A04E5F5BD08C1CA2F4B1D2E",
+                "owner": "A1B2C3D4E5F60718293A4B5C6D7E8F90",
+                "device_type": "SMART.TAPOPLUG",
+                "device_model": "P110(EU)",
+                "ip": "192.168.1.50",
+                "mac": "AA-BB-CC-DD-EE-FF",
+                "is_support_iot_cloud": true,
+                "obd_src": "tplink",
+                "factory_default": false,
+                "mgt_encrypt_schm": {
+                    "is_support_https": false,
+                    "encrypt_type": "KLAP",
+                    "http_port": 80,
+                    "lv": 2
+                }
+            }
+        }));
+
+        let announcement =
+            DeviceAnnouncement::parse("192.168.1.100".parse().unwrap(), &response).unwrap();
+
+        assert_eq!(announcement.ip, "192.168.1.100".parse::<IpAddr>().unwrap());
+        assert_eq!(announcement.model, "P110(EU)");
+        assert_eq!(announcement.device_type, "SMART.TAPOPLUG");
+        assert_eq!(announcement.mac, "AA-BB-CC-DD-EE-FF");
+        assert_eq!(announcement.device_id, "80224D3B7A04E5F5BD08C1CA2F4B1D2E");
+        assert!(!announcement.factory_default);
+        assert_eq!(
+            announcement.encryption,
+            Some(EncryptionScheme {
+                encrypt_type: Some(EncryptionType::Klap),
+                is_support_https: false,
+                http_port: Some(80),
+                lv: Some(2),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_encryption() {
+        let response = response(json!({
+            "error_code": 0,
+            "result": {
+                "device_id": "id",
+                "device_type": "SMART.TAPOBULB",
+                "device_model": "L530",
+                "ip": "192.168.1.51",
+                "mac": "AA-BB-CC-DD-EE-00",
+                "mgt_encrypt_schm": {"encrypt_type": "TPAP"}
+            }
+        }));
+
+        let announcement =
+            DeviceAnnouncement::parse("192.168.1.51".parse().unwrap(), &response).unwrap();
+
+        assert_eq!(announcement.owner, None);
+        assert_eq!(
+            announcement.encryption.and_then(|e| e.encrypt_type),
+            Some(EncryptionType::Unknown)
+        );
+    }
+
+    #[test]
+    fn test_parse_truncated() {
+        assert!(DeviceAnnouncement::parse("192.168.1.51".parse().unwrap(), &[0; 8]).is_err());
+    }
+}
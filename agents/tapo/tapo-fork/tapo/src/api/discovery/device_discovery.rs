@@ -29,173 +29,193 @@ impl DeviceDiscovery {
         target: impl Into<String>,
         timeout: Duration,
     ) -> anyhow::Result<Self> {
-        let target = SocketAddr::new(target.into().parse()?, 20002);
-
-        let bind_address = match target.ip() {
-            IpAddr::V4(_) => "0.0.0.0:0", // IPv4
-            IpAddr::V6(_) => "[::]:0",    // IPv6
-        };
-
-        let transport = UdpSocket::bind(bind_address).await?;
-        transport.set_broadcast(true)?;
-        let transport = Arc::new(transport);
-
-        let (tx, rx) = mpsc::channel(1024);
-        let seen_addrs = Arc::new(Mutex::new(vec![]));
-
-        let discovery_transport = transport.clone();
-        let discovery_seen_addrs = seen_addrs.clone();
-        let discovery_tx = tx.clone();
-
         let client: Arc<RwLock<ApiClient>> = Arc::new(RwLock::new(client));
 
-        tokio::spawn(async move {
-            let result = tokio::time::timeout(
-                timeout,
-                Self::send_discovery_query(
-                    discovery_transport,
-                    target,
-                    discovery_seen_addrs,
-                    discovery_tx.clone(),
-                ),
-            )
-            .await;
-
-            if result.is_err() {
-                trace!("Discovery query timed out");
-            }
-        });
-
-        tokio::spawn(async move {
-            let result = tokio::time::timeout(
-                timeout,
-                Self::receive_discovery_response(client, transport, target, seen_addrs, tx.clone()),
-            )
-            .await;
-
-            if result.is_err() {
-                trace!("Discovery response timed out");
-            }
-        });
+        let rx = spawn_discovery(target, timeout, move |addr, _, target, tx| {
+            tokio::spawn(Self::process_discovery_response(
+                client.clone(),
+                addr.ip(),
+                target.ip(),
+                tx,
+            ));
+        })
+        .await?;
 
         Ok(Self { rx })
     }
 
-    async fn send_discovery_query(
-        transport: Arc<UdpSocket>,
-        target: SocketAddr,
-        seen_addrs: Arc<Mutex<Vec<SocketAddr>>>,
+    async fn process_discovery_response(
+        client: Arc<RwLock<ApiClient>>,
+        ip_addr: IpAddr,
+        target: IpAddr,
         tx: mpsc::Sender<Option<Result<DiscoveryResult, Error>>>,
     ) {
-        let error_handling_tx = tx.clone();
-
-        let result = async move {
-            let aes_discovery_query = AesDiscoveryQueryGenerator::new()?.generate()?;
-
-            loop {
-                if tx.is_closed() {
-                    info!("Channel closed, stopping discovery queries");
-                    break;
-                }
+        let client = client.read().await.clone();
 
-                let seen_addrs = seen_addrs.lock().await;
-                if seen_addrs.contains(&target) {
-                    trace!("Target found, stopping discovery queries");
-                    break;
-                }
-                drop(seen_addrs);
+        let result = DiscoveryResult::new(client, ip_addr).await;
 
-                transport.send_to(&aes_discovery_query, target).await?;
+        let _ = tx.send(Some(result)).await;
 
-                tokio::time::sleep(DISCOVERY_INTERVAL).await;
-            }
+        if ip_addr == target {
+            debug!("Target found, stopping discovery responses");
+            let _ = tx.send(None).await;
+        }
+    }
+}
 
-            trace!("Discovery queries finished");
+/// Sends discovery queries to `target` and hands every new response to `on_response`
+/// until `timeout` elapses or the receiving end is dropped.
+pub(super) async fn spawn_discovery<T, F>(
+    target: impl Into<String>,
+    timeout: Duration,
+    on_response: F,
+) -> anyhow::Result<Receiver<Option<Result<T, Error>>>>
+where
+    T: Send + 'static,
+    F: Fn(SocketAddr, Vec<u8>, SocketAddr, mpsc::Sender<Option<Result<T, Error>>>) + Send + 'static,
+{
+    let target = SocketAddr::new(target.into().parse()?, 20002);
+
+    let bind_address = match target.ip() {
+        IpAddr::V4(_) => "0.0.0.0:0", // IPv4
+        IpAddr::V6(_) => "[::]:0",    // IPv6
+    };
+
+    let transport = UdpSocket::bind(bind_address).await?;
+    transport.set_broadcast(true)?;
+    let transport = Arc::new(transport);
+
+    let (tx, rx) = mpsc::channel(1024);
+    let seen_addrs = Arc::new(Mutex::new(vec![]));
+
+    let discovery_transport = transport.clone();
+    let discovery_seen_addrs = seen_addrs.clone();
+    let discovery_tx = tx.clone();
+
+    tokio::spawn(async move {
+        let result = tokio::time::timeout(
+            timeout,
+            send_discovery_query(
+                discovery_transport,
+                target,
+                discovery_seen_addrs,
+                discovery_tx.clone(),
+            ),
+        )
+        .await;
 
-            Ok::<_, anyhow::Error>(())
+        if result.is_err() {
+            trace!("Discovery query timed out");
         }
+    });
+
+    tokio::spawn(async move {
+        let result = tokio::time::timeout(
+            timeout,
+            receive_discovery_response(transport, target, seen_addrs, tx.clone(), on_response),
+        )
         .await;
 
-        if let Err(e) = result {
-            let _ = error_handling_tx.send(Some(Err(e.into()))).await;
+        if result.is_err() {
+            trace!("Discovery response timed out");
         }
-    }
+    });
+
+    Ok(rx)
+}
+
+async fn send_discovery_query<T>(
+    transport: Arc<UdpSocket>,
+    target: SocketAddr,
+    seen_addrs: Arc<Mutex<Vec<SocketAddr>>>,
+    tx: mpsc::Sender<Option<Result<T, Error>>>,
+) {
+    let error_handling_tx = tx.clone();
+
+    let result = async move {
+        let aes_discovery_query = AesDiscoveryQueryGenerator::new()?.generate()?;
 
-    async fn receive_discovery_response(
-        client: Arc<RwLock<ApiClient>>,
-        transport: Arc<UdpSocket>,
-        target: SocketAddr,
-        seen_addrs: Arc<Mutex<Vec<SocketAddr>>>,
-        tx: mpsc::Sender<Option<Result<DiscoveryResult, Error>>>,
-    ) {
         loop {
             if tx.is_closed() {
-                trace!("Channel closed, stopping discovery responses");
+                info!("Channel closed, stopping discovery queries");
                 break;
             }
 
-            if tokio::time::timeout(Duration::from_millis(100), transport.readable())
-                .await
-                .is_err()
-            {
-                continue;
+            let seen_addrs = seen_addrs.lock().await;
+            if seen_addrs.contains(&target) {
+                trace!("Target found, stopping discovery queries");
+                break;
             }
+            drop(seen_addrs);
 
-            let mut buf = [0; 2048];
-
-            // Try to recv data, this may still fail with `WouldBlock`
-            // if the readiness event is a false positive.
-            match transport.try_recv_from(&mut buf) {
-                Ok((size, addr)) => {
-                    let mut seen_addrs = seen_addrs.lock().await;
-                    if seen_addrs.contains(&addr) {
-                        continue;
-                    } else {
-                        seen_addrs.push(addr);
-                    }
-                    drop(seen_addrs);
-
-                    if size > 16 && log_enabled!(Level::Debug) {
-                        debug!("Received discovery response from {addr:?}");
-                        let message: String = String::from_utf8_lossy(&buf[16..]).to_string();
-                        debug!("Discovery response message: {message}");
-                    }
-
-                    tokio::spawn(Self::process_discovery_response(
-                        client.clone(),
-                        addr.ip(),
-                        target.ip(),
-                        tx.clone(),
-                    ));
-                }
-                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                    continue;
-                }
-                Err(e) => {
-                    let error =
-                        anyhow::Error::from(e).context("Failed to receive discovery response");
-                    tx.send(Some(Err(error.into()))).await.ok();
-                    break;
-                }
-            }
+            transport.send_to(&aes_discovery_query, target).await?;
+
+            tokio::time::sleep(DISCOVERY_INTERVAL).await;
         }
+
+        trace!("Discovery queries finished");
+
+        Ok::<_, anyhow::Error>(())
     }
+    .await;
 
-    async fn process_discovery_response(
-        client: Arc<RwLock<ApiClient>>,
-        ip_addr: IpAddr,
-        target: IpAddr,
-        tx: mpsc::Sender<Option<Result<DiscoveryResult, Error>>>,
-    ) {
-        let client = client.read().await.clone();
+    if let Err(e) = result {
+        let _ = error_handling_tx.send(Some(Err(e.into()))).await;
+    }
+}
 
-        let result = DiscoveryResult::new(client, ip_addr).await;
+async fn receive_discovery_response<T, F>(
+    transport: Arc<UdpSocket>,
+    target: SocketAddr,
+    seen_addrs: Arc<Mutex<Vec<SocketAddr>>>,
+    tx: mpsc::Sender<Option<Result<T, Error>>>,
+    on_response: F,
+) where
+    F: Fn(SocketAddr, Vec<u8>, SocketAddr, mpsc::Sender<Option<Result<T, Error>>>),
+{
+    loop {
+        if tx.is_closed() {
+            trace!("Channel closed, stopping discovery responses");
+            break;
+        }
 
-        let _ = tx.send(Some(result)).await;
+        if tokio::time::timeout(Duration::from_millis(100), transport.readable())
+            .await
+            .is_err()
+        {
+            continue;
+        }
 
-        if ip_addr == target {
-            debug!("Target found, stopping discovery responses");
-            let _ = tx.send(None).await;
+        let mut buf = [0; 2048];
+
+        // Try to recv data, this may still fail with `WouldBlock`
+        // if the readiness event is a false positive.
+        match transport.try_recv_from(&mut buf) {
+            Ok((size, addr)) => {
+                let mut seen_addrs = seen_addrs.lock().await;
+                if seen_addrs.contains(&addr) {
+                    continue;
+                } else {
+                    seen_addrs.push(addr);
+                }
+                drop(seen_addrs);
+
+                if size > 16 && log_enabled!(Level::Debug) {
+                    debug!("Received discovery response from {addr:?}");
+                    let message: String = String::from_utf8_lossy(&buf[16..size]).to_string();
+                    debug!("Discovery response message: {message}");
+                }
+
+                on_response(addr, buf[..size].to_vec(), target, tx.clone());
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                continue;
+            }
+            Err(e) => {
+                let error = anyhow::Error::from(e).context("Failed to receive discovery response");
+                tx.send(Some(Err(error.into()))).await.ok();
+                break;
+            }
         }
     }
 }
@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+
+use log::{debug, trace};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::time::Duration;
+use tokio_stream::Stream;
+
+use super::aes_discovery_query_generator::AesDiscoveryQueryGenerator;
+use super::device_announcement::DeviceAnnouncement;
+use super::local_networks::local_broadcast_addresses;
+use crate::Error;
+
+/// A change in the devices seen by a [`DiscoveryWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiscoveryEvent {
+    /// A device answered for the first time, or again after it disappeared.
+    Appeared(DeviceAnnouncement),
+    /// A device answered from a different IP address, e.g. after a DHCP renewal.
+    AddressChanged {
+        /// The announcement received from the new address.
+        announcement: DeviceAnnouncement,
+        /// The address the device answered from before.
+        previous_ip: IpAddr,
+    },
+    /// A device stopped answering. Contains its last announcement.
+    Disappeared(DeviceAnnouncement),
+}
+
+/// Long-running discovery that re-broadcasts discovery queries periodically
+/// and reports the devices that appear, change address or disappear.
+///
+/// Devices are told apart by their MAC address. Like [`crate::AnnouncementDiscovery`],
+/// the watcher does not log into the devices.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use tapo::{DiscoveryEvent, DiscoveryWatcher, StreamExt};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut events = DiscoveryWatcher::new(["192.168.1.255", "192.168.2.255"])
+///         .with_interval(Duration::from_secs(30))
+///         .with_missed_rounds(3)
+///         .start()
+///         .await?;
+///
+///     while let Some(event) = events.next().await {
+///         match event? {
+///             DiscoveryEvent::Appeared(device) => println!("{} appeared at {}", device.mac, device.ip),
+///             DiscoveryEvent::AddressChanged { announcement, previous_ip } => {
+///                 println!("{} moved from {previous_ip} to {}", announcement.mac, announcement.ip)
+///             }
+///             DiscoveryEvent::Disappeared(device) => println!("{} disappeared", device.mac),
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiscoveryWatcher {
+    targets: Vec<String>,
+    interval: Duration,
+    missed_rounds: u32,
+}
+
+impl DiscoveryWatcher {
+    /// Creates a watcher that broadcasts every 60 seconds and reports a device as disappeared
+    /// after it missed 3 rounds.
+    ///
+    /// # Arguments
+    ///
+    /// * `targets` - the unicast or broadcast IP addresses to send the discovery queries to,
+    ///   e.g. one broadcast address per interface. When empty, the broadcast addresses of the
+    ///   local networks are used, see [`local_broadcast_addresses`].
+    pub fn new<I, T>(targets: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        Self {
+            targets: targets.into_iter().map(Into::into).collect(),
+            interval: Duration::from_secs(60),
+            missed_rounds: 3,
+        }
+    }
+
+    /// Changes how often the discovery queries are sent.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - must be at least `1` second.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_secs(1));
+        self
+    }
+
+    /// Changes how many rounds in a row a device may not answer before it is reported as disappeared.
+    ///
+    /// # Arguments
+    ///
+    /// * `missed_rounds` - must be at least `1`.
+    pub fn with_missed_rounds(mut self, missed_rounds: u32) -> Self {
+        self.missed_rounds = missed_rounds.max(1);
+        self
+    }
+
+    /// Starts watching. The watcher runs in the background until the returned stream is dropped.
+    pub async fn start(self) -> Result<DiscoveryEvents, Error> {
+        let mut targets = Vec::with_capacity(self.targets.len());
+        for target in &self.targets {
+            let ip_addr: IpAddr = target.parse().map_err(|_| Error::Validation {
+                field: "targets".to_string(),
+                message: format!("{target} is not an IP address"),
+            })?;
+            targets.push(SocketAddr::new(ip_addr, 20002));
+        }
+
+        if targets.is_empty() {
+            targets = local_broadcast_addresses()?
+                .into_iter()
+                .map(|ip_addr| SocketAddr::new(ip_addr, 20002))
+                .collect();
+            debug!("Watching the local networks at {targets:?}");
+        }
+
+        if targets.is_empty() {
+            return Err(Error::Validation {
+                field: "targets".to_string(),
+                message: "No target given and no local network found".to_string(),
+            });
+        }
+
+        let query_generator = AesDiscoveryQueryGenerator::new()?;
+        let (messages_tx, messages_rx) = mpsc::channel(1024);
+
+        let mut transports = Vec::with_capacity(targets.len());
+        for target in targets {
+            let bind_address = match target.ip() {
+                IpAddr::V4(_) => "0.0.0.0:0", // IPv4
+                IpAddr::V6(_) => "[::]:0",    // IPv6
+            };
+
+            let transport = UdpSocket::bind(bind_address)
+                .await
+                .map_err(anyhow::Error::from)?;
+            transport.set_broadcast(true).map_err(anyhow::Error::from)?;
+            let transport = Arc::new(transport);
+
+            tokio::spawn(receive_responses(transport.clone(), messages_tx.clone()));
+            transports.push((transport, target));
+        }
+
+        tokio::spawn(tick(self.interval, messages_tx));
+
+        let (tx, rx) = mpsc::channel(1024);
+        tokio::spawn(watch(
+            transports,
+            query_generator,
+            messages_rx,
+            DeviceTracker::new(self.missed_rounds),
+            tx,
+        ));
+
+        Ok(DiscoveryEvents { rx })
+    }
+}
+
+/// Stream of [`DiscoveryEvent`]s returned by [`DiscoveryWatcher::start`].
+pub struct DiscoveryEvents {
+    rx: Receiver<Result<DiscoveryEvent, Error>>,
+}
+
+impl Stream for DiscoveryEvents {
+    type Item = Result<DiscoveryEvent, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<DiscoveryEvent, Error>>> {
+        Pin::new(&mut self.rx).poll_recv(cx)
+    }
+}
+
+enum WatcherMessage {
+    Tick,
+    Response(SocketAddr, Vec<u8>),
+}
+
+async fn tick(interval: Duration, tx: Sender<WatcherMessage>) {
+    while tx.send(WatcherMessage::Tick).await.is_ok() {
+        tokio::time::sleep(interval).await;
+    }
+    trace!("Discovery watcher stopped, stopping discovery queries");
+}
+
+async fn receive_responses(transport: Arc<UdpSocket>, tx: Sender<WatcherMessage>) {
+    let mut buf = [0; 2048];
+
+    while !tx.is_closed() {
+        if tokio::time::timeout(Duration::from_millis(100), transport.readable())
+            .await
+            .is_err()
+        {
+            continue;
+        }
+
+        // Try to recv data, this may still fail with `WouldBlock`
+        // if the readiness event is a false positive.
+        match transport.try_recv_from(&mut buf) {
+            Ok((size, addr)) => {
+                let _ = tx
+                    .send(WatcherMessage::Response(addr, buf[..size].to_vec()))
+                    .await;
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Err(e) => debug!("Failed to receive discovery response: {e}"),
+        }
+    }
+    trace!("Discovery watcher stopped, stopping discovery responses");
+}
+
+async fn watch(
+    transports: Vec<(Arc<UdpSocket>, SocketAddr)>,
+    mut query_generator: AesDiscoveryQueryGenerator,
+    mut messages: Receiver<WatcherMessage>,
+    mut tracker: DeviceTracker,
+    tx: Sender<Result<DiscoveryEvent, Error>>,
+) {
+    while let Some(message) = messages.recv().await {
+        let events = match message {
+            WatcherMessage::Tick => {
+                let query = match query_generator.generate() {
+                    Ok(query) => query,
+                    Err(e) => {
+                        let _ = tx.send(Err(e.into())).await;
+                        break;
+                    }
+                };
+
+                for (transport, target) in &transports {
+                    if let Err(e) = transport.send_to(&query, target).await {
+                        debug!("Failed to send discovery query to {target}: {e}");
+                    }
+                }
+
+                tracker.next_round().into_iter().map(Ok).collect()
+            }
+            WatcherMessage::Response(addr, response) => {
+                match DeviceAnnouncement::parse(addr.ip(), &response) {
+                    Ok(announcement) => tracker.seen(announcement).map(Ok).into_iter().collect(),
+                    Err(e) => vec![Err(e)],
+                }
+            }
+        };
+
+        for event in events {
+            if tx.send(event).await.is_err() {
+                trace!("Channel closed, stopping discovery watcher");
+                return;
+            }
+        }
+    }
+}
+
+struct TrackedDevice {
+    announcement: DeviceAnnouncement,
+    last_seen_round: u64,
+}
+
+/// Turns the announcements received in each round into [`DiscoveryEvent`]s.
+struct DeviceTracker {
+    devices: HashMap<String, TrackedDevice>,
+    round: u64,
+    missed_rounds: u32,
+}
+
+impl DeviceTracker {
+    fn new(missed_rounds: u32) -> Self {
+        Self {
+            devices: HashMap::new(),
+            round: 0,
+            missed_rounds,
+        }
+    }
+
+    fn seen(&mut self, announcement: DeviceAnnouncement) -> Option<DiscoveryEvent> {
+        let round = self.round;
+
+        match self.devices.get_mut(&announcement.mac) {
+            Some(device) => {
+                device.last_seen_round = round;
+                let previous_ip = device.announcement.ip;
+                device.announcement = announcement.clone();
+
+                (previous_ip != announcement.ip).then_some(DiscoveryEvent::AddressChanged {
+                    announcement,
+                    previous_ip,
+                })
+            }
+            None => {
+                self.devices.insert(
+                    announcement.mac.clone(),
+                    TrackedDevice {
+                        announcement: announcement.clone(),
+                        last_seen_round: round,
+                    },
+                );
+
+                Some(DiscoveryEvent::Appeared(announcement))
+            }
+        }
+    }
+
+    /// Starts a new round and returns the devices that missed too many rounds.
+    fn next_round(&mut self) -> Vec<DiscoveryEvent> {
+        self.round += 1;
+
+        let round = self.round;
+        let missed_rounds = self.missed_rounds as u64;
+        let disappeared: Vec<String> = self
+            .devices
+            .iter()
+            .filter(|(_, device)| round - device.last_seen_round > missed_rounds)
+            .map(|(mac, _)| mac.clone())
+            .collect();
+
+        disappeared
+            .into_iter()
+            .filter_map(|mac| self.devices.remove(&mac))
+            .map(|device| DiscoveryEvent::Disappeared(device.announcement))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(mac: &str, ip: &str) -> DeviceAnnouncement {
+        DeviceAnnouncement {
+            ip: ip.parse().unwrap(),
+            mac: mac.to_string(),
+            device_id: format!("id-{mac}"),
+            model: "P110".to_string(),
+            device_type: "SMART.TAPOPLUG".to_string(),
+            owner: None,
+            hw_ver: None,
+            factory_default: false,
+            encryption: None,
+        }
+    }
+
+    #[test]
+    fn test_appeared_and_address_changed() {
+        let mut tracker = DeviceTracker::new(3);
+        let first = announcement("AA", "192.168.1.10");
+
+        assert_eq!(
+            tracker.seen(first.clone()),
+            Some(DiscoveryEvent::Appeared(first.clone()))
+        );
+        assert_eq!(tracker.seen(first.clone()), None);
+
+        tracker.next_round();
+        let moved = announcement("AA", "192.168.1.20");
+        assert_eq!(
+            tracker.seen(moved.clone()),
+            Some(DiscoveryEvent::AddressChanged {
+                announcement: moved,
+                previous_ip: first.ip,
+            })
+        );
+    }
+
+    #[test]
+    fn test_disappeared_after_missed_rounds() {
+        let mut tracker = DeviceTracker::new(2);
+        let device = announcement("AA", "192.168.1.10");
+        let other = announcement("BB", "192.168.1.11");
+        tracker.seen(device.clone());
+        tracker.seen(other.clone());
+
+        assert!(tracker.next_round().is_empty());
+        tracker.seen(other.clone());
+        assert!(tracker.next_round().is_empty());
+        assert_eq!(
+            tracker.next_round(),
+            [DiscoveryEvent::Disappeared(device.clone())]
+        );
+        tracker.seen(other);
+        assert!(tracker.next_round().is_empty());
+
+        // A device that comes back appears again
+        assert_eq!(
+            tracker.seen(device.clone()),
+            Some(DiscoveryEvent::Appeared(device))
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::net::{IpAddr, Ipv4Addr};
+
+use anyhow::Context;
+use ipnet::Ipv4Net;
+
+use crate::Error;
+
+/// Returns the broadcast address of every IPv4 network this host is connected to,
+/// e.g. `192.168.1.255` for an interface with the address `192.168.1.20/24`.
+///
+/// Loopback and point-to-point interfaces, and interfaces that are down, are skipped.
+/// Use the addresses as discovery targets when the network of the devices is not known in advance.
+pub fn local_broadcast_addresses() -> Result<Vec<IpAddr>, Error> {
+    let interfaces =
+        if_addrs::get_if_addrs().context("Failed to list the local network interfaces")?;
+
+    let mut addresses = Vec::new();
+    for interface in interfaces {
+        if interface.is_loopback() || interface.is_p2p() || !interface.is_oper_up() {
+            continue;
+        }
+
+        if let if_addrs::IfAddr::V4(addr) = &interface.addr {
+            let broadcast = addr
+                .broadcast
+                .unwrap_or_else(|| broadcast_address(addr.ip, addr.prefixlen));
+            if !addresses.contains(&IpAddr::V4(broadcast)) {
+                addresses.push(IpAddr::V4(broadcast));
+            }
+        }
+    }
+
+    Ok(addresses)
+}
+
+fn broadcast_address(ip: Ipv4Addr, prefix_len: u8) -> Ipv4Addr {
+    Ipv4Net::new(ip, prefix_len)
+        .map(|network| network.broadcast())
+        .unwrap_or(Ipv4Addr::BROADCAST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_broadcast_address() {
+        assert_eq!(
+            broadcast_address(Ipv4Addr::new(192, 168, 1, 20), 24),
+            Ipv4Addr::new(192, 168, 1, 255)
+        );
+        assert_eq!(
+            broadcast_address(Ipv4Addr::new(10, 20, 3, 4), 22),
+            Ipv4Addr::new(10, 20, 3, 255)
+        );
+        assert_eq!(
+            broadcast_address(Ipv4Addr::new(10, 20, 3, 4), 33),
+            Ipv4Addr::BROADCAST
+        );
+    }
+}
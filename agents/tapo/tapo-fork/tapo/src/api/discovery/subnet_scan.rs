@@ -0,0 +1,345 @@
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+
+use ipnet::Ipv4Net;
+use log::{debug, trace};
+use tokio::net::UdpSocket;
+use tokio::sync::Semaphore;
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::time::Duration;
+use tokio_stream::Stream;
+
+use super::aes_discovery_query_generator::AesDiscoveryQueryGenerator;
+use super::device_announcement::DeviceAnnouncement;
+use super::discovery_result::DiscoveryResult;
+use crate::requests::{EmptyParams, TapoParams, TapoRequest};
+use crate::{AnyDevice, ApiClient, Error, HttpTransport, Transport, TransportRequest};
+
+// Larger networks take too long to probe host by host.
+const MIN_PREFIX_LEN: u8 = 16;
+
+/// How [`SubnetScan`] probes each host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanProbe {
+    /// Sends the discovery query to the host. Returns the [`DeviceAnnouncement`] of the device.
+    Udp,
+    /// Sends an unauthenticated `component_nego` request to the host.
+    /// Finds devices that do not answer discovery queries, but without their [`DeviceAnnouncement`].
+    Http,
+    /// Sends the discovery query, and the `component_nego` request to the hosts that do not answer it.
+    UdpThenHttp,
+}
+
+/// A host found by [`SubnetScan`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScannedHost {
+    /// The IP address of the host.
+    pub ip: IpAddr,
+    /// The announcement of the device, if it answered the discovery query.
+    pub announcement: Option<DeviceAnnouncement>,
+}
+
+impl ScannedHost {
+    /// Logs into the device with the credentials of the given [`ApiClient`]
+    /// and returns the matching [`DiscoveryResult`].
+    pub async fn connect(&self, client: ApiClient) -> Result<DiscoveryResult, Error> {
+        DiscoveryResult::new(client, self.ip).await
+    }
+
+    /// Logs into the device with the credentials of the given [`ApiClient`]
+    /// and returns it as an [`AnyDevice`]. See [`ApiClient::connect`].
+    pub async fn connect_any(&self, client: ApiClient) -> Result<AnyDevice, Error> {
+        client.connect(self.ip.to_string()).await
+    }
+}
+
+/// Discovery for networks that do not forward broadcasts, e.g. a separate VLAN.
+/// Probes every host of an IPv4 network one by one, without logging into the devices.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tapo::{ScanProbe, StreamExt, SubnetScan};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let mut hosts = SubnetScan::new("10.20.0.0/24")?
+///         .with_probe(ScanProbe::UdpThenHttp)
+///         .with_concurrency(32)
+///         .start()
+///         .await?;
+///
+///     while let Some(host) = hosts.next().await {
+///         let host = host?;
+///         match host.announcement {
+///             Some(announcement) => println!("{} at {}", announcement.model, host.ip),
+///             None => println!("Unknown device at {}", host.ip),
+///         }
+///     }
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct SubnetScan {
+    network: Ipv4Net,
+    probe: ScanProbe,
+    concurrency: usize,
+    timeout: Duration,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl SubnetScan {
+    /// Creates a scan of the given network that probes 64 hosts at a time with [`ScanProbe::UdpThenHttp`],
+    /// waiting up to 1 second for each probe.
+    ///
+    /// # Arguments
+    ///
+    /// * `cidr` - the IPv4 network to scan, e.g. `10.20.0.0/24`. The prefix must be at least `/16`.
+    pub fn new(cidr: &str) -> Result<Self, Error> {
+        let network: Ipv4Net = cidr.parse().map_err(|_| Error::Validation {
+            field: "cidr".to_string(),
+            message: format!("{cidr} is not an IPv4 network, e.g. 10.20.0.0/24"),
+        })?;
+
+        if network.prefix_len() < MIN_PREFIX_LEN {
+            return Err(Error::Validation {
+                field: "cidr".to_string(),
+                message: format!("The prefix must be at least /{MIN_PREFIX_LEN}"),
+            });
+        }
+
+        Ok(Self {
+            network: network.trunc(),
+            probe: ScanProbe::UdpThenHttp,
+            concurrency: 64,
+            timeout: Duration::from_secs(1),
+            transport: None,
+        })
+    }
+
+    /// Changes how each host is probed.
+    pub fn with_probe(mut self, probe: ScanProbe) -> Self {
+        self.probe = probe;
+        self
+    }
+
+    /// Changes how many hosts are probed at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - must be at least `1`.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Changes how long to wait for each probe.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends the `component_nego` requests with the given [`Transport`] instead of [`HttpTransport`].
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Starts the scan. The hosts that answer are returned as they are found.
+    pub async fn start(self) -> Result<ScannedHosts, Error> {
+        let query = Arc::new(AesDiscoveryQueryGenerator::new()?.generate()?);
+        let transport = match self.transport {
+            Some(transport) => transport,
+            None => Arc::new(HttpTransport::new(self.timeout)?),
+        };
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let (tx, rx) = mpsc::channel(1024);
+
+        let network = self.network;
+        let probe = self.probe;
+        let timeout = self.timeout;
+
+        tokio::spawn(async move {
+            for host in network.hosts() {
+                let Ok(permit) = semaphore.clone().acquire_owned().await else {
+                    break;
+                };
+                if tx.is_closed() {
+                    trace!("Channel closed, stopping subnet scan");
+                    break;
+                }
+
+                let tx = tx.clone();
+                let query = query.clone();
+                let transport = transport.clone();
+                tokio::spawn(async move {
+                    let ip = IpAddr::V4(host);
+                    let result = probe_host(ip, probe, &query, transport.as_ref(), timeout).await;
+                    drop(permit);
+
+                    let result = match result {
+                        Ok(Some(host)) => Ok(host),
+                        Ok(None) => return,
+                        Err(e) => Err(e),
+                    };
+                    let _ = tx.send(result).await;
+                });
+            }
+            trace!("Subnet scan of {network} queued");
+        });
+
+        Ok(ScannedHosts { rx })
+    }
+}
+
+/// Stream of [`ScannedHost`]s returned by [`SubnetScan::start`].
+pub struct ScannedHosts {
+    rx: Receiver<Result<ScannedHost, Error>>,
+}
+
+impl Stream for ScannedHosts {
+    type Item = Result<ScannedHost, Error>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<ScannedHost, Error>>> {
+        Pin::new(&mut self.rx).poll_recv(cx)
+    }
+}
+
+async fn probe_host(
+    ip: IpAddr,
+    probe: ScanProbe,
+    query: &[u8],
+    transport: &dyn Transport,
+    timeout: Duration,
+) -> Result<Option<ScannedHost>, Error> {
+    if matches!(probe, ScanProbe::Udp | ScanProbe::UdpThenHttp)
+        && let Some(announcement) = probe_udp(ip, query, timeout).await?
+    {
+        return Ok(Some(ScannedHost {
+            ip,
+            announcement: Some(announcement),
+        }));
+    }
+
+    if matches!(probe, ScanProbe::Http | ScanProbe::UdpThenHttp) && probe_http(ip, transport).await
+    {
+        return Ok(Some(ScannedHost {
+            ip,
+            announcement: None,
+        }));
+    }
+
+    Ok(None)
+}
+
+async fn probe_udp(
+    ip: IpAddr,
+    query: &[u8],
+    timeout: Duration,
+) -> Result<Option<DeviceAnnouncement>, Error> {
+    let socket = UdpSocket::bind("0.0.0.0:0")
+        .await
+        .map_err(anyhow::Error::from)?;
+
+    if let Err(e) = socket.send_to(query, SocketAddr::new(ip, 20002)).await {
+        debug!("Failed to send discovery query to {ip}: {e}");
+        return Ok(None);
+    }
+
+    let mut buf = [0; 2048];
+    let response = tokio::time::timeout(timeout, async {
+        loop {
+            match socket.recv_from(&mut buf).await {
+                Ok((size, addr)) if addr.ip() == ip => return Some(size),
+                Ok(_) => continue,
+                Err(e) => {
+                    debug!("Failed to receive discovery response from {ip}: {e}");
+                    return None;
+                }
+            }
+        }
+    })
+    .await;
+
+    match response {
+        Ok(Some(size)) => DeviceAnnouncement::parse(ip, &buf[..size]).map(Some),
+        _ => Ok(None),
+    }
+}
+
+// Any Tapo response, including errors, means there is a device at the address.
+async fn probe_http(ip: IpAddr, transport: &dyn Transport) -> bool {
+    let request = TapoRequest::ComponentNegotiation(TapoParams::new(EmptyParams));
+    let Ok(body) = serde_json::to_vec(&request) else {
+        return false;
+    };
+
+    let response = transport
+        .send(TransportRequest {
+            url: format!("http://{ip}/app"),
+            cookie: None,
+            body,
+        })
+        .await;
+
+    match response {
+        Ok(response) => serde_json::from_slice::<serde_json::Value>(&response.body)
+            .is_ok_and(|body| body.get("error_code").is_some()),
+        Err(e) => {
+            trace!("No device at {ip}: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+
+    use crate::api::protocol::SimulatedDevice;
+
+    use super::*;
+
+    #[test]
+    fn test_new_validates_cidr() {
+        assert!(SubnetScan::new("10.20.0.0/24").is_ok());
+        assert!(SubnetScan::new("10.20.0.0").is_err());
+        assert!(SubnetScan::new("10.0.0.0/8").is_err());
+        assert!(SubnetScan::new("fd00::/120").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_http_probe() {
+        let device = Arc::new(SimulatedDevice::new("username", "password"));
+
+        let hosts: Vec<_> = SubnetScan::new("10.20.0.0/30")
+            .unwrap()
+            .with_probe(ScanProbe::Http)
+            .with_transport(device.clone())
+            .start()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        let mut ips: Vec<IpAddr> = hosts.into_iter().map(|host| host.unwrap().ip).collect();
+        ips.sort();
+        assert_eq!(
+            ips,
+            [
+                "10.20.0.1".parse::<IpAddr>().unwrap(),
+                "10.20.0.2".parse().unwrap()
+            ]
+        );
+
+        let mut urls = device.urls.lock().unwrap().clone();
+        urls.sort();
+        assert_eq!(urls, ["http://10.20.0.1/app", "http://10.20.0.2/app"]);
+    }
+}
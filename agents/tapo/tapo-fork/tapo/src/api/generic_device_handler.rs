@@ -5,12 +5,12 @@ use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::api::{ApiClient, ApiClientExt};
 use crate::error::Error;
-use crate::requests::GenericSetDeviceInfoParams;
 use crate::responses::DeviceInfoGenericResult;
 
 use super::{
-    ColorLightHandler, HandlerExt, HubHandler, LightHandler, PlugEnergyMonitoringHandler,
-    PlugHandler, PowerStripEnergyMonitoringHandler, PowerStripHandler, RgbLightStripHandler,
+    ColorLightHandler, ComponentListExt, DeviceInfoExt, DeviceTimeExt, FirmwareExt, HandlerExt,
+    HubHandler, LightHandler, OnOffExt, PlugEnergyMonitoringHandler, PlugHandler,
+    PowerStripEnergyMonitoringHandler, PowerStripHandler, RgbLightStripHandler,
     RgbicLightStripHandler,
 };
 
@@ -34,18 +34,6 @@ impl GenericDeviceHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
-        self.client.read().await.set_device_info(json).await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
-        self.client.read().await.set_device_info(json).await
-    }
-
     /// Returns *device info* as [`DeviceInfoGenericResult`].
     /// It is not guaranteed to contain all the properties returned from the Tapo API.
     /// If the deserialization fails, or if a property that you care about it's not present, try [`GenericDeviceHandler::get_device_info_json`].
@@ -70,6 +58,16 @@ impl HandlerExt for GenericDeviceHandler {
     }
 }
 
+impl DeviceInfoExt for GenericDeviceHandler {}
+
+impl DeviceTimeExt for GenericDeviceHandler {}
+
+impl FirmwareExt for GenericDeviceHandler {}
+
+impl ComponentListExt for GenericDeviceHandler {}
+
+impl OnOffExt for GenericDeviceHandler {}
+
 impl From<GenericDeviceHandler> for LightHandler {
     fn from(value: GenericDeviceHandler) -> Self {
         LightHandler::new(value.client)
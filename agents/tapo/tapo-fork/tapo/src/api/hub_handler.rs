@@ -1,51 +1,91 @@
 use async_trait::async_trait;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::{AlarmDuration, AlarmRingtone, AlarmVolume, PlayAlarmParams};
-use crate::responses::{ChildDeviceHubResult, ChildDeviceListHubResult, DeviceInfoHubResult};
+use crate::requests::{
+    AlarmDuration, AlarmRingtone, AlarmVolume, ChildDeviceListParams, PlayAlarmParams,
+};
+use crate::responses::{
+    ChildDeviceHubPage, ChildDeviceHubResult, ChildDeviceListHubResult, ChildDeviceScanResult,
+    DeviceInfoHubResult, PairingCandidate,
+};
 
 use super::{
-    ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt, KE100Handler, S200BHandler,
-    T31XHandler, T100Handler, T110Handler, T300Handler,
+    ApiClient, ApiClientExt, ChildDevicesExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, KE100Handler, S200BHandler, T31XHandler, T100Handler,
+    T110Handler, T300Handler,
 };
 
 macro_rules! get_device_id {
     ($self:expr, $identifier:expr, $($value:path),+) => {{
-        let children = $self.get_child_device_list().await?;
-
-        match $identifier {
-            HubDevice::ByDeviceId(device_id) => children
-                .iter()
-                .filter_map(|d| match d {
-                    $($value(child) if child.device_id == device_id => Some(child.device_id.clone()),)+
-                    _ => None,
-                })
-                .next()
-                .ok_or_else(|| Error::DeviceNotFound)?,
-            HubDevice::ByNickname(nickname) => children
-                .iter()
-                .filter_map(|d| match d {
-                    $($value(child) if child.nickname == nickname => Some(child.device_id.clone()),)+
-                    _ => None,
-                })
-                .next()
-                .ok_or_else(|| Error::DeviceNotFound)?,
-        }
+        $self
+            .find_child_device(&$identifier, |child| matches!(child, $($value(_))|+))
+            .await?
     }};
 }
 
+/// Child devices of a hub, indexed by device id and nickname.
+#[derive(Debug, Default)]
+struct ChildDeviceIndex {
+    by_device_id: HashMap<String, ChildDeviceHubResult>,
+    // Nicknames are not unique, e.g. a sensor and a button in the same room.
+    by_nickname: HashMap<String, Vec<String>>,
+}
+
+impl ChildDeviceIndex {
+    fn new(children: &[ChildDeviceHubResult]) -> Self {
+        let mut index = Self::default();
+
+        for child in children {
+            let (Some(device_id), Some(nickname)) = (child.device_id(), child.nickname()) else {
+                continue;
+            };
+            index
+                .by_nickname
+                .entry(nickname.to_string())
+                .or_default()
+                .push(device_id.to_string());
+            index
+                .by_device_id
+                .insert(device_id.to_string(), child.clone());
+        }
+
+        index
+    }
+
+    fn find(
+        &self,
+        identifier: &HubDevice,
+        is_model: impl Fn(&ChildDeviceHubResult) -> bool,
+    ) -> Option<String> {
+        let device_ids = match identifier {
+            HubDevice::ByDeviceId(device_id) => std::slice::from_ref(device_id),
+            HubDevice::ByNickname(nickname) => self.by_nickname.get(nickname)?.as_slice(),
+        };
+
+        device_ids
+            .iter()
+            .find(|device_id| self.by_device_id.get(*device_id).is_some_and(&is_model))
+            .cloned()
+    }
+}
+
 /// Handler for the [H100](https://www.tapo.com/en/search/?q=H100) devices.
 #[derive(Debug)]
 pub struct HubHandler {
     client: Arc<RwLock<ApiClient>>,
+    children: RwLock<Option<ChildDeviceIndex>>,
 }
 
 /// Hub handler methods.
 impl HubHandler {
     pub(crate) fn new(client: Arc<RwLock<ApiClient>>) -> Self {
-        Self { client }
+        Self {
+            client,
+            children: RwLock::new(None),
+        }
     }
 
     /// Refreshes the authentication session.
@@ -67,58 +107,52 @@ impl HubHandler {
         self.client.read().await.get_device_info().await
     }
 
-    /// Returns *child device list* as [`ChildDeviceHubResult`].
+    /// Returns *child device list* as [`ChildDeviceHubResult`], fetching every page.
     /// It is not guaranteed to contain all the properties returned from the Tapo API
     /// or to support all the possible devices connected to the hub.
     /// If the deserialization fails, or if a property that you care about it's not present, try [`HubHandler::get_child_device_list_json`].
+    ///
+    /// The child devices are also indexed by device id and nickname,
+    /// so that the child device handlers, e.g. [`HubHandler::t310`], can be created without another request.
     pub async fn get_child_device_list(&self) -> Result<Vec<ChildDeviceHubResult>, Error> {
         let mut results = Vec::new();
-        let mut start_index = 0;
-        let mut fetch = true;
-
-        while fetch {
-            let devices = self
-                .client
-                .read()
-                .await
-                .get_child_device_list::<ChildDeviceListHubResult>(start_index)
-                .await
-                .map(|r| r.devices)?;
-
-            fetch = devices.len() == 10;
-            start_index += 10;
-            results.extend(devices);
+        let mut start_index = Some(0);
+
+        while let Some(index) = start_index {
+            let page = self.get_child_device_list_page(index).await?;
+            start_index = page.next_start_index();
+            results.extend(page.devices);
         }
 
+        *self.children.write().await = Some(ChildDeviceIndex::new(&results));
+
         Ok(results)
     }
 
-    /// Returns *child device list* as [`serde_json::Value`].
-    /// It contains all the properties returned from the Tapo API.
+    /// Returns a single page of the *child device list* as [`ChildDeviceHubPage`].
+    /// Use [`ChildDeviceHubPage::next_start_index`] to fetch the next page,
+    /// e.g. to resume listing the child devices after an error.
     ///
     /// # Arguments
     ///
-    /// * `start_index` - the index to start fetching the child device list.
-    ///   It should be `0` for the first page, `10` for the second, and so on.
-    pub async fn get_child_device_list_json(
+    /// * `start_index` - the index to start fetching the child device list, `0` for the first page.
+    pub async fn get_child_device_list_page(
         &self,
         start_index: u64,
-    ) -> Result<serde_json::Value, Error> {
+    ) -> Result<ChildDeviceHubPage, Error> {
         self.client
             .read()
             .await
-            .get_child_device_list(start_index)
+            .get_child_device_list::<ChildDeviceListHubResult>(start_index)
             .await
+            .map(ChildDeviceHubPage::from)
     }
 
-    /// Returns *child device component list* as [`serde_json::Value`].
-    /// This information is useful in debugging or when investigating new functionality to add.
-    pub async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
-        self.client
-            .read()
-            .await
-            .get_child_device_component_list()
-            .await
+    /// Fetches the *child device list* again and rebuilds the index used to create the child device handlers.
+    /// Call it after the nickname of a child device was changed in the Tapo app.
+    /// Child devices that are not in the index yet are looked up again automatically.
+    pub async fn refresh_child_devices(&self) -> Result<(), Error> {
+        self.get_child_device_list().await.map(|_| ())
     }
 
     /// Returns a list of ringtones (alarm types) supported by the hub.
@@ -152,8 +186,109 @@ impl HubHandler {
     }
 }
 
+/// Child device pairing methods.
+impl HubHandler {
+    /// Puts the hub into pairing mode, so that it looks for child devices that are in pairing mode too.
+    /// Poll [`HubHandler::get_pairing_candidates`] for the devices found,
+    /// and pair them with [`HubHandler::pair`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use tapo::ApiClient;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let hub = ApiClient::new("tapo-username@example.com", "tapo-password")
+    ///     .h100("192.168.1.100")
+    ///     .await?;
+    ///
+    /// hub.start_pairing().await?;
+    /// tokio::time::sleep(Duration::from_secs(10)).await;
+    ///
+    /// let scan = hub.get_pairing_candidates().await?;
+    /// hub.pair(&scan.devices).await?;
+    /// hub.stop_pairing().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn start_pairing(&self) -> Result<(), Error> {
+        self.client.read().await.begin_scanning_child_device().await
+    }
+
+    /// Returns the child devices the hub has found since [`HubHandler::start_pairing`],
+    /// as [`ChildDeviceScanResult`].
+    pub async fn get_pairing_candidates(&self) -> Result<ChildDeviceScanResult, Error> {
+        self.client.read().await.get_scan_child_device_list().await
+    }
+
+    /// Pairs the given child devices, found by [`HubHandler::get_pairing_candidates`], with the hub.
+    ///
+    /// # Arguments
+    ///
+    /// * `candidates` - the child devices to pair.
+    pub async fn pair(&self, candidates: &[PairingCandidate]) -> Result<(), Error> {
+        if candidates.is_empty() {
+            return Err(Error::Validation {
+                field: "candidates".to_string(),
+                message: "At least one device must be given".to_string(),
+            });
+        }
+
+        self.client
+            .read()
+            .await
+            .add_child_device_list(ChildDeviceListParams::pair(candidates))
+            .await?;
+        self.children.write().await.take();
+
+        Ok(())
+    }
+
+    /// Takes the hub out of pairing mode.
+    pub async fn stop_pairing(&self) -> Result<(), Error> {
+        self.client.read().await.end_scanning_child_device().await
+    }
+
+    /// Removes the given child device from the hub.
+    /// The device has to be paired again before it can be used.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - the device id of the child device, e.g. from [`HubHandler::get_child_device_list`].
+    pub async fn remove_child_device(&self, device_id: &str) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .remove_child_device_list(ChildDeviceListParams::remove(device_id))
+            .await?;
+        self.children.write().await.take();
+
+        Ok(())
+    }
+}
+
 /// Child device handler builders.
 impl HubHandler {
+    // Looks the child device up in the index, fetching the child device list when
+    // the index is empty or does not know the device yet.
+    async fn find_child_device(
+        &self,
+        identifier: &HubDevice,
+        is_model: impl Fn(&ChildDeviceHubResult) -> bool,
+    ) -> Result<String, Error> {
+        if let Some(index) = self.children.read().await.as_ref()
+            && let Some(device_id) = index.find(identifier, &is_model)
+        {
+            return Ok(device_id);
+        }
+
+        let children = self.get_child_device_list().await?;
+        ChildDeviceIndex::new(&children)
+            .find(identifier, &is_model)
+            .ok_or(Error::DeviceNotFound)
+    }
+
     /// Returns a [`KE100Handler`] for the given [`HubDevice`].
     ///
     /// # Arguments
@@ -374,6 +509,16 @@ impl HandlerExt for HubHandler {
 
 impl DeviceManagementExt for HubHandler {}
 
+impl DeviceInfoExt for HubHandler {}
+
+impl DeviceTimeExt for HubHandler {}
+
+impl FirmwareExt for HubHandler {}
+
+impl ComponentListExt for HubHandler {}
+
+impl ChildDevicesExt for HubHandler {}
+
 /// Hub Device.
 pub enum HubDevice {
     /// By Device ID.
@@ -381,3 +526,70 @@ pub enum HubDevice {
     /// By Nickname.
     ByNickname(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn t100(device_id: &str, nickname: &str) -> ChildDeviceHubResult {
+        serde_json::from_value(json!({
+            "model": "T100",
+            "at_low_battery": false,
+            "avatar": "sensor_t100",
+            "bind_count": 1,
+            "category": "subg.trigger.motion-sensor",
+            "device_id": device_id,
+            "fw_ver": "1.12.0 Build 230512 Rel.103011",
+            "hw_id": "hw_id",
+            "hw_ver": "1.0",
+            "jamming_rssi": -115,
+            "jamming_signal_level": 1,
+            "mac": "AABBCCDDEEFF",
+            "nickname": nickname,
+            "oem_id": "oem_id",
+            "parent_device_id": "hub",
+            "region": "Europe/London",
+            "rssi": -60,
+            "signal_level": 3,
+            "specs": "EU",
+            "status": "online",
+            "type": "SMART.TAPOSENSOR",
+            "detected": false,
+            "lastOnboardingTimestamp": 1700000000,
+            "report_interval": 16,
+            "status_follow_edge": false,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_child_device_index() {
+        let index = ChildDeviceIndex::new(&[
+            t100("id-1", "Hall"),
+            t100("id-2", "Kitchen"),
+            t100("id-3", "Kitchen"),
+            ChildDeviceHubResult::Other,
+        ]);
+        let is_t100 = |child: &ChildDeviceHubResult| matches!(child, ChildDeviceHubResult::T100(_));
+        let is_t110 = |child: &ChildDeviceHubResult| matches!(child, ChildDeviceHubResult::T110(_));
+
+        assert_eq!(
+            index.find(&HubDevice::ByDeviceId("id-1".to_string()), is_t100),
+            Some("id-1".to_string())
+        );
+        assert_eq!(
+            index.find(&HubDevice::ByNickname("Kitchen".to_string()), is_t100),
+            Some("id-2".to_string())
+        );
+        assert_eq!(
+            index.find(&HubDevice::ByNickname("Hall".to_string()), is_t110),
+            None
+        );
+        assert_eq!(
+            index.find(&HubDevice::ByDeviceId("id-4".to_string()), is_t100),
+            None
+        );
+    }
+}
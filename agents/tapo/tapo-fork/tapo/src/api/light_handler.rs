@@ -4,10 +4,13 @@ use async_trait::async_trait;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::LightSetDeviceInfoParams;
+use crate::requests::{DefaultPowerState, GenericSetDeviceInfoParams};
 use crate::responses::{DeviceInfoLightResult, DeviceUsageEnergyMonitoringResult};
 
-use super::{ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt};
+use super::{
+    ApiClient, ApiClientExt, BrightnessExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, OnOffExt,
+};
 
 /// Handler for the [L510](https://www.tapo.com/en/search/?q=L510),
 /// [L520](https://www.tapo.com/en/search/?q=L520) and
@@ -28,24 +31,15 @@ impl LightHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        let client = RwLockReadGuard::map(
-            self.client.read().await,
-            |client: &ApiClient| -> &dyn ApiClientExt { client },
-        );
-
-        LightSetDeviceInfoParams::new(client).on().send().await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        let client = RwLockReadGuard::map(
-            self.client.read().await,
-            |client: &ApiClient| -> &dyn ApiClientExt { client },
-        );
-
-        LightSetDeviceInfoParams::new(client).off().send().await
+    /// Sets the state the device is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - either [`DefaultPowerState::On`] or [`DefaultPowerState::LastState`].
+    ///   Lights cannot default to [`DefaultPowerState::Off`].
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::light_default_state(state)?)?;
+        self.client.read().await.set_device_info(json).await
     }
 
     /// Returns *device info* as [`DeviceInfoLightResult`].
@@ -65,23 +59,6 @@ impl LightHandler {
     pub async fn get_device_usage(&self) -> Result<DeviceUsageEnergyMonitoringResult, Error> {
         self.client.read().await.get_device_usage().await
     }
-
-    /// Sets the *brightness* and turns *on* the device.
-    ///
-    /// # Arguments
-    ///
-    /// * `brightness` - between 1 and 100
-    pub async fn set_brightness(&self, brightness: u8) -> Result<(), Error> {
-        let client = RwLockReadGuard::map(
-            self.client.read().await,
-            |client: &ApiClient| -> &dyn ApiClientExt { client },
-        );
-
-        LightSetDeviceInfoParams::new(client)
-            .brightness(brightness)
-            .send()
-            .await
-    }
 }
 
 #[async_trait]
@@ -95,3 +72,15 @@ impl HandlerExt for LightHandler {
 }
 
 impl DeviceManagementExt for LightHandler {}
+
+impl DeviceInfoExt for LightHandler {}
+
+impl DeviceTimeExt for LightHandler {}
+
+impl FirmwareExt for LightHandler {}
+
+impl ComponentListExt for LightHandler {}
+
+impl OnOffExt for LightHandler {}
+
+impl BrightnessExt for LightHandler {}
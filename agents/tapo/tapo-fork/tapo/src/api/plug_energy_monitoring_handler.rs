@@ -4,14 +4,19 @@ use async_trait::async_trait;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::{EnergyDataInterval, GenericSetDeviceInfoParams, PowerDataInterval};
+use crate::requests::{
+    AntitheftRuleParams, BatchReadParams, DefaultPowerState, GenericSetDeviceInfoParams,
+};
 use crate::responses::{
-    CountdownRulesResult, CurrentPowerResult, DeviceInfoPlugEnergyMonitoringResult,
-    DeviceUsageEnergyMonitoringResult, EnergyDataResult, EnergyUsageResult,
-    PowerDataResult, ScheduleRulesResult,
+    AntitheftRulesResult, BatchReadResult, DeviceInfoPlugEnergyMonitoringResult,
+    DeviceUsageEnergyMonitoringResult, MaxPowerResult, PowerProtectionResult,
 };
 
-use super::{ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt};
+use super::{
+    ApiClient, ApiClientExt, AutoOffExt, ComponentListExt, CountdownExt, DeviceInfoExt,
+    DeviceManagementExt, DeviceTimeExt, EnergyMonitoringExt, FirmwareExt, HandlerExt, LedExt,
+    OnOffExt, ScheduleExt,
+};
 
 /// Handler for the [P110](https://www.tapo.com/en/search/?q=P110),
 /// [P110M](https://www.tapo.com/en/search/?q=P110M) and
@@ -32,15 +37,13 @@ impl PlugEnergyMonitoringHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
-        self.client.read().await.set_device_info(json).await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
+    /// Sets the state the device is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - one of [`DefaultPowerState`]
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::plug_default_state(state)?)?;
         self.client.read().await.set_device_info(json).await
     }
 
@@ -57,9 +60,35 @@ impl PlugEnergyMonitoringHandler {
         self.client.read().await.get_device_info().await
     }
 
-    /// Returns *current power* as [`CurrentPowerResult`].
-    pub async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
-        self.client.read().await.get_current_power().await
+    /// Returns the *power protection* settings as [`PowerProtectionResult`].
+    /// When enabled, the device turns itself *off* if the power drawn exceeds the threshold
+    /// and reports it in the `power_protection_status` of [`DeviceInfoPlugEnergyMonitoringResult`].
+    pub async fn get_power_protection(&self) -> Result<PowerProtectionResult, Error> {
+        self.client.read().await.get_power_protection().await
+    }
+
+    /// Enables or disables *power protection* and sets its threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - whether the device turns itself *off* when the threshold is exceeded.
+    /// * `protection_power` - the threshold in Watts (W). It must be greater than 0 when `enabled` is true,
+    ///   and it cannot exceed the rated power returned by [`PlugEnergyMonitoringHandler::get_max_power`].
+    pub async fn set_power_protection(
+        &self,
+        enabled: bool,
+        protection_power: u16,
+    ) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .set_power_protection(enabled, protection_power)
+            .await
+    }
+
+    /// Returns the *maximum power* the device is rated for as [`MaxPowerResult`].
+    pub async fn get_max_power(&self) -> Result<MaxPowerResult, Error> {
+        self.client.read().await.get_max_power().await
     }
 
     /// Returns *device usage* as [`DeviceUsageEnergyMonitoringResult`].
@@ -67,44 +96,66 @@ impl PlugEnergyMonitoringHandler {
         self.client.read().await.get_device_usage().await
     }
 
-    /// Returns *energy usage* as [`EnergyUsageResult`].
-    pub async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
-        self.client.read().await.get_energy_usage().await
+    /// Returns *anti-theft rules* as [`AntitheftRulesResult`].
+    pub async fn get_antitheft_rules(&self) -> Result<AntitheftRulesResult, Error> {
+        self.client.read().await.get_antitheft_rules().await
     }
 
-    /// Returns *energy data* as [`EnergyDataResult`].
-    pub async fn get_energy_data(
-        &self,
-        interval: EnergyDataInterval,
-    ) -> Result<EnergyDataResult, Error> {
-        self.client.read().await.get_energy_data(interval).await
+    /// Adds an anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - the rule, see [`AntitheftRuleParams`].
+    pub async fn add_antitheft_rule(&self, params: AntitheftRuleParams) -> Result<(), Error> {
+        self.client.read().await.add_antitheft_rule(params).await
     }
 
-    /// Returns *power data* as [`PowerDataResult`].
-    pub async fn get_power_data(
+    /// Replaces an existing anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the rule, as returned by `get_antitheft_rules`.
+    /// * `params` - the new rule, see [`AntitheftRuleParams`].
+    pub async fn edit_antitheft_rule(
         &self,
-        interval: PowerDataInterval,
-    ) -> Result<PowerDataResult, Error> {
-        self.client.read().await.get_power_data(interval).await
+        id: impl Into<String>,
+        params: AntitheftRuleParams,
+    ) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .edit_antitheft_rule(id.into(), params)
+            .await
     }
 
-    /// Returns *countdown rules* as [`CountdownRulesResult`].
-    pub async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
-        self.client.read().await.get_countdown_rules().await
-    }
-
-    /// Returns *schedule rules* as [`ScheduleRulesResult`].
-    pub async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
-        self.client.read().await.get_schedule_rules().await
+    /// Removes an anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the rule, as returned by `get_antitheft_rules`.
+    pub async fn remove_antitheft_rule(&self, id: impl Into<String>) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .remove_antitheft_rule(id.into())
+            .await
     }
 
-    /// Sets a countdown rule.
-    /// 
+    /// Returns the properties selected in [`BatchReadParams`] as [`BatchReadResult`], using a single request when possible.
+    /// Each property is returned as its own result, so a failure to read one of them does not affect the others.
+    /// Devices that do not support batching are queried one property at a time.
+    ///
     /// # Arguments
-    /// * `delay` - Seconds until action
-    /// * `turn_on` - true to turn on, false to turn off when countdown completes
-    pub async fn set_countdown(&self, delay: u64, turn_on: bool) -> Result<(), Error> {
-        self.client.read().await.add_countdown_rule(delay, turn_on).await
+    ///
+    /// * `params` - the properties to read.
+    pub async fn get_batch(
+        &self,
+        params: BatchReadParams,
+    ) -> Result<
+        BatchReadResult<DeviceInfoPlugEnergyMonitoringResult, DeviceUsageEnergyMonitoringResult>,
+        Error,
+    > {
+        self.client.read().await.get_batch(params).await
     }
 }
 
@@ -119,3 +170,23 @@ impl HandlerExt for PlugEnergyMonitoringHandler {
 }
 
 impl DeviceManagementExt for PlugEnergyMonitoringHandler {}
+
+impl DeviceInfoExt for PlugEnergyMonitoringHandler {}
+
+impl DeviceTimeExt for PlugEnergyMonitoringHandler {}
+
+impl FirmwareExt for PlugEnergyMonitoringHandler {}
+
+impl ComponentListExt for PlugEnergyMonitoringHandler {}
+
+impl AutoOffExt for PlugEnergyMonitoringHandler {}
+
+impl LedExt for PlugEnergyMonitoringHandler {}
+
+impl OnOffExt for PlugEnergyMonitoringHandler {}
+
+impl EnergyMonitoringExt for PlugEnergyMonitoringHandler {}
+
+impl CountdownExt for PlugEnergyMonitoringHandler {}
+
+impl ScheduleExt for PlugEnergyMonitoringHandler {}
@@ -4,12 +4,17 @@ use async_trait::async_trait;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::GenericSetDeviceInfoParams;
+use crate::requests::{
+    AntitheftRuleParams, BatchReadParams, DefaultPowerState, GenericSetDeviceInfoParams,
+};
 use crate::responses::{
-    CountdownRulesResult, DeviceInfoPlugResult, DeviceUsageResult, ScheduleRulesResult,
+    AntitheftRulesResult, BatchReadResult, DeviceInfoPlugResult, DeviceUsageResult,
 };
 
-use super::{ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt};
+use super::{
+    ApiClient, ApiClientExt, AutoOffExt, ComponentListExt, CountdownExt, DeviceInfoExt,
+    DeviceManagementExt, DeviceTimeExt, FirmwareExt, HandlerExt, LedExt, OnOffExt, ScheduleExt,
+};
 
 /// Handler for the [P100](https://www.tapo.com/en/search/?q=P100) and
 /// [P105](https://www.tapo.com/en/search/?q=P105) devices.
@@ -29,15 +34,13 @@ impl PlugHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(true)?)?;
-        self.client.read().await.set_device_info(json).await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        let json = serde_json::to_value(GenericSetDeviceInfoParams::device_on(false)?)?;
+    /// Sets the state the device is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - one of [`DefaultPowerState`]
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::plug_default_state(state)?)?;
         self.client.read().await.set_device_info(json).await
     }
 
@@ -59,23 +62,63 @@ impl PlugHandler {
         self.client.read().await.get_device_usage().await
     }
 
-    /// Returns *countdown rules* as [`CountdownRulesResult`].
-    pub async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
-        self.client.read().await.get_countdown_rules().await
+    /// Returns *anti-theft rules* as [`AntitheftRulesResult`].
+    pub async fn get_antitheft_rules(&self) -> Result<AntitheftRulesResult, Error> {
+        self.client.read().await.get_antitheft_rules().await
+    }
+
+    /// Adds an anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - the rule, see [`AntitheftRuleParams`].
+    pub async fn add_antitheft_rule(&self, params: AntitheftRuleParams) -> Result<(), Error> {
+        self.client.read().await.add_antitheft_rule(params).await
+    }
+
+    /// Replaces an existing anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the rule, as returned by `get_antitheft_rules`.
+    /// * `params` - the new rule, see [`AntitheftRuleParams`].
+    pub async fn edit_antitheft_rule(
+        &self,
+        id: impl Into<String>,
+        params: AntitheftRuleParams,
+    ) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .edit_antitheft_rule(id.into(), params)
+            .await
     }
 
-    /// Returns *schedule rules* as [`ScheduleRulesResult`].
-    pub async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
-        self.client.read().await.get_schedule_rules().await
+    /// Removes an anti-theft (away mode) rule.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - the ID of the rule, as returned by `get_antitheft_rules`.
+    pub async fn remove_antitheft_rule(&self, id: impl Into<String>) -> Result<(), Error> {
+        self.client
+            .read()
+            .await
+            .remove_antitheft_rule(id.into())
+            .await
     }
 
-    /// Sets a countdown rule.
-    /// 
+    /// Returns the properties selected in [`BatchReadParams`] as [`BatchReadResult`], using a single request when possible.
+    /// Each property is returned as its own result, so a failure to read one of them does not affect the others.
+    /// Devices that do not support batching are queried one property at a time.
+    ///
     /// # Arguments
-    /// * `delay` - Seconds until action
-    /// * `turn_on` - true to turn on, false to turn off when countdown completes
-    pub async fn set_countdown(&self, delay: u64, turn_on: bool) -> Result<(), Error> {
-        self.client.read().await.add_countdown_rule(delay, turn_on).await
+    ///
+    /// * `params` - the properties to read.
+    pub async fn get_batch(
+        &self,
+        params: BatchReadParams,
+    ) -> Result<BatchReadResult<DeviceInfoPlugResult, DeviceUsageResult>, Error> {
+        self.client.read().await.get_batch(params).await
     }
 }
 
@@ -90,3 +133,21 @@ impl HandlerExt for PlugHandler {
 }
 
 impl DeviceManagementExt for PlugHandler {}
+
+impl DeviceInfoExt for PlugHandler {}
+
+impl DeviceTimeExt for PlugHandler {}
+
+impl FirmwareExt for PlugHandler {}
+
+impl ComponentListExt for PlugHandler {}
+
+impl AutoOffExt for PlugHandler {}
+
+impl LedExt for PlugHandler {}
+
+impl OnOffExt for PlugHandler {}
+
+impl CountdownExt for PlugHandler {}
+
+impl ScheduleExt for PlugHandler {}
@@ -10,8 +10,8 @@ use crate::responses::{
 };
 
 use super::{
-    ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt, Plug,
-    PowerStripPlugEnergyMonitoringHandler,
+    ApiClient, ApiClientExt, ChildDevicesExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, LedExt, Plug, PowerStripPlugEnergyMonitoringHandler,
 };
 
 /// Handler for the [P304M](https://www.tp-link.com/uk/search/?q=P304M) and
@@ -60,22 +60,6 @@ impl PowerStripEnergyMonitoringHandler {
             .await
             .map(|r| r.plugs)
     }
-
-    /// Returns *child device list* as [`serde_json::Value`].
-    /// It contains all the properties returned from the Tapo API.
-    pub async fn get_child_device_list_json(&self) -> Result<serde_json::Value, Error> {
-        self.client.read().await.get_child_device_list(0).await
-    }
-
-    /// Returns *child device component list* as [`serde_json::Value`].
-    /// This information is useful in debugging or when investigating new functionality to add.
-    pub async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
-        self.client
-            .read()
-            .await
-            .get_child_device_component_list()
-            .await
-    }
 }
 
 /// Child device handler builders.
@@ -149,3 +133,15 @@ impl HandlerExt for PowerStripEnergyMonitoringHandler {
 }
 
 impl DeviceManagementExt for PowerStripEnergyMonitoringHandler {}
+
+impl DeviceInfoExt for PowerStripEnergyMonitoringHandler {}
+
+impl DeviceTimeExt for PowerStripEnergyMonitoringHandler {}
+
+impl FirmwareExt for PowerStripEnergyMonitoringHandler {}
+
+impl ComponentListExt for PowerStripEnergyMonitoringHandler {}
+
+impl LedExt for PowerStripEnergyMonitoringHandler {}
+
+impl ChildDevicesExt for PowerStripEnergyMonitoringHandler {}
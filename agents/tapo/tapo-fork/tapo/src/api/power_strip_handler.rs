@@ -9,7 +9,8 @@ use crate::responses::{
 };
 
 use super::{
-    ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt, Plug, PowerStripPlugHandler,
+    ApiClient, ApiClientExt, ChildDevicesExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, LedExt, Plug, PowerStripPlugHandler,
 };
 
 /// Handler for the [P300](https://www.tp-link.com/en/search/?q=P300) and
@@ -56,22 +57,6 @@ impl PowerStripHandler {
             .await
             .map(|r| r.plugs)
     }
-
-    /// Returns *child device list* as [`serde_json::Value`].
-    /// It contains all the properties returned from the Tapo API.
-    pub async fn get_child_device_list_json(&self) -> Result<serde_json::Value, Error> {
-        self.client.read().await.get_child_device_list(0).await
-    }
-
-    /// Returns *child device component list* as [`serde_json::Value`].
-    /// This information is useful in debugging or when investigating new functionality to add.
-    pub async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
-        self.client
-            .read()
-            .await
-            .get_child_device_component_list()
-            .await
-    }
 }
 
 /// Child device handler builders.
@@ -139,3 +124,15 @@ impl HandlerExt for PowerStripHandler {
 }
 
 impl DeviceManagementExt for PowerStripHandler {}
+
+impl DeviceInfoExt for PowerStripHandler {}
+
+impl DeviceTimeExt for PowerStripHandler {}
+
+impl FirmwareExt for PowerStripHandler {}
+
+impl ComponentListExt for PowerStripHandler {}
+
+impl LedExt for PowerStripHandler {}
+
+impl ChildDevicesExt for PowerStripHandler {}
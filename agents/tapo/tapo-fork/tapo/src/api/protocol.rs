@@ -3,6 +3,10 @@ mod klap_cipher;
 mod klap_protocol;
 mod passthrough_cipher;
 mod passthrough_protocol;
+#[cfg(test)]
+mod simulated_device;
 mod tapo_protocol;
 
+#[cfg(test)]
+pub(crate) use simulated_device::*;
 pub(crate) use tapo_protocol::*;
@@ -1,21 +1,22 @@
+use std::sync::Arc;
+
 use log::debug;
-use reqwest::Client;
 
 use crate::api::protocol::klap_protocol::KlapProtocol;
 use crate::requests::{EmptyParams, TapoParams, TapoRequest};
 use crate::responses::{TapoResponse, validate_response};
-use crate::{Error, TapoResponseError};
+use crate::{Error, TapoResponseError, Transport, TransportRequest};
 
 use super::{TapoProtocolType, passthrough_protocol::PassthroughProtocol};
 
 #[derive(Debug, Clone)]
 pub(crate) struct DiscoveryProtocol {
-    client: Client,
+    transport: Arc<dyn Transport>,
 }
 
 impl DiscoveryProtocol {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
+        Self { transport }
     }
 
     pub async fn discover(&mut self, url: &str) -> Result<TapoProtocolType, Error> {
@@ -23,12 +24,12 @@ impl DiscoveryProtocol {
         if self.is_passthrough_supported(url).await? {
             debug!("Supported. Setting up the Passthrough protocol...");
             Ok(TapoProtocolType::Passthrough(PassthroughProtocol::new(
-                self.client.clone(),
+                self.transport.clone(),
             )?))
         } else {
             debug!("Not supported. Setting up the Klap protocol...");
             Ok(TapoProtocolType::Klap(KlapProtocol::new(
-                self.client.clone(),
+                self.transport.clone(),
             )))
         }
     }
@@ -47,14 +48,20 @@ impl DiscoveryProtocol {
         debug!("Component negotiation request: {request_string}");
 
         let response = self
-            .client
-            .post(url)
-            .body(request_string)
-            .send()
-            .await?
-            .json::<TapoResponse<serde_json::Value>>()
+            .transport
+            .send(TransportRequest {
+                url: url.to_string(),
+                cookie: None,
+                body: request_string.clone().into_bytes(),
+            })
             .await?;
 
+        let response_string = String::from_utf8_lossy(&response.body);
+        self.transport
+            .on_exchange(&request_string, &response_string);
+
+        let response: TapoResponse<serde_json::Value> = serde_json::from_str(&response_string)?;
+
         debug!("Device responded with: {response:?}");
 
         validate_response(&response)?;
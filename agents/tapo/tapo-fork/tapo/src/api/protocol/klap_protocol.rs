@@ -1,16 +1,17 @@
 use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use log::{debug, error, trace};
-use reqwest::header::COOKIE;
-use reqwest::{Client, StatusCode};
+use reqwest::StatusCode;
 use rsa::rand_core::{OsRng, RngCore as _};
 use serde::de::DeserializeOwned;
 
 use crate::api::protocol::TapoProtocol;
 use crate::requests::TapoRequest;
 use crate::responses::{TapoResponse, TapoResponseExt, validate_response};
-use crate::{Error, TapoResponseError};
+use crate::{Error, TapoResponseError, Transport, TransportRequest};
 
 use super::TapoProtocolExt;
 use super::discovery_protocol::DiscoveryProtocol;
@@ -18,11 +19,16 @@ use super::klap_cipher::KlapCipher;
 
 #[derive(Debug)]
 pub(crate) struct KlapProtocol {
-    client: Client,
+    transport: Arc<dyn Transport>,
+    session: RwLock<Option<Arc<KlapSession>>>,
+}
+
+#[derive(Debug)]
+struct KlapSession {
+    url: String,
     cookie: String,
-    rng: OsRng,
-    url: Option<String>,
-    cipher: Option<KlapCipher>,
+    cipher: KlapCipher,
+    expires_at: Instant,
 }
 
 #[async_trait]
@@ -37,12 +43,20 @@ impl TapoProtocolExt for KlapProtocol {
         Ok(())
     }
 
-    async fn refresh_session(&mut self, username: String, password: String) -> Result<(), Error> {
-        let url = self.url.as_ref().expect("This should never happen").clone();
+    async fn refresh_session(&self, username: String, password: String) -> Result<(), Error> {
+        let url = self.get_session().url.clone();
         self.handshake(url, username, password).await?;
         Ok(())
     }
 
+    fn session_expires_at(&self) -> Option<Instant> {
+        self.session
+            .read()
+            .expect("This should never happen")
+            .as_ref()
+            .map(|session| session.expires_at)
+    }
+
     async fn execute_request<R>(
         &self,
         request: TapoRequest,
@@ -51,26 +65,28 @@ impl TapoProtocolExt for KlapProtocol {
     where
         R: fmt::Debug + DeserializeOwned + TapoResponseExt,
     {
-        let url = self.url.as_ref().expect("This should never happen");
-        let cipher = self.get_cipher_ref();
+        let session = self.get_session();
+        let url = &session.url;
+        let cipher = &session.cipher;
 
         let request_string = serde_json::to_string(&request)?;
-        debug!("Request: {request_string}");
+        debug!("Request: {request:?}");
 
-        let (payload, seq) = cipher.encrypt(request_string)?;
+        let (payload, seq) = cipher.encrypt(request_string.clone())?;
 
         let response = self
-            .client
-            .post(format!("{url}/request?seq={seq}"))
-            .header(COOKIE, self.cookie.clone())
-            .body(payload)
-            .send()
+            .transport
+            .send(TransportRequest {
+                url: format!("{url}/request?seq={seq}"),
+                cookie: Some(session.cookie.clone()),
+                body: payload,
+            })
             .await?;
 
-        if !response.status().is_success() {
-            error!("Response error: {}", response.status());
+        if !response.status.is_success() {
+            error!("Response error: {}", response.status);
 
-            let error = match response.status() {
+            let error = match response.status {
                 StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
                     TapoResponseError::SessionTimeout
                 }
@@ -80,11 +96,14 @@ impl TapoProtocolExt for KlapProtocol {
             return Err(Error::Tapo(error));
         }
 
-        let response_body = response.bytes().await.map_err(anyhow::Error::from)?;
-
-        let response_decrypted = cipher.decrypt(seq, response_body.to_vec())?;
+        let response_decrypted = cipher.decrypt(seq, response.body)?;
         trace!("Device responded with (raw): {response_decrypted}");
 
+        if !request.is_sensitive() {
+            self.transport
+                .on_exchange(&request_string, &response_decrypted);
+        }
+
         let response: TapoResponse<R> = serde_json::from_str(&response_decrypted)?;
         debug!("Device responded with: {response:?}");
 
@@ -95,23 +114,20 @@ impl TapoProtocolExt for KlapProtocol {
     }
 
     fn clone_as_discovery(&self) -> DiscoveryProtocol {
-        DiscoveryProtocol::new(self.client.clone())
+        DiscoveryProtocol::new(self.transport.clone())
     }
 }
 
 impl KlapProtocol {
-    pub fn new(client: Client) -> Self {
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
         Self {
-            client,
-            cookie: String::new(),
-            rng: OsRng,
-            url: None,
-            cipher: None,
+            transport,
+            session: RwLock::new(None),
         }
     }
 
     async fn handshake(
-        &mut self,
+        &self,
         url: String,
         username: String,
         password: String,
@@ -125,40 +141,49 @@ impl KlapProtocol {
         )
         .to_vec();
 
-        let local_seed = self.get_local_seed().to_vec();
-        let remote_seed = self.handshake1(&url, &local_seed, &auth_hash).await?;
+        let local_seed = Self::get_local_seed().to_vec();
+        let (remote_seed, cookie, timeout) = self.handshake1(&url, &local_seed, &auth_hash).await?;
 
-        self.handshake2(&url, &local_seed, &remote_seed, &auth_hash)
+        self.handshake2(&url, &cookie, &local_seed, &remote_seed, &auth_hash)
             .await?;
 
         let cipher = KlapCipher::new(local_seed, remote_seed, auth_hash)?;
 
-        self.url.replace(url);
-        self.cipher.replace(cipher);
+        self.session
+            .write()
+            .expect("This should never happen")
+            .replace(Arc::new(KlapSession {
+                url,
+                cookie,
+                cipher,
+                expires_at: Instant::now() + timeout,
+            }));
 
         Ok(())
     }
 
     async fn handshake1(
-        &mut self,
+        &self,
         url: &str,
         local_seed: &[u8],
         auth_hash: &[u8],
-    ) -> Result<Vec<u8>, Error> {
+    ) -> Result<(Vec<u8>, String, Duration), Error> {
         debug!("Performing handshake1...");
         let url = format!("{url}/handshake1");
 
         let response = self
-            .client
-            .post(&url)
-            .body(local_seed.to_vec())
-            .send()
+            .transport
+            .send(TransportRequest {
+                url,
+                cookie: None,
+                body: local_seed.to_vec(),
+            })
             .await?;
 
-        if !response.status().is_success() {
-            error!("Handshake1 error: {}", response.status());
+        if !response.status.is_success() {
+            error!("Handshake1 error: {}", response.status);
 
-            if response.status() == StatusCode::FORBIDDEN {
+            if response.status == StatusCode::FORBIDDEN {
                 return Err(Error::Tapo(TapoResponseError::Forbidden {
                     code: "FORBIDDEN".to_string(),
                     description: r"Make sure Third-Party Compatibility is turned on in the Tapo app. If it's already enabled, try switching it off and then back on again. You can find this option by navigating to Me > Third-Party Services in the app."
@@ -168,11 +193,18 @@ impl KlapProtocol {
             return Err(Error::Tapo(TapoResponseError::InvalidResponse));
         }
 
-        self.cookie = TapoProtocol::get_cookie(response.cookies())?;
+        let cookie = TapoProtocol::get_cookie(&response.headers)?;
+        let timeout = TapoProtocol::get_session_timeout(&response.headers);
 
-        let response_body = response.bytes().await.map_err(anyhow::Error::from)?;
+        if response.body.len() != 48 {
+            error!(
+                "Handshake1 response has an invalid length: {}",
+                response.body.len()
+            );
+            return Err(Error::Tapo(TapoResponseError::InvalidResponse));
+        }
 
-        let (remote_seed, server_hash) = response_body.split_at(16);
+        let (remote_seed, server_hash) = response.body.split_at(16);
         let local_hash = KlapCipher::sha256(&[local_seed, remote_seed, auth_hash].concat());
 
         if local_hash != server_hash {
@@ -183,14 +215,15 @@ impl KlapProtocol {
              }));
         }
 
-        debug!("Handshake1 OK");
+        debug!("Handshake1 OK, session timeout: {timeout:?}");
 
-        Ok(remote_seed.to_vec())
+        Ok((remote_seed.to_vec(), cookie, timeout))
     }
 
     async fn handshake2(
         &self,
         url: &str,
+        cookie: &str,
         local_seed: &[u8],
         remote_seed: &[u8],
         auth_hash: &[u8],
@@ -201,15 +234,16 @@ impl KlapProtocol {
         let payload = KlapCipher::sha256(&[remote_seed, local_seed, auth_hash].concat());
 
         let response = self
-            .client
-            .post(&url)
-            .header(COOKIE, self.cookie.clone())
-            .body(payload.to_vec())
-            .send()
+            .transport
+            .send(TransportRequest {
+                url,
+                cookie: Some(cookie.to_string()),
+                body: payload.to_vec(),
+            })
             .await?;
 
-        if !response.status().is_success() {
-            error!("Handshake2 error: {}", response.status());
+        if !response.status.is_success() {
+            error!("Handshake2 error: {}", response.status);
             return Err(Error::Tapo(TapoResponseError::InvalidResponse));
         }
 
@@ -218,13 +252,78 @@ impl KlapProtocol {
         Ok(())
     }
 
-    fn get_local_seed(&mut self) -> [u8; 16] {
+    fn get_local_seed() -> [u8; 16] {
         let mut buffer = [0u8; 16];
-        self.rng.fill_bytes(&mut buffer);
+        OsRng.fill_bytes(&mut buffer);
         buffer
     }
 
-    fn get_cipher_ref(&self) -> &KlapCipher {
-        self.cipher.as_ref().expect("This should never happen")
+    fn get_session(&self) -> Arc<KlapSession> {
+        self.session
+            .read()
+            .expect("This should never happen")
+            .clone()
+            .expect("This should never happen")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use crate::api::protocol::SimulatedDevice;
+    use crate::{ApiClient, HookTransport};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_execute_request_with_in_memory_transport() {
+        let device = Arc::new(SimulatedDevice::new("username", "password"));
+        let exchanges = Arc::new(Mutex::new(Vec::new()));
+
+        let transport = {
+            let exchanges = exchanges.clone();
+            HookTransport::new(device.clone()).with_exchange_hook(move |request, response| {
+                exchanges
+                    .lock()
+                    .unwrap()
+                    .push((request.to_string(), response.to_string()));
+            })
+        };
+
+        let handler = ApiClient::new("username", "password")
+            .with_transport(transport)
+            .with_base_url("http://localhost:8080/app/")
+            .generic_device("192.168.1.100")
+            .await
+            .unwrap();
+
+        let info = handler.get_device_info_json().await.unwrap();
+        assert_eq!(info["device_on"], true);
+
+        let urls = device.urls.lock().unwrap().clone();
+        assert!(
+            urls.iter()
+                .all(|url| url.starts_with("http://localhost:8080/app"))
+        );
+
+        let exchanges = exchanges.lock().unwrap();
+        let (request, response) = exchanges.last().unwrap();
+        assert!(request.contains("get_device_info"));
+        assert!(response.contains("device_on"));
+    }
+
+    #[tokio::test]
+    async fn test_login_with_invalid_credentials() {
+        let device = Arc::new(SimulatedDevice::new("username", "password"));
+
+        let error = ApiClient::new("username", "wrong-password")
+            .with_transport(device)
+            .with_port(8080)
+            .generic_device("192.168.1.100")
+            .await
+            .unwrap_err();
+
+        assert!(error.is_auth());
     }
 }
@@ -1,10 +1,11 @@
 use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use async_trait::async_trait;
 use base64::{Engine as _, engine::general_purpose};
-use log::{debug, trace};
-use reqwest::Client;
-use reqwest::header::COOKIE;
+use log::{debug, error, trace};
+use reqwest::StatusCode;
 use rsa::rand_core::OsRng;
 use serde::de::DeserializeOwned;
 
@@ -16,7 +17,7 @@ use crate::responses::{
     HandshakeResult, TapoResponse, TapoResponseExt, TapoResult, TokenResult, validate_response,
 };
 
-use crate::{Error, TapoResponseError};
+use crate::{Error, TapoResponseError, Transport, TransportRequest};
 
 use super::discovery_protocol::DiscoveryProtocol;
 use super::passthrough_cipher::{PassthroughCipher, PassthroughKeyPair};
@@ -24,9 +25,9 @@ use super::tapo_protocol::TapoProtocolExt;
 
 #[derive(Debug)]
 pub(crate) struct PassthroughProtocol {
-    client: Client,
+    transport: Arc<dyn Transport>,
     key_pair: PassthroughKeyPair,
-    session: Option<Session>,
+    session: RwLock<Option<Arc<Session>>>,
 }
 
 #[derive(Debug)]
@@ -35,6 +36,7 @@ struct Session {
     pub cookie: String,
     pub cipher: PassthroughCipher,
     pub token: Option<String>,
+    pub expires_at: Instant,
 }
 
 #[async_trait]
@@ -45,15 +47,20 @@ impl TapoProtocolExt for PassthroughProtocol {
         username: String,
         password: String,
     ) -> Result<(), Error> {
-        self.handshake(url).await?;
-        self.login_request(username, password).await?;
+        self.new_session(url, username, password).await
+    }
 
-        Ok(())
+    async fn refresh_session(&self, username: String, password: String) -> Result<(), Error> {
+        let url = self.get_session().url.clone();
+        self.new_session(url, username, password).await
     }
 
-    async fn refresh_session(&mut self, username: String, password: String) -> Result<(), Error> {
-        let url = self.get_session_ref().url.clone();
-        self.login(url, username, password).await
+    fn session_expires_at(&self) -> Option<Instant> {
+        self.session
+            .read()
+            .expect("This should never happen")
+            .as_ref()
+            .map(|session| session.expires_at)
     }
 
     async fn execute_request<R>(
@@ -64,7 +71,52 @@ impl TapoProtocolExt for PassthroughProtocol {
     where
         R: fmt::Debug + DeserializeOwned + TapoResponseExt,
     {
-        let session = self.get_session_ref();
+        let session = self.get_session();
+        self.execute_session_request(&session, request, with_token)
+            .await
+    }
+
+    fn clone_as_discovery(&self) -> DiscoveryProtocol {
+        DiscoveryProtocol::new(self.transport.clone())
+    }
+}
+
+impl PassthroughProtocol {
+    pub fn new(transport: Arc<dyn Transport>) -> Result<Self, Error> {
+        Ok(Self {
+            transport,
+            key_pair: PassthroughKeyPair::new(OsRng)?,
+            session: RwLock::new(None),
+        })
+    }
+
+    async fn new_session(
+        &self,
+        url: String,
+        username: String,
+        password: String,
+    ) -> Result<(), Error> {
+        let mut session = self.handshake(url).await?;
+        let token = self.login_request(&session, username, password).await?;
+        session.token.replace(token);
+
+        self.session
+            .write()
+            .expect("This should never happen")
+            .replace(Arc::new(session));
+
+        Ok(())
+    }
+
+    async fn execute_session_request<R>(
+        &self,
+        session: &Session,
+        request: TapoRequest,
+        with_token: bool,
+    ) -> Result<Option<R>, Error>
+    where
+        R: fmt::Debug + DeserializeOwned + TapoResponseExt,
+    {
         let url = if with_token {
             format!(
                 "{}?token={}",
@@ -79,7 +131,7 @@ impl TapoProtocolExt for PassthroughProtocol {
         };
 
         let request_string = serde_json::to_string(&request)?;
-        debug!("Request to passthrough: {request_string}");
+        debug!("Request to passthrough: {request:?}");
 
         let request_encrypted = session.cipher.encrypt(&request_string)?;
 
@@ -88,18 +140,32 @@ impl TapoProtocolExt for PassthroughProtocol {
             TapoRequest::SecurePassthrough(TapoParams::new(secure_passthrough_params));
         let secure_passthrough_request_string = serde_json::to_string(&secure_passthrough_request)?;
 
-        let request = self
-            .client
-            .post(url)
-            .header(COOKIE, session.cookie.clone())
-            .body(secure_passthrough_request_string);
+        let is_sensitive = request.is_sensitive();
 
-        let response = request
-            .send()
-            .await?
-            .json::<TapoResponse<TapoResult>>()
+        let response = self
+            .transport
+            .send(TransportRequest {
+                url,
+                cookie: Some(session.cookie.clone()),
+                body: secure_passthrough_request_string.into_bytes(),
+            })
             .await?;
 
+        if !response.status.is_success() {
+            error!("Response error: {}", response.status);
+
+            let error = match response.status {
+                StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                    TapoResponseError::SessionTimeout
+                }
+                _ => TapoResponseError::InvalidResponse,
+            };
+
+            return Err(Error::Tapo(error));
+        }
+
+        let response: TapoResponse<TapoResult> = serde_json::from_slice(&response.body)?;
+
         debug!("Device responded with: {response:?}");
 
         validate_response(&response)?;
@@ -113,6 +179,11 @@ impl TapoProtocolExt for PassthroughProtocol {
 
         trace!("Device inner response (raw): {inner_response_decrypted}");
 
+        if !is_sensitive {
+            self.transport
+                .on_exchange(&request_string, &inner_response_decrypted);
+        }
+
         let inner_response: TapoResponse<R> = serde_json::from_str(&inner_response_decrypted)?;
 
         debug!("Device inner response: {inner_response:?}");
@@ -124,30 +195,24 @@ impl TapoProtocolExt for PassthroughProtocol {
         Ok(result)
     }
 
-    fn clone_as_discovery(&self) -> DiscoveryProtocol {
-        DiscoveryProtocol::new(self.client.clone())
-    }
-}
-
-impl PassthroughProtocol {
-    pub fn new(client: Client) -> Result<Self, Error> {
-        Ok(Self {
-            client,
-            key_pair: PassthroughKeyPair::new(OsRng)?,
-            session: None,
-        })
-    }
-
-    async fn handshake(&mut self, url: String) -> Result<(), Error> {
+    async fn handshake(&self, url: String) -> Result<Session, Error> {
         debug!("Performing handshake...");
 
         let params = HandshakeParams::new(self.key_pair.get_public_key()?);
         let request = TapoRequest::Handshake(TapoParams::new(params));
         let request_string = serde_json::to_string(&request)?;
 
-        let response = self.client.post(&url).body(request_string).send().await?;
-        let cookie = TapoProtocol::get_cookie(response.cookies())?;
-        let response_json = response.json::<TapoResponse<HandshakeResult>>().await?;
+        let response = self
+            .transport
+            .send(TransportRequest {
+                url: url.clone(),
+                cookie: None,
+                body: request_string.into_bytes(),
+            })
+            .await?;
+        let cookie = TapoProtocol::get_cookie(&response.headers)?;
+        let timeout = TapoProtocol::get_session_timeout(&response.headers);
+        let response_json: TapoResponse<HandshakeResult> = serde_json::from_slice(&response.body)?;
 
         validate_response(&response_json)?;
 
@@ -156,21 +221,25 @@ impl PassthroughProtocol {
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))?
             .key;
 
-        debug!("Handshake OK");
+        debug!("Handshake OK, session timeout: {timeout:?}");
 
         let cipher = PassthroughCipher::new(&handshake_key, &self.key_pair)?;
 
-        self.session.replace(Session {
+        Ok(Session {
             url,
             cookie,
             cipher,
             token: None,
-        });
-
-        Ok(())
+            expires_at: Instant::now() + timeout,
+        })
     }
 
-    async fn login_request(&mut self, username: String, password: String) -> Result<(), Error> {
+    async fn login_request(
+        &self,
+        session: &Session,
+        username: String,
+        password: String,
+    ) -> Result<String, Error> {
         let username_digest = PassthroughCipher::sha1_digest_username(username);
         debug!("Username digest: {username_digest}");
 
@@ -184,21 +253,18 @@ impl PassthroughProtocol {
         let request = TapoRequest::LoginDevice(params);
 
         let result = self
-            .execute_request::<TokenResult>(request, false)
+            .execute_session_request::<TokenResult>(session, request, false)
             .await?
             .ok_or_else(|| Error::Tapo(TapoResponseError::EmptyResult))?;
 
-        let session = self.get_session_mut();
-        session.token.replace(result.token);
-
-        Ok(())
-    }
-
-    fn get_session_ref(&self) -> &Session {
-        self.session.as_ref().expect("This should never happen")
+        Ok(result.token)
     }
 
-    fn get_session_mut(&mut self) -> &mut Session {
-        self.session.as_mut().expect("This should never happen")
+    fn get_session(&self) -> Arc<Session> {
+        self.session
+            .read()
+            .expect("This should never happen")
+            .clone()
+            .expect("This should never happen")
     }
 }
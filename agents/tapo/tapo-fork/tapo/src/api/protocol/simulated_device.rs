@@ -0,0 +1,163 @@
+//! In-memory KLAP device used to test the API against without real hardware.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+use crate::{Error, Transport, TransportRequest, TransportResponse};
+
+use super::klap_cipher::KlapCipher;
+
+const REMOTE_SEED: [u8; 16] = [7; 16];
+
+type Responder = Box<dyn Fn(&serde_json::Value) -> serde_json::Value + Send + Sync>;
+
+/// In-memory KLAP device that accepts the given credentials and answers requests with a responder.
+/// By default, it answers `get_device_info` with `{"device_on": true}`.
+pub(crate) struct SimulatedDevice {
+    auth_hash: Vec<u8>,
+    local_seed: Mutex<Vec<u8>>,
+    cipher: Mutex<Option<KlapCipher>>,
+    responder: Responder,
+    unreachable_method: Option<&'static str>,
+    pub urls: Mutex<Vec<String>>,
+    pub requests: Mutex<Vec<serde_json::Value>>,
+}
+
+impl SimulatedDevice {
+    pub fn new(username: &str, password: &str) -> Self {
+        let auth_hash = KlapCipher::sha256(
+            &[
+                KlapCipher::sha1(username.as_bytes()),
+                KlapCipher::sha1(password.as_bytes()),
+            ]
+            .concat(),
+        )
+        .to_vec();
+
+        Self {
+            auth_hash,
+            local_seed: Mutex::new(Vec::new()),
+            cipher: Mutex::new(None),
+            responder: Box::new(|request| {
+                assert_eq!(request["method"], "get_device_info");
+                serde_json::json!({"error_code": 0, "result": {"device_on": true}})
+            }),
+            unreachable_method: None,
+            urls: Mutex::new(Vec::new()),
+            requests: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Answers every decrypted request with the response returned by `responder`.
+    pub fn with_responder(
+        mut self,
+        responder: impl Fn(&serde_json::Value) -> serde_json::Value + Send + Sync + 'static,
+    ) -> Self {
+        self.responder = Box::new(responder);
+        self
+    }
+
+    /// Fails requests for `method` with a connection error, as if the device could not be reached.
+    pub fn with_unreachable_method(mut self, method: &'static str) -> Self {
+        self.unreachable_method = Some(method);
+        self
+    }
+
+    async fn connect_error() -> Error {
+        // Nothing listens on port 1, so connecting to it fails before the request is sent.
+        let error = reqwest::Client::new()
+            .post("http://127.0.0.1:1")
+            .send()
+            .await
+            .expect_err("port 1 should refuse connections");
+        Error::Http(error)
+    }
+
+    fn respond(status: StatusCode, body: Vec<u8>) -> TransportResponse {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("TP_SESSIONID=SIMULATED;TIMEOUT=86400"),
+        );
+
+        TransportResponse {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+impl fmt::Debug for SimulatedDevice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SimulatedDevice").finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Transport for SimulatedDevice {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        self.urls.lock().unwrap().push(request.url.clone());
+
+        if request.url.ends_with("/handshake1") {
+            let local_seed = request.body;
+            let server_hash =
+                KlapCipher::sha256(&[&local_seed, &REMOTE_SEED[..], &self.auth_hash].concat());
+            *self.local_seed.lock().unwrap() = local_seed;
+
+            let body = [&REMOTE_SEED[..], &server_hash[..]].concat();
+            return Ok(Self::respond(StatusCode::OK, body));
+        }
+
+        if request.url.ends_with("/handshake2") {
+            let local_seed = self.local_seed.lock().unwrap().clone();
+            let expected =
+                KlapCipher::sha256(&[&REMOTE_SEED[..], &local_seed, &self.auth_hash].concat());
+
+            if request.body != expected {
+                return Ok(Self::respond(StatusCode::FORBIDDEN, Vec::new()));
+            }
+
+            let cipher = KlapCipher::new(local_seed, REMOTE_SEED.to_vec(), self.auth_hash.clone())?;
+            self.cipher.lock().unwrap().replace(cipher);
+
+            return Ok(Self::respond(StatusCode::OK, Vec::new()));
+        }
+
+        if let Some((_, seq)) = request.url.split_once("/request?seq=") {
+            let seq = seq.parse().unwrap();
+            let request = {
+                let cipher = self.cipher.lock().unwrap();
+                let cipher = cipher.as_ref().expect("handshake should have happened");
+                cipher.decrypt(seq, request.body)?
+            };
+            let request: serde_json::Value = serde_json::from_str(&request)?;
+
+            if self
+                .unreachable_method
+                .is_some_and(|method| request["method"] == method)
+            {
+                return Err(Self::connect_error().await);
+            }
+
+            let cipher = self.cipher.lock().unwrap();
+            let cipher = cipher.as_ref().expect("handshake should have happened");
+
+            let response = (self.responder)(&request).to_string();
+            self.requests.lock().unwrap().push(request);
+            let (body, _) = cipher.encrypt(response)?;
+
+            return Ok(Self::respond(StatusCode::OK, body));
+        }
+
+        // KLAP devices reject the passthrough protocol negotiation.
+        let body = serde_json::json!({"error_code": 1003})
+            .to_string()
+            .into_bytes();
+        Ok(Self::respond(StatusCode::OK, body))
+    }
+}
@@ -1,12 +1,17 @@
 use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use reqwest::Client;
-use reqwest::cookie::Cookie;
+use log::debug;
+use reqwest::header::{HeaderMap, SET_COOKIE};
 use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
 
 use crate::Error;
 use crate::responses::TapoResponseExt;
+use crate::{RetryPolicy, Transport};
 use crate::{TapoResponseError, requests::TapoRequest};
 
 use super::{
@@ -14,16 +19,43 @@ use super::{
     passthrough_protocol::PassthroughProtocol,
 };
 
-#[derive(Debug, Clone)]
+// Used when the device does not advertise a `TIMEOUT` cookie attribute.
+const DEFAULT_SESSION_TIMEOUT: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Controls how the protocol keeps the authentication session alive.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SessionOptions {
+    /// Whether the session is refreshed automatically.
+    pub auto_refresh: bool,
+    /// How long before the session expiry a refresh is triggered.
+    pub refresh_margin: Duration,
+}
+
+impl Default for SessionOptions {
+    fn default() -> Self {
+        Self {
+            auto_refresh: true,
+            refresh_margin: Duration::from_secs(20 * 60),
+        }
+    }
+}
+
+#[derive(Debug)]
 pub(crate) struct TapoProtocol {
     protocol: TapoProtocolType,
+    options: SessionOptions,
+    retry_policy: Option<RetryPolicy>,
+    credentials: Option<(String, String)>,
+    refresh_lock: Mutex<()>,
+    session_generation: AtomicU64,
 }
 
 #[async_trait]
 pub(crate) trait TapoProtocolExt {
     async fn login(&mut self, url: String, username: String, password: String)
     -> Result<(), Error>;
-    async fn refresh_session(&mut self, username: String, password: String) -> Result<(), Error>;
+    async fn refresh_session(&self, username: String, password: String) -> Result<(), Error>;
+    fn session_expires_at(&self) -> Option<Instant>;
     async fn execute_request<R>(
         &self,
         request: TapoRequest,
@@ -52,6 +84,19 @@ impl Clone for TapoProtocolType {
     }
 }
 
+impl Clone for TapoProtocol {
+    fn clone(&self) -> Self {
+        Self {
+            protocol: self.protocol.clone(),
+            options: self.options,
+            retry_policy: self.retry_policy.clone(),
+            credentials: self.credentials.clone(),
+            refresh_lock: Mutex::new(()),
+            session_generation: AtomicU64::new(0),
+        }
+    }
+}
+
 #[async_trait]
 impl TapoProtocolExt for TapoProtocol {
     async fn login(
@@ -66,20 +111,38 @@ impl TapoProtocolExt for TapoProtocol {
 
         match &mut self.protocol {
             TapoProtocolType::Passthrough(protocol) => {
-                protocol.login(url, username, password).await
+                protocol
+                    .login(url, username.clone(), password.clone())
+                    .await?
+            }
+            TapoProtocolType::Klap(protocol) => {
+                protocol
+                    .login(url, username.clone(), password.clone())
+                    .await?
+            }
+            _ => {
+                return Err(
+                    anyhow::anyhow!("The protocol discovery should have happened already").into(),
+                );
             }
-            TapoProtocolType::Klap(protocol) => protocol.login(url, username, password).await,
-            _ => Err(anyhow::anyhow!("The protocol discovery should have happened already").into()),
         }
+
+        self.credentials.replace((username, password));
+        self.session_generation.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
     }
 
-    async fn refresh_session(&mut self, username: String, password: String) -> Result<(), Error> {
-        match &mut self.protocol {
-            TapoProtocolType::Passthrough(protocol) => {
-                protocol.refresh_session(username, password).await
-            }
-            TapoProtocolType::Klap(protocol) => protocol.refresh_session(username, password).await,
-            _ => Err(anyhow::anyhow!("The protocol discovery should have happened already").into()),
+    async fn refresh_session(&self, username: String, password: String) -> Result<(), Error> {
+        let _guard = self.refresh_lock.lock().await;
+        self.refresh_inner_session(username, password).await
+    }
+
+    fn session_expires_at(&self) -> Option<Instant> {
+        match &self.protocol {
+            TapoProtocolType::Passthrough(protocol) => protocol.session_expires_at(),
+            TapoProtocolType::Klap(protocol) => protocol.session_expires_at(),
+            _ => None,
         }
     }
 
@@ -91,12 +154,31 @@ impl TapoProtocolExt for TapoProtocol {
     where
         R: fmt::Debug + DeserializeOwned + TapoResponseExt,
     {
-        match &self.protocol {
-            TapoProtocolType::Passthrough(protocol) => {
-                protocol.execute_request(request, with_token).await
-            }
-            TapoProtocolType::Klap(protocol) => protocol.execute_request(request, with_token).await,
-            _ => Err(anyhow::anyhow!("The protocol discovery should have happened already").into()),
+        let policy = match &self.retry_policy {
+            Some(policy) if request.is_idempotent() => policy,
+            _ => return self.execute_session_request(request, with_token).await,
+        };
+
+        let mut attempt = 1;
+
+        loop {
+            let delay = match self
+                .execute_session_request(request.clone(), with_token)
+                .await
+            {
+                Err(error) if attempt < policy.max_attempts() && policy.is_retryable(&error) => {
+                    let delay = policy.backoff(attempt);
+                    debug!(
+                        "Request failed with {error:?}, retrying in {delay:?} (attempt {attempt} of {})",
+                        policy.max_attempts()
+                    );
+                    delay
+                }
+                result => return result,
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
         }
     }
 
@@ -110,18 +192,199 @@ impl TapoProtocolExt for TapoProtocol {
 }
 
 impl TapoProtocol {
-    pub fn new(client: Client) -> Self {
+    pub fn new(
+        transport: Arc<dyn Transport>,
+        options: SessionOptions,
+        retry_policy: Option<RetryPolicy>,
+    ) -> Self {
         Self {
-            protocol: TapoProtocolType::Discovery(DiscoveryProtocol::new(client)),
+            protocol: TapoProtocolType::Discovery(DiscoveryProtocol::new(transport)),
+            options,
+            retry_policy,
+            credentials: None,
+            refresh_lock: Mutex::new(()),
+            session_generation: AtomicU64::new(0),
         }
     }
 
-    pub fn get_cookie<'a>(mut cookies: impl Iterator<Item = Cookie<'a>>) -> Result<String, Error> {
-        let cookie = cookies.find(|c| c.name() == "TP_SESSIONID");
+    /// Reads the session cookie from the `Set-Cookie` headers, e.g. `TP_SESSIONID=...;TIMEOUT=86400`.
+    pub fn get_cookie(headers: &HeaderMap) -> Result<String, Error> {
+        headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .map(str::trim)
+            .find(|cookie| cookie.starts_with("TP_SESSIONID="))
+            .map(ToString::to_string)
+            .ok_or(Error::Tapo(TapoResponseError::InvalidResponse))
+    }
+
+    /// Reads the session lifetime from the `TIMEOUT` attribute of the session cookie,
+    /// e.g. `TP_SESSIONID=...;TIMEOUT=86400`.
+    pub fn get_session_timeout(headers: &HeaderMap) -> Duration {
+        headers
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+            .filter_map(|part| part.trim().strip_prefix("TIMEOUT="))
+            .find_map(|value| value.parse::<u64>().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_SESSION_TIMEOUT)
+    }
 
-        match cookie {
-            Some(cookie) => Ok(format!("{}={}", cookie.name(), cookie.value())),
-            None => Err(Error::Tapo(TapoResponseError::InvalidResponse)),
+    fn is_session_expiring(&self) -> bool {
+        self.session_expires_at()
+            .is_some_and(|expires_at| Self::is_expiring(expires_at, self.options.refresh_margin))
+    }
+
+    fn is_expiring(expires_at: Instant, margin: Duration) -> bool {
+        Instant::now() + margin >= expires_at
+    }
+
+    /// Refreshes the session unless another request already did so
+    /// since `generation` was observed.
+    async fn refresh_session_once(&self, generation: u64) -> Result<(), Error> {
+        let _guard = self.refresh_lock.lock().await;
+
+        if self.session_generation.load(Ordering::Acquire) != generation {
+            debug!("Session already refreshed by a concurrent request");
+            return Ok(());
         }
+
+        let (username, password) = self.credentials.clone().ok_or_else(|| {
+            Error::Other(anyhow::anyhow!(
+                "The protocol should have been logged in already"
+            ))
+        })?;
+
+        self.refresh_inner_session(username, password).await
+    }
+
+    async fn refresh_inner_session(&self, username: String, password: String) -> Result<(), Error> {
+        match &self.protocol {
+            TapoProtocolType::Passthrough(protocol) => {
+                protocol.refresh_session(username, password).await?
+            }
+            TapoProtocolType::Klap(protocol) => {
+                protocol.refresh_session(username, password).await?
+            }
+            _ => {
+                return Err(
+                    anyhow::anyhow!("The protocol discovery should have happened already").into(),
+                );
+            }
+        }
+
+        self.session_generation.fetch_add(1, Ordering::AcqRel);
+
+        Ok(())
+    }
+
+    /// Executes the request, refreshing the session beforehand if it is about to expire
+    /// and once more if the device reports that it has already expired.
+    async fn execute_session_request<R>(
+        &self,
+        request: TapoRequest,
+        with_token: bool,
+    ) -> Result<Option<R>, Error>
+    where
+        R: fmt::Debug + DeserializeOwned + TapoResponseExt,
+    {
+        if !self.options.auto_refresh {
+            return self.execute_inner_request(request, with_token).await;
+        }
+
+        if self.is_session_expiring() {
+            debug!("Session is about to expire, refreshing...");
+            let generation = self.session_generation.load(Ordering::Acquire);
+            self.refresh_session_once(generation).await?;
+        }
+
+        let generation = self.session_generation.load(Ordering::Acquire);
+
+        match self
+            .execute_inner_request(request.clone(), with_token)
+            .await
+        {
+            Err(Error::Tapo(TapoResponseError::SessionTimeout)) => (),
+            result => return result,
+        }
+
+        debug!("Session timed out, refreshing and retrying the request...");
+        self.refresh_session_once(generation).await?;
+        self.execute_inner_request(request, with_token).await
+    }
+
+    async fn execute_inner_request<R>(
+        &self,
+        request: TapoRequest,
+        with_token: bool,
+    ) -> Result<Option<R>, Error>
+    where
+        R: fmt::Debug + DeserializeOwned + TapoResponseExt,
+    {
+        match &self.protocol {
+            TapoProtocolType::Passthrough(protocol) => {
+                protocol.execute_request(request, with_token).await
+            }
+            TapoProtocolType::Klap(protocol) => protocol.execute_request(request, with_token).await,
+            _ => Err(anyhow::anyhow!("The protocol discovery should have happened already").into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::header::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn test_get_cookie() {
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("OTHER=1"));
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("TP_SESSIONID=ABCDEF;TIMEOUT=1440"),
+        );
+        assert_eq!(
+            TapoProtocol::get_cookie(&headers).unwrap(),
+            "TP_SESSIONID=ABCDEF"
+        );
+
+        assert!(TapoProtocol::get_cookie(&HeaderMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_get_session_timeout() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("TP_SESSIONID=ABCDEF;TIMEOUT=1440"),
+        );
+        assert_eq!(
+            TapoProtocol::get_session_timeout(&headers),
+            Duration::from_secs(1440)
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, HeaderValue::from_static("TP_SESSIONID=ABCDEF"));
+        assert_eq!(
+            TapoProtocol::get_session_timeout(&headers),
+            DEFAULT_SESSION_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn test_is_expiring() {
+        let margin = Duration::from_secs(60);
+
+        let expires_at = Instant::now() + Duration::from_secs(3600);
+        assert!(!TapoProtocol::is_expiring(expires_at, margin));
+
+        let expires_at = Instant::now() + Duration::from_secs(30);
+        assert!(TapoProtocol::is_expiring(expires_at, margin));
     }
 }
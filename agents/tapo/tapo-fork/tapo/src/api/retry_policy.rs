@@ -0,0 +1,172 @@
+use std::time::Duration;
+
+use rsa::rand_core::{OsRng, RngCore as _};
+
+use crate::error::Error;
+
+/// Retry policy for requests that fail with a transient error.
+/// Use [`crate::ApiClient::with_retry_policy`] to enable it.
+///
+/// Only idempotent requests are retried. For example, turning a device *on* is retried,
+/// while `device_reboot` and `device_reset` are never sent more than once.
+///
+/// The delay between attempts starts at `initial_backoff` and doubles after every attempt,
+/// up to `max_backoff`. When jitter is enabled, a random delay of up to half of the
+/// computed backoff is subtracted to avoid retrying many devices in lockstep.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use tapo::{ApiClient, OnOffExt, RetryPolicy};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let retry_policy = RetryPolicy::default()
+///         .with_max_attempts(5)
+///         .with_backoff(Duration::from_millis(250), Duration::from_secs(5));
+///
+///     let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+///         .with_retry_policy(retry_policy)
+///         .p110("192.168.1.100")
+///         .await?;
+///
+///     device.on().await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    retryable: fn(&Error) -> bool,
+}
+
+impl Default for RetryPolicy {
+    /// Returns a policy with 3 attempts, a backoff between 500 milliseconds and 10 seconds,
+    /// jitter enabled and [`Error::is_retryable`] as the retryable error classifier.
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable: Error::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Changes the maximum number of attempts, including the first one.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_attempts` - must be at least `1`. A value of `1` disables retries.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Changes the delay before the first retry and the upper bound of the delay.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial` - the delay before the first retry.
+    /// * `max` - the maximum delay between two attempts.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Enables or disables the random jitter applied to the backoff delay.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Changes the function that decides whether an error is worth retrying.
+    ///
+    /// # Arguments
+    ///
+    /// * `retryable` - returns `true` if the request that failed with the given error should be retried.
+    pub fn with_retryable(mut self, retryable: fn(&Error) -> bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Returns the maximum number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn is_retryable(&self, error: &Error) -> bool {
+        (self.retryable)(error)
+    }
+
+    /// Returns the delay to wait after the given failed attempt, starting at `1`.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(1 << exponent)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            let jitter = OsRng.next_u32() as f64 / u32::MAX as f64;
+            backoff.mul_f64(1.0 - jitter / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(false);
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_millis(500));
+        assert_eq!(policy.backoff(100), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter() {
+        let policy = RetryPolicy::default()
+            .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(true);
+
+        for attempt in 1..10 {
+            let backoff = policy.backoff(attempt);
+            let max = policy.clone().with_jitter(false).backoff(attempt);
+            assert!(backoff <= max);
+            assert!(backoff >= max / 2);
+        }
+    }
+
+    #[test]
+    fn test_max_attempts() {
+        assert_eq!(
+            RetryPolicy::default().with_max_attempts(0).max_attempts(),
+            1
+        );
+        assert_eq!(
+            RetryPolicy::default().with_max_attempts(5).max_attempts(),
+            5
+        );
+    }
+}
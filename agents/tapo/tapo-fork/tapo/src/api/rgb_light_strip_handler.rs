@@ -4,10 +4,15 @@ use async_trait::async_trait;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::{Color, ColorLightSetDeviceInfoParams};
+use crate::requests::{
+    Color, ColorLightSetDeviceInfoParams, DefaultPowerState, GenericSetDeviceInfoParams,
+};
 use crate::responses::{DeviceInfoRgbLightStripResult, DeviceUsageEnergyMonitoringResult};
 
-use super::{ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt};
+use super::{
+    ApiClient, ApiClientExt, BrightnessExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, OnOffExt,
+};
 
 /// Handler for the [L900](https://www.tapo.com/en/search/?q=L900) devices.
 #[derive(Debug)]
@@ -26,14 +31,15 @@ impl RgbLightStripHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new().on().send(self).await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new().off().send(self).await
+    /// Sets the state the device is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - either [`DefaultPowerState::On`] or [`DefaultPowerState::LastState`].
+    ///   Lights cannot default to [`DefaultPowerState::Off`].
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::light_default_state(state)?)?;
+        self.client.read().await.set_device_info(json).await
     }
 
     /// Returns *device info* as [`DeviceInfoRgbLightStripResult`].
@@ -80,18 +86,6 @@ impl RgbLightStripHandler {
         ColorLightSetDeviceInfoParams::new()
     }
 
-    /// Sets the *brightness* and turns *on* the device.
-    ///
-    /// # Arguments
-    ///
-    /// * `brightness` - between 1 and 100
-    pub async fn set_brightness(&self, brightness: u8) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new()
-            .brightness(brightness)
-            .send(self)
-            .await
-    }
-
     /// Sets the *color* and turns *on* the device.
     ///
     /// # Arguments
@@ -141,3 +135,15 @@ impl HandlerExt for RgbLightStripHandler {
 }
 
 impl DeviceManagementExt for RgbLightStripHandler {}
+
+impl DeviceInfoExt for RgbLightStripHandler {}
+
+impl DeviceTimeExt for RgbLightStripHandler {}
+
+impl FirmwareExt for RgbLightStripHandler {}
+
+impl ComponentListExt for RgbLightStripHandler {}
+
+impl OnOffExt for RgbLightStripHandler {}
+
+impl BrightnessExt for RgbLightStripHandler {}
@@ -4,10 +4,16 @@ use async_trait::async_trait;
 use tokio::sync::{RwLock, RwLockReadGuard};
 
 use crate::error::Error;
-use crate::requests::{Color, ColorLightSetDeviceInfoParams, LightingEffect};
+use crate::requests::{
+    Color, ColorLightSetDeviceInfoParams, DefaultPowerState, GenericSetDeviceInfoParams,
+    LightingEffect,
+};
 use crate::responses::{DeviceInfoRgbicLightStripResult, DeviceUsageEnergyMonitoringResult};
 
-use super::{ApiClient, ApiClientExt, DeviceManagementExt, HandlerExt};
+use super::{
+    ApiClient, ApiClientExt, BrightnessExt, ComponentListExt, DeviceInfoExt, DeviceManagementExt,
+    DeviceTimeExt, FirmwareExt, HandlerExt, OnOffExt,
+};
 
 /// Handler for the [L920](https://www.tapo.com/en/search/?q=L920) and
 /// [L930](https://www.tapo.com/en/search/?q=L930) devices.
@@ -27,14 +33,15 @@ impl RgbicLightStripHandler {
         Ok(self)
     }
 
-    /// Turns *on* the device.
-    pub async fn on(&self) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new().on().send(self).await
-    }
-
-    /// Turns *off* the device.
-    pub async fn off(&self) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new().off().send(self).await
+    /// Sets the state the device is restored to when power comes back after a power cut.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - either [`DefaultPowerState::On`] or [`DefaultPowerState::LastState`].
+    ///   Lights cannot default to [`DefaultPowerState::Off`].
+    pub async fn set_default_state(&self, state: DefaultPowerState) -> Result<(), Error> {
+        let json = serde_json::to_value(GenericSetDeviceInfoParams::light_default_state(state)?)?;
+        self.client.read().await.set_device_info(json).await
     }
 
     /// Returns *device info* as [`DeviceInfoRgbicLightStripResult`].
@@ -82,19 +89,6 @@ impl RgbicLightStripHandler {
         ColorLightSetDeviceInfoParams::new()
     }
 
-    /// Sets the *brightness* and turns *on* the device.
-    /// Pre-existing *lighting effect* will be removed.
-    ///
-    /// # Arguments
-    ///
-    /// * `brightness` - between 1 and 100
-    pub async fn set_brightness(&self, brightness: u8) -> Result<(), Error> {
-        ColorLightSetDeviceInfoParams::new()
-            .brightness(brightness)
-            .send(self)
-            .await
-    }
-
     /// Sets the *color* and turns *on* the device.
     /// Pre-existing *lighting effect* will be removed.
     ///
@@ -163,3 +157,15 @@ impl HandlerExt for RgbicLightStripHandler {
 }
 
 impl DeviceManagementExt for RgbicLightStripHandler {}
+
+impl DeviceInfoExt for RgbicLightStripHandler {}
+
+impl DeviceTimeExt for RgbicLightStripHandler {}
+
+impl FirmwareExt for RgbicLightStripHandler {}
+
+impl ComponentListExt for RgbicLightStripHandler {}
+
+impl OnOffExt for RgbicLightStripHandler {}
+
+impl BrightnessExt for RgbicLightStripHandler {}
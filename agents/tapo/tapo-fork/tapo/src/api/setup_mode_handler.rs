@@ -0,0 +1,272 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::debug;
+use tokio::sync::RwLock;
+
+use crate::error::Error;
+use crate::requests::QsTime;
+use crate::responses::WirelessNetwork;
+
+use super::{ApiClient, ApiClientExt};
+
+/// Handler for a factory-reset device in setup mode, reached by joining the Wi-Fi network
+/// that the device broadcasts (e.g. `Tapo_Plug_XXXX`). The device is usually at `192.168.0.1`.
+///
+/// It is used to onboard the device without the Tapo app: scan for Wi-Fi networks with
+/// [`SetupModeHandler::scan_wifi`], then call [`SetupModeHandler::onboard`] to bind the
+/// credentials of the [`ApiClient`] and connect the device to the chosen network.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use tapo::ApiClient;
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+///         .setup_mode("192.168.0.1")
+///         .await?;
+///
+///     let networks = device.scan_wifi().await?;
+///     let network = networks
+///         .iter()
+///         .find(|network| network.ssid == "grow-room")
+///         .ok_or("network not found")?;
+///
+///     device.onboard(network, "wifi-password").await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SetupModeHandler {
+    client: Arc<RwLock<ApiClient>>,
+    tapo_username: String,
+    tapo_password: String,
+}
+
+impl SetupModeHandler {
+    pub(crate) fn new(
+        client: Arc<RwLock<ApiClient>>,
+        tapo_username: String,
+        tapo_password: String,
+    ) -> Self {
+        Self {
+            client,
+            tapo_username,
+            tapo_password,
+        }
+    }
+
+    /// Returns *device info* as [`serde_json::Value`].
+    /// It contains all the properties returned from the Tapo API.
+    pub async fn get_device_info_json(&self) -> Result<serde_json::Value, Error> {
+        self.client.read().await.get_device_info().await
+    }
+
+    /// Returns the Wi-Fi networks the device can see, as a list of [`WirelessNetwork`].
+    pub async fn scan_wifi(&self) -> Result<Vec<WirelessNetwork>, Error> {
+        self.client.read().await.get_wireless_scan_info().await
+    }
+
+    /// Binds the credentials of the [`ApiClient`] to the device and connects it to the given network.
+    ///
+    /// The device leaves setup mode and joins the network, so the connection to it is lost.
+    /// A lost connection after the request was sent is therefore not an error,
+    /// but failing to connect to the device is.
+    /// Once it has joined, it can be reached at the address assigned by the network
+    /// with the same [`ApiClient`] credentials, e.g. after finding it with [`ApiClient::discover_devices`].
+    ///
+    /// # Arguments
+    ///
+    /// * `network` - the network to join, as returned by [`SetupModeHandler::scan_wifi`].
+    /// * `wifi_password` - the password of the network. Ignored for open networks.
+    pub async fn onboard(
+        &self,
+        network: &WirelessNetwork,
+        wifi_password: &str,
+    ) -> Result<(), Error> {
+        self.onboard_ssid(&network.ssid, &network.key_type, wifi_password)
+            .await
+    }
+
+    /// Same as [`SetupModeHandler::onboard`], for a network that is not in the scan results,
+    /// e.g. a hidden network.
+    ///
+    /// # Arguments
+    ///
+    /// * `ssid` - the name of the network.
+    /// * `key_type` - the security of the network, e.g. `wpa2_psk`, as reported in [`WirelessNetwork::key_type`].
+    /// * `wifi_password` - the password of the network. Ignored for open networks.
+    pub async fn onboard_ssid(
+        &self,
+        ssid: &str,
+        key_type: &str,
+        wifi_password: &str,
+    ) -> Result<(), Error> {
+        let client = self.client.read().await;
+
+        // Keep the device time zone, but make sure the clock is right.
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(anyhow::Error::from)?
+            .as_secs() as i64;
+        let time = match client.get_device_time().await {
+            Ok(time) => QsTime {
+                timestamp,
+                time_diff: time.time_diff,
+                region: time.region,
+            },
+            Err(e) => {
+                debug!("Failed to get the device time, using UTC: {e}");
+                QsTime {
+                    timestamp,
+                    time_diff: 0,
+                    region: "UTC".to_string(),
+                }
+            }
+        };
+
+        client
+            .set_qs_info(
+                &self.tapo_username,
+                &self.tapo_password,
+                ssid,
+                wifi_password,
+                key_type,
+                time,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::HookTransport;
+    use crate::api::protocol::SimulatedDevice;
+
+    use super::*;
+
+    fn setup_mode_device() -> SimulatedDevice {
+        SimulatedDevice::new("test@tp-link.net", "test").with_responder(|request| {
+                match request["method"].as_str().unwrap() {
+                    "get_wireless_scan_info" => {
+                        let networks = [
+                            json!({"ssid": "R3Jvdw==", "bssid": "AA:BB", "key_type": "wpa2_psk", "signal_level": 3}),
+                            json!({"ssid": "R3Vlc3Q=", "bssid": "CC:DD", "key_type": "none", "signal_level": 1}),
+                        ];
+                        let start_index = request["params"]["start_index"].as_u64().unwrap() as usize;
+                        json!({
+                            "error_code": 0,
+                            "result": {
+                                "ap_list": networks[start_index..=start_index],
+                                "sum": networks.len(),
+                                "start_index": start_index,
+                            },
+                        })
+                    }
+                    "get_device_time" => json!({
+                        "error_code": 0,
+                        "result": {"timestamp": 0, "time_diff": 60, "region": "Europe/London"},
+                    }),
+                    "set_qs_info" => json!({"error_code": 0}),
+                    method => panic!("Unexpected method {method}"),
+                }
+            })
+    }
+
+    #[tokio::test]
+    async fn test_onboard() {
+        let device = Arc::new(setup_mode_device());
+
+        let handler = ApiClient::new("user@example.com", "secret")
+            .with_transport(device.clone())
+            .setup_mode("192.168.0.1")
+            .await
+            .unwrap();
+
+        let networks = handler.scan_wifi().await.unwrap();
+        let ssids = networks
+            .iter()
+            .map(|network| network.ssid.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ssids, ["Grow", "Guest"]);
+
+        handler.onboard(&networks[0], "pass").await.unwrap();
+
+        let requests = device.requests.lock().unwrap().clone();
+        let set_qs_info = requests
+            .iter()
+            .find(|request| request["method"] == "set_qs_info")
+            .unwrap();
+        assert_eq!(
+            set_qs_info["params"]["account"],
+            json!({"username": "dXNlckBleGFtcGxlLmNvbQ==", "password": "c2VjcmV0"})
+        );
+        assert_eq!(
+            set_qs_info["params"]["wireless"],
+            json!({"ssid": "R3Jvdw==", "password": "cGFzcw==", "key_type": "wpa2_psk"})
+        );
+        assert_eq!(set_qs_info["params"]["time"]["region"], "Europe/London");
+    }
+
+    #[tokio::test]
+    async fn test_onboard_does_not_report_credentials() {
+        let exchanges = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let transport = HookTransport::new(setup_mode_device()).with_exchange_hook({
+            let exchanges = exchanges.clone();
+            move |request, _| exchanges.lock().unwrap().push(request.to_string())
+        });
+
+        let handler = ApiClient::new("user@example.com", "secret")
+            .with_transport(transport)
+            .setup_mode("192.168.0.1")
+            .await
+            .unwrap();
+        let networks = handler.scan_wifi().await.unwrap();
+        handler.onboard(&networks[0], "pass").await.unwrap();
+
+        let exchanges = exchanges.lock().unwrap();
+        assert!(
+            exchanges
+                .iter()
+                .any(|request| request.contains("get_wireless_scan_info"))
+        );
+        assert!(
+            !exchanges
+                .iter()
+                .any(|request| request.contains("set_qs_info"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_onboard_reports_unreachable_device() {
+        let device = setup_mode_device().with_unreachable_method("set_qs_info");
+
+        let handler = ApiClient::new("user@example.com", "secret")
+            .with_transport(device)
+            .setup_mode("192.168.0.1")
+            .await
+            .unwrap();
+        let networks = handler.scan_wifi().await.unwrap();
+        let error = handler.onboard(&networks[0], "pass").await.unwrap_err();
+
+        assert!(matches!(error, Error::Http(e) if e.is_connect()));
+    }
+
+    #[tokio::test]
+    async fn test_setup_mode_rejects_bound_device() {
+        let device = Arc::new(SimulatedDevice::new("user@example.com", "secret"));
+
+        let error = ApiClient::new("user@example.com", "secret")
+            .with_transport(device)
+            .setup_mode("192.168.0.1")
+            .await
+            .unwrap_err();
+
+        assert!(error.is_auth());
+    }
+}
@@ -0,0 +1,69 @@
+mod hook_transport;
+mod http_transport;
+mod recording_transport;
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use reqwest::header::HeaderMap;
+
+use crate::error::Error;
+
+pub use hook_transport::*;
+pub use http_transport::*;
+pub use recording_transport::*;
+
+/// HTTP request sent to a device by the protocol layer.
+/// The body is already encrypted, except for the initial protocol negotiation.
+#[derive(Debug, Clone)]
+pub struct TransportRequest {
+    /// The URL of the device endpoint.
+    pub url: String,
+    /// The value of the `Cookie` header, if any.
+    pub cookie: Option<String>,
+    /// The request body.
+    pub body: Vec<u8>,
+}
+
+/// HTTP response received from a device.
+#[derive(Debug, Clone)]
+pub struct TransportResponse {
+    /// The HTTP status code.
+    pub status: StatusCode,
+    /// The response headers. The session cookie is read from the `Set-Cookie` headers.
+    pub headers: HeaderMap,
+    /// The response body.
+    pub body: Vec<u8>,
+}
+
+/// Sends the HTTP requests of the protocol layer to the devices.
+///
+/// [`HttpTransport`] is used by default. A custom transport can be set with [`crate::ApiClient::with_transport`],
+/// e.g. to talk to a simulated device in tests, or to wrap another transport
+/// with [`HookTransport`] or [`RecordingTransport`].
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    /// Sends a `POST` request to the device and returns its response.
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error>;
+
+    /// Called with the decrypted JSON of every request and the matching response
+    /// once they have been exchanged with the device.
+    /// Requests that contain credentials, such as logins and the quick setup of a device,
+    /// are not reported.
+    /// Does nothing by default.
+    fn on_exchange(&self, _request: &str, _response: &str) {}
+}
+
+/// Allows keeping a handle to a transport that is also used by an [`crate::ApiClient`].
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        (**self).send(request).await
+    }
+
+    fn on_exchange(&self, request: &str, response: &str) {
+        (**self).on_exchange(request, response)
+    }
+}
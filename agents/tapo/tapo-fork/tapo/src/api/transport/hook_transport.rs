@@ -0,0 +1,123 @@
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::error::Error;
+
+use super::{Transport, TransportRequest, TransportResponse};
+
+type RequestHook = Arc<dyn Fn(&TransportRequest) + Send + Sync>;
+type ResponseHook = Arc<dyn Fn(&TransportRequest, &Result<TransportResponse, Error>) + Send + Sync>;
+type ExchangeHook = Arc<dyn Fn(&str, &str) + Send + Sync>;
+
+/// [`Transport`] that calls the given hooks around an inner transport, e.g. for tracing.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+///
+/// use tapo::{ApiClient, HookTransport, HttpTransport, OnOffExt};
+///
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let transport = HookTransport::new(HttpTransport::new(Duration::from_secs(30))?)
+///         .with_response_hook(|request, response| {
+///             println!("{} -> {:?}", request.url, response.as_ref().map(|r| r.status));
+///         })
+///         .with_exchange_hook(|request, response| {
+///             println!("{request} -> {response}");
+///         });
+///
+///     let device = ApiClient::new("tapo-username@example.com", "tapo-password")
+///         .with_transport(transport)
+///         .p110("192.168.1.100")
+///         .await?;
+///
+///     device.on().await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct HookTransport<T> {
+    inner: T,
+    request_hook: Option<RequestHook>,
+    response_hook: Option<ResponseHook>,
+    exchange_hook: Option<ExchangeHook>,
+}
+
+impl<T: Transport> HookTransport<T> {
+    /// Returns a new instance of [`HookTransport`] that wraps the given transport.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            request_hook: None,
+            response_hook: None,
+            exchange_hook: None,
+        }
+    }
+
+    /// Sets the hook that is called before every request is sent.
+    pub fn with_request_hook(
+        mut self,
+        hook: impl Fn(&TransportRequest) + Send + Sync + 'static,
+    ) -> Self {
+        self.request_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the hook that is called with every request and its response, or the error that occurred.
+    pub fn with_response_hook(
+        mut self,
+        hook: impl Fn(&TransportRequest, &Result<TransportResponse, Error>) + Send + Sync + 'static,
+    ) -> Self {
+        self.response_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the hook that is called with the decrypted JSON of every request and its response.
+    /// See [`Transport::on_exchange`].
+    pub fn with_exchange_hook(mut self, hook: impl Fn(&str, &str) + Send + Sync + 'static) -> Self {
+        self.exchange_hook = Some(Arc::new(hook));
+        self
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HookTransport<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookTransport")
+            .field("inner", &self.inner)
+            .field("request_hook", &self.request_hook.is_some())
+            .field("response_hook", &self.response_hook.is_some())
+            .field("exchange_hook", &self.exchange_hook.is_some())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for HookTransport<T> {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        if let Some(hook) = &self.request_hook {
+            hook(&request);
+        }
+
+        let Some(hook) = &self.response_hook else {
+            return self.inner.send(request).await;
+        };
+
+        let response = self.inner.send(request.clone()).await;
+        hook(&request, &response);
+
+        response
+    }
+
+    fn on_exchange(&self, request: &str, response: &str) {
+        if let Some(hook) = &self.exchange_hook {
+            hook(request, response);
+        }
+
+        self.inner.on_exchange(request, response);
+    }
+}
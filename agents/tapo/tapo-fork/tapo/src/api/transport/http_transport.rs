@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use reqwest::header::COOKIE;
+
+use crate::error::Error;
+
+use super::{Transport, TransportRequest, TransportResponse};
+
+/// The default [`Transport`], which sends the requests over HTTP with [`reqwest`].
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: Client,
+}
+
+impl HttpTransport {
+    /// Returns a new instance of [`HttpTransport`].
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - the connection timeout.
+    pub fn new(timeout: Duration) -> Result<Self, Error> {
+        let client = Client::builder()
+            .http1_title_case_headers()
+            .timeout(timeout)
+            .build()?;
+
+        Ok(Self::from_client(client))
+    }
+
+    /// Returns a new instance of [`HttpTransport`] that uses the given [`reqwest::Client`].
+    pub fn from_client(client: Client) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        let mut builder = self.client.post(&request.url).body(request.body);
+
+        if let Some(cookie) = request.cookie {
+            builder = builder.header(COOKIE, cookie);
+        }
+
+        let response = builder.send().await?;
+
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = response.bytes().await?.to_vec();
+
+        Ok(TransportResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
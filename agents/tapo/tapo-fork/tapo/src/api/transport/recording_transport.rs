@@ -0,0 +1,83 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use log::warn;
+use serde::Serialize;
+
+use crate::error::Error;
+
+use super::{Transport, TransportRequest, TransportResponse};
+
+/// [`Transport`] that appends the decrypted JSON of every request and its response
+/// to a file, one JSON object per line, e.g. to attach to a bug report.
+///
+/// Login requests are never recorded, but the recorded data can still contain personal information
+/// like the device nickname, SSID or location. Review the file before sharing it.
+#[derive(Debug)]
+pub struct RecordingTransport<T> {
+    inner: T,
+    file: Mutex<File>,
+}
+
+#[derive(Serialize)]
+struct Exchange {
+    timestamp: String,
+    request: serde_json::Value,
+    response: serde_json::Value,
+}
+
+impl<T: Transport> RecordingTransport<T> {
+    /// Returns a new instance of [`RecordingTransport`] that wraps the given transport.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - the transport that sends the requests.
+    /// * `path` - the file the exchanges are appended to. It is created if it doesn't exist.
+    pub fn new(inner: T, path: impl AsRef<Path>) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(anyhow::Error::from)?;
+
+        Ok(Self {
+            inner,
+            file: Mutex::new(file),
+        })
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordingTransport<T> {
+    async fn send(&self, request: TransportRequest) -> Result<TransportResponse, Error> {
+        self.inner.send(request).await
+    }
+
+    fn on_exchange(&self, request: &str, response: &str) {
+        let exchange = Exchange {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            request: parse_or_string(request),
+            response: parse_or_string(response),
+        };
+
+        let result = serde_json::to_string(&exchange)
+            .map_err(anyhow::Error::from)
+            .and_then(|line| {
+                let mut file = self.file.lock().expect("This should never happen");
+                Ok(writeln!(file, "{line}")?)
+            });
+
+        if let Err(err) = result {
+            warn!("Failed to record the exchange: {err}");
+        }
+
+        self.inner.on_exchange(request, response);
+    }
+}
+
+fn parse_or_string(value: &str) -> serde_json::Value {
+    serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()))
+}
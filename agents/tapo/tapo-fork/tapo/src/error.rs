@@ -2,6 +2,13 @@
 #[derive(thiserror::Error, Debug)]
 #[non_exhaustive]
 pub enum TapoResponseError {
+    /// The device clock is out of sync and rejected the request.
+    /// Setting the device time usually resolves it.
+    #[error("Clock out of sync")]
+    ClockOutOfSync,
+    /// The device is busy processing another request or operation.
+    #[error("Device busy")]
+    DeviceBusy,
     /// Unexpected empty result.
     #[error("Unexpected empty result")]
     EmptyResult,
@@ -28,6 +35,9 @@ pub enum TapoResponseError {
     /// Malformed request.
     #[error("Malformed request")]
     MalformedRequest,
+    /// The device is temporarily refusing requests, e.g. after too many failed login attempts.
+    #[error("Rate limited")]
+    RateLimited,
     /// Session timeout.
     #[error("Session timeout")]
     SessionTimeout,
@@ -39,6 +49,9 @@ pub enum TapoResponseError {
         /// Error description.
         description: String,
     },
+    /// The device does not support the requested method.
+    #[error("Unsupported method")]
+    UnsupportedMethod,
     /// Unknown Error. This is a catch-all for errors that don't fit into the other categories.
     /// In time, some of these might be added as their own variants.
     #[error("Unknown error: {0}")]
@@ -73,3 +86,35 @@ pub enum Error {
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
+
+impl Error {
+    /// Returns `true` if the error is likely transient and the request may succeed if retried after a backoff,
+    /// e.g. connection failures, timeouts, an expired session or a busy device.
+    ///
+    /// [`TapoResponseError::RateLimited`] is not retryable, the device locks out further logins
+    /// for a while and retrying only extends the lockout.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Http(error) => {
+                error.is_timeout() || error.is_connect() || error.is_request() || error.is_body()
+            }
+            Error::Tapo(
+                TapoResponseError::DeviceBusy
+                | TapoResponseError::InvalidResponse
+                | TapoResponseError::SessionTimeout,
+            ) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the error is caused by invalid credentials or denied access.
+    /// Retrying such requests without changing the credentials or the device settings will not succeed.
+    pub fn is_auth(&self) -> bool {
+        matches!(
+            self,
+            Error::Tapo(
+                TapoResponseError::Unauthorized { .. } | TapoResponseError::Forbidden { .. }
+            )
+        )
+    }
+}
@@ -10,7 +10,7 @@
 //! ```rust,no_run
 //! use std::{env, thread, time::Duration};
 //!
-//! use tapo::{requests::Color, ApiClient};
+//! use tapo::{ApiClient, BrightnessExt, OnOffExt, requests::Color};
 //!
 //! #[tokio::main]
 //! async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -1,6 +1,9 @@
 //! Tapo request objects.
 
 mod add_countdown_rule;
+mod antitheft_rule;
+mod batch_read;
+mod child_device_list;
 mod control_child;
 mod device_reboot;
 mod energy_data_interval;
@@ -15,16 +18,26 @@ mod multiple_request;
 mod play_alarm;
 mod power_data_interval;
 mod secure_passthrough;
+mod set_auto_off_config;
 mod set_device_info;
+mod set_device_time;
+mod set_led_info;
+mod set_protection_power;
+mod set_qs_info;
 mod tapo_request;
 
 pub use crate::responses::TemperatureUnitKE100;
+pub use antitheft_rule::AntitheftRuleParams;
+pub use batch_read::BatchReadParams;
 pub use energy_data_interval::*;
 pub use play_alarm::*;
 pub use power_data_interval::*;
 pub use set_device_info::*;
 
 pub(crate) use add_countdown_rule::*;
+pub(crate) use antitheft_rule::{EditAntitheftRuleParams, RemoveRulesParams};
+pub(crate) use batch_read::BatchRead;
+pub(crate) use child_device_list::*;
 pub(crate) use control_child::*;
 pub(crate) use device_reboot::*;
 pub(crate) use get_child_device_list::*;
@@ -36,5 +49,9 @@ pub(crate) use handshake::*;
 pub(crate) use login_device::*;
 pub(crate) use multiple_request::*;
 pub(crate) use secure_passthrough::*;
+pub(crate) use set_auto_off_config::*;
+pub(crate) use set_device_time::*;
+pub(crate) use set_led_info::*;
+pub(crate) use set_protection_power::*;
+pub(crate) use set_qs_info::*;
 pub(crate) use tapo_request::*;
-
@@ -3,7 +3,7 @@
 use serde::Serialize;
 
 /// Parameters for editing a countdown rule
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct EditCountdownRuleParams {
     /// Rule ID to edit
     pub id: String,
@@ -34,7 +34,7 @@ impl EditCountdownRuleParams {
 }
 
 /// Parameters for adding a countdown rule
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct AddCountdownRuleParams {
     /// Delay in seconds
     pub delay: u64,
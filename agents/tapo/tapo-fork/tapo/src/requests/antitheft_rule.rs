@@ -0,0 +1,146 @@
+//! Parameters for anti-theft (away mode) rules
+
+use serde::Serialize;
+
+use crate::error::Error;
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+const ALL_WEEK_DAYS: u8 = 0b111_1111;
+
+/// Builder that is used by the `add_antitheft_rule` and `edit_antitheft_rule` APIs of the plug handlers.
+///
+/// While the rule is active, the device turns *on* and *off* at random times
+/// between `start_min` and `end_min` on the selected week days.
+#[derive(Debug, Clone, Serialize)]
+pub struct AntitheftRuleParams {
+    enable: bool,
+    s_min: u16,
+    e_min: u16,
+    week_day: u8,
+    mode: &'static str,
+}
+
+impl AntitheftRuleParams {
+    /// Returns a new, enabled rule that repeats every day.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_min` - start of the active period in minutes since midnight, e.g. `18 * 60` for 18:00.
+    /// * `end_min` - end of the active period in minutes since midnight.
+    pub fn new(start_min: u16, end_min: u16) -> Self {
+        Self {
+            enable: true,
+            s_min: start_min,
+            e_min: end_min,
+            week_day: ALL_WEEK_DAYS,
+            mode: "repeat",
+        }
+    }
+
+    /// Sets the week days on which the rule is active, as a bit mask where
+    /// bit 0 is Sunday and bit 6 is Saturday, e.g. `0b011_1110` for Monday to Friday.
+    pub fn with_week_days(mut self, week_days: u8) -> Self {
+        self.week_day = week_days;
+        self
+    }
+
+    /// Sets whether the rule is enabled.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enable = enabled;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.s_min >= MINUTES_PER_DAY || self.e_min >= MINUTES_PER_DAY {
+            return Err(Error::Validation {
+                field: "start_min/end_min".to_string(),
+                message: "Must be lower than 1440 minutes".to_string(),
+            });
+        }
+
+        if self.week_day == 0 || self.week_day > ALL_WEEK_DAYS {
+            return Err(Error::Validation {
+                field: "week_days".to_string(),
+                message: "Must select at least one day and only use the lowest 7 bits".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Parameters for editing an anti-theft rule
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct EditAntitheftRuleParams {
+    /// Rule ID to edit
+    pub id: String,
+    #[serde(flatten)]
+    pub rule: AntitheftRuleParams,
+}
+
+/// Parameters for removing rules
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RemoveRulesParams {
+    pub rule_list: Vec<RuleId>,
+}
+
+/// A rule reference
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RuleId {
+    pub id: String,
+}
+
+impl RemoveRulesParams {
+    pub fn new(id: String) -> Self {
+        Self {
+            rule_list: vec![RuleId { id }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_edit_antitheft_rule() {
+        let params = EditAntitheftRuleParams {
+            id: "A1".to_string(),
+            rule: AntitheftRuleParams::new(18 * 60, 23 * 60).with_week_days(0b011_1110),
+        };
+
+        assert_eq!(
+            serde_json::to_value(params).unwrap(),
+            json!({
+                "id": "A1",
+                "enable": true,
+                "s_min": 1080,
+                "e_min": 1380,
+                "week_day": 62,
+                "mode": "repeat",
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            AntitheftRuleParams::new(18 * 60, 23 * 60)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            AntitheftRuleParams::new(18 * 60, 24 * 60)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            AntitheftRuleParams::new(18 * 60, 23 * 60)
+                .with_week_days(0)
+                .validate()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use crate::requests::{EmptyParams, GetRulesParams, TapoParams, TapoRequest};
+
+/// Builder that is used by the `get_batch` API of the plug handlers
+/// to read multiple properties in a single `multipleRequest` round trip.
+///
+/// Each selected property is returned as its own result in [`crate::responses::BatchReadResult`],
+/// so that a property the device fails to return does not fail the whole batch.
+#[derive(Debug, Clone, Default)]
+pub struct BatchReadParams {
+    device_info: bool,
+    device_usage: bool,
+    current_power: bool,
+    energy_usage: bool,
+    emeter_data: bool,
+    countdown_rules: bool,
+    schedule_rules: bool,
+    next_event: bool,
+}
+
+impl BatchReadParams {
+    /// Returns a new, empty instance of [`BatchReadParams`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the *device info*.
+    pub fn device_info(mut self) -> Self {
+        self.device_info = true;
+        self
+    }
+
+    /// Reads the *device usage*.
+    pub fn device_usage(mut self) -> Self {
+        self.device_usage = true;
+        self
+    }
+
+    /// Reads the *current power*. Only supported by energy monitoring devices.
+    pub fn current_power(mut self) -> Self {
+        self.current_power = true;
+        self
+    }
+
+    /// Reads the *energy usage*. Only supported by energy monitoring devices.
+    pub fn energy_usage(mut self) -> Self {
+        self.energy_usage = true;
+        self
+    }
+
+    /// Reads the *emeter data*. Only supported by energy monitoring devices.
+    pub fn emeter_data(mut self) -> Self {
+        self.emeter_data = true;
+        self
+    }
+
+    /// Reads the *countdown rules*.
+    pub fn countdown_rules(mut self) -> Self {
+        self.countdown_rules = true;
+        self
+    }
+
+    /// Reads the *schedule rules*.
+    pub fn schedule_rules(mut self) -> Self {
+        self.schedule_rules = true;
+        self
+    }
+
+    /// Reads the *next event*.
+    pub fn next_event(mut self) -> Self {
+        self.next_event = true;
+        self
+    }
+}
+
+/// A single read of a [`BatchReadParams`] batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BatchRead {
+    DeviceInfo,
+    DeviceUsage,
+    CurrentPower,
+    EnergyUsage,
+    EmeterData,
+    CountdownRules,
+    ScheduleRules,
+    NextEvent,
+}
+
+impl BatchReadParams {
+    pub(crate) fn reads(&self) -> Vec<BatchRead> {
+        [
+            (self.device_info, BatchRead::DeviceInfo),
+            (self.device_usage, BatchRead::DeviceUsage),
+            (self.current_power, BatchRead::CurrentPower),
+            (self.energy_usage, BatchRead::EnergyUsage),
+            (self.emeter_data, BatchRead::EmeterData),
+            (self.countdown_rules, BatchRead::CountdownRules),
+            (self.schedule_rules, BatchRead::ScheduleRules),
+            (self.next_event, BatchRead::NextEvent),
+        ]
+        .into_iter()
+        .filter_map(|(selected, read)| selected.then_some(read))
+        .collect()
+    }
+}
+
+impl BatchRead {
+    pub(crate) fn to_request(self) -> TapoRequest {
+        match self {
+            Self::DeviceInfo => TapoRequest::GetDeviceInfo(TapoParams::new(EmptyParams)),
+            Self::DeviceUsage => TapoRequest::GetDeviceUsage(TapoParams::new(EmptyParams)),
+            Self::CurrentPower => TapoRequest::GetCurrentPower(TapoParams::new(EmptyParams)),
+            Self::EnergyUsage => TapoRequest::GetEnergyUsage(TapoParams::new(EmptyParams)),
+            Self::EmeterData => TapoRequest::GetEmeterData(TapoParams::new(EmptyParams)),
+            Self::CountdownRules => {
+                TapoRequest::GetCountdownRules(TapoParams::new(GetRulesParams::default()))
+            }
+            Self::ScheduleRules => {
+                TapoRequest::GetScheduleRules(TapoParams::new(GetRulesParams::default()))
+            }
+            Self::NextEvent => TapoRequest::GetNextEvent(TapoParams::new(EmptyParams)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads() {
+        assert!(BatchReadParams::new().reads().is_empty());
+
+        let params = BatchReadParams::new()
+            .schedule_rules()
+            .device_info()
+            .current_power();
+
+        assert_eq!(
+            params.reads(),
+            vec![
+                BatchRead::DeviceInfo,
+                BatchRead::CurrentPower,
+                BatchRead::ScheduleRules
+            ]
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+use crate::responses::PairingCandidate;
+
+/// Child devices to pair with or remove from a hub.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ChildDeviceListParams {
+    child_device_list: Vec<ChildDeviceParams>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ChildDeviceParams {
+    device_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<String>,
+}
+
+impl ChildDeviceListParams {
+    pub fn pair(candidates: &[PairingCandidate]) -> Self {
+        Self {
+            child_device_list: candidates
+                .iter()
+                .map(|candidate| ChildDeviceParams {
+                    device_id: candidate.device_id.clone(),
+                    category: Some(candidate.category.clone()),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn remove(device_id: impl Into<String>) -> Self {
+        Self {
+            child_device_list: vec![ChildDeviceParams {
+                device_id: device_id.into(),
+                category: None,
+            }],
+        }
+    }
+}
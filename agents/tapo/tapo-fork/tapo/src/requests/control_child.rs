@@ -2,7 +2,7 @@ use serde::Serialize;
 
 use crate::requests::tapo_request::TapoRequest;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct ControlChildParams {
     device_id: String,
     #[serde(rename = "requestData")]
@@ -16,4 +16,12 @@ impl ControlChildParams {
             request_data,
         }
     }
+
+    pub fn is_idempotent(&self) -> bool {
+        self.request_data.is_idempotent()
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.request_data.is_sensitive()
+    }
 }
@@ -1,6 +1,6 @@
 use serde::Serialize;
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub(crate) struct GetChildDeviceListParams {
     start_index: u64,
 }
@@ -3,7 +3,7 @@ use serde::Serialize;
 
 use crate::requests::PowerDataInterval;
 
-#[derive(Debug, Default, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub(crate) struct GetPowerDataParams {
     start_timestamp: u64,
     end_timestamp: u64,
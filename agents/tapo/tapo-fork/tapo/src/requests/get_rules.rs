@@ -1,7 +1,7 @@
 use serde::Serialize;
 
 /// Parameters for getting schedule/countdown rules
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub(crate) struct GetRulesParams {
     pub start_index: u32,
 }
@@ -12,9 +12,3 @@ impl GetRulesParams {
         Self { start_index }
     }
 }
-
-impl Default for GetRulesParams {
-    fn default() -> Self {
-        Self { start_index: 0 }
-    }
-}
@@ -2,7 +2,7 @@ use serde::Serialize;
 
 use crate::requests::TapoRequest;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct MultipleRequestParams {
     requests: Vec<TapoRequest>,
 }
@@ -11,4 +11,12 @@ impl MultipleRequestParams {
     pub fn new(requests: Vec<TapoRequest>) -> Self {
         Self { requests }
     }
+
+    pub fn is_idempotent(&self) -> bool {
+        self.requests.iter().all(TapoRequest::is_idempotent)
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.requests.iter().any(TapoRequest::is_sensitive)
+    }
 }
@@ -1,9 +1,9 @@
 use crate::Error;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 
 /// The volume of the alarm.
 /// For the H100, this is a fixed list of volume levels.
-#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 #[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all, eq, eq_int))]
 pub enum AlarmVolume {
@@ -30,7 +30,7 @@ impl AlarmVolume {
 }
 
 /// The ringtone of a H100 alarm.
-#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
 #[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all, eq, eq_int))]
 pub enum AlarmRingtone {
     /// Use the default ringtone for the hub.
@@ -135,7 +135,7 @@ impl Serialize for AlarmDuration {
 }
 
 /// Parameters for playing the alarm on a H100 hub.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct PlayAlarmParams {
     #[serde(skip_serializing_if = "AlarmRingtone::is_default")]
     alarm_type: AlarmRingtone,
@@ -296,6 +296,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deserialize_ringtone_and_volume() {
+        assert_eq!(
+            serde_json::from_str::<AlarmRingtone>(r#""Doorbell Ring 1""#).unwrap(),
+            AlarmRingtone::DoorbellRing1
+        );
+        assert_eq!(
+            serde_json::from_str::<AlarmVolume>(r#""high""#).unwrap(),
+            AlarmVolume::High
+        );
+    }
+
     #[test]
     fn test_serialize_all_params_are_some_and_duration_is_1second() {
         assert_eq!(
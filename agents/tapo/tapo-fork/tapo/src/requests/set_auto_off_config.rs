@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SetAutoOffConfigParams {
+    enable: bool,
+    delay_min: u64,
+}
+
+impl SetAutoOffConfigParams {
+    pub fn new(enable: bool, delay_min: u64) -> Result<Self, Error> {
+        Self { enable, delay_min }.validate()
+    }
+
+    fn validate(self) -> Result<Self, Error> {
+        if self.enable && self.delay_min == 0 {
+            return Err(Error::Validation {
+                field: "delay_min".to_string(),
+                message: "Must be greater than 0 when auto-off is enabled".to_string(),
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_set_auto_off_config() {
+        assert_eq!(
+            serde_json::to_value(SetAutoOffConfigParams::new(true, 120).unwrap()).unwrap(),
+            json!({"enable": true, "delay_min": 120})
+        );
+        assert!(SetAutoOffConfigParams::new(true, 0).is_err());
+    }
+}
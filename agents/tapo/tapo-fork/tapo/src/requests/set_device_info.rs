@@ -1,14 +1,19 @@
 mod color;
 mod color_light;
+mod default_state;
 mod generic_device;
 mod light;
 mod lighting_effect;
+mod t31x;
 mod trv;
 
 pub use color::*;
 pub use color_light::*;
+pub use default_state::DefaultPowerState;
 pub use lighting_effect::*;
 
+pub(crate) use default_state::DefaultStatesParams;
 pub(crate) use generic_device::*;
 pub(crate) use light::*;
+pub(crate) use t31x::*;
 pub(crate) use trv::*;
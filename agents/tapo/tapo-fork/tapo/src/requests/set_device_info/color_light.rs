@@ -180,6 +180,13 @@ mod tests {
     use tokio::sync::{RwLock, RwLockReadGuard};
 
     use crate::ApiClientExt;
+    use crate::requests::{EnergyDataInterval, PowerDataInterval};
+    use crate::responses::{
+        AutoOffConfigResult, ComponentListResult, CountdownRulesResult, CurrentPowerResult,
+        DeviceTimeResult, EmeterDataResult, EnergyDataResult, EnergyUsageResult,
+        FirmwareDownloadStateResult, LatestFirmwareResult, LedInfoResult, NextEventResult,
+        PowerDataResult, ScheduleRulesResult,
+    };
 
     use super::*;
 
@@ -197,6 +204,69 @@ mod tests {
         async fn device_reset(&self) -> Result<(), Error> {
             unimplemented!()
         }
+        async fn get_led_info(&self) -> Result<LedInfoResult, Error> {
+            unimplemented!()
+        }
+        async fn set_led_info(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+            unimplemented!()
+        }
+        async fn set_auto_off_config(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_device_time(&self) -> Result<DeviceTimeResult, Error> {
+            unimplemented!()
+        }
+        async fn set_device_time(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_latest_firmware(&self) -> Result<LatestFirmwareResult, Error> {
+            unimplemented!()
+        }
+        async fn firmware_download(&self) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_firmware_download_state(&self) -> Result<FirmwareDownloadStateResult, Error> {
+            unimplemented!()
+        }
+        async fn get_component_list(&self) -> Result<ComponentListResult, Error> {
+            unimplemented!()
+        }
+        async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
+            unimplemented!()
+        }
+        async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
+            unimplemented!()
+        }
+        async fn get_energy_data(&self, _: EnergyDataInterval) -> Result<EnergyDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_power_data(&self, _: PowerDataInterval) -> Result<PowerDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
+            unimplemented!()
+        }
+        async fn add_countdown_rule(&self, _: u64, _: bool) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
+            unimplemented!()
+        }
+        async fn get_next_event(&self) -> Result<NextEventResult, Error> {
+            unimplemented!()
+        }
+        async fn get_child_device_list_json(&self, _: u64) -> Result<serde_json::Value, Error> {
+            unimplemented!()
+        }
+        async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
+            unimplemented!()
+        }
     }
 
     #[derive(Debug)]
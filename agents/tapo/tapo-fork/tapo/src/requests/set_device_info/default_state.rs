@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::responses::{DefaultPlugState, DefaultPowerType, PlugState};
+
+/// The state a device is restored to when power comes back after a power cut.
+/// Used by the `set_default_state` API of the plug, power strip plug and light handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefaultPowerState {
+    /// Always turn *on*.
+    On,
+    /// Always turn *off*. Not supported by light bulbs and strips.
+    Off,
+    /// Restore the state the device was in before the power cut.
+    LastState,
+}
+
+impl From<&DefaultPlugState> for DefaultPowerState {
+    fn from(value: &DefaultPlugState) -> Self {
+        match value {
+            DefaultPlugState::Custom { state } if state.on => Self::On,
+            DefaultPlugState::Custom { .. } => Self::Off,
+            DefaultPlugState::LastStates {} => Self::LastState,
+        }
+    }
+}
+
+/// The `default_states` property of `set_device_info`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum DefaultStatesParams {
+    Plug(DefaultPlugState),
+    Light { re_power_type: DefaultPowerType },
+}
+
+impl DefaultStatesParams {
+    pub fn plug(state: DefaultPowerState) -> Self {
+        Self::Plug(match state {
+            DefaultPowerState::On => DefaultPlugState::Custom {
+                state: PlugState { on: true },
+            },
+            DefaultPowerState::Off => DefaultPlugState::Custom {
+                state: PlugState { on: false },
+            },
+            DefaultPowerState::LastState => DefaultPlugState::LastStates {},
+        })
+    }
+
+    pub fn light(state: DefaultPowerState) -> Result<Self, Error> {
+        let re_power_type = match state {
+            DefaultPowerState::On => DefaultPowerType::AlwaysOn,
+            DefaultPowerState::LastState => DefaultPowerType::LastStates,
+            DefaultPowerState::Off => {
+                return Err(Error::Validation {
+                    field: "default_state".to_string(),
+                    message: "Lights can only default to on or to their last state".to_string(),
+                });
+            }
+        };
+
+        Ok(Self::Light { re_power_type })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_plug() {
+        assert_eq!(
+            serde_json::to_value(DefaultStatesParams::plug(DefaultPowerState::Off)).unwrap(),
+            json!({"type": "custom", "state": {"on": false}})
+        );
+        assert_eq!(
+            serde_json::to_value(DefaultStatesParams::plug(DefaultPowerState::LastState)).unwrap(),
+            json!({"type": "last_states"})
+        );
+    }
+
+    #[test]
+    fn test_from_default_plug_state() {
+        for state in [
+            DefaultPowerState::On,
+            DefaultPowerState::Off,
+            DefaultPowerState::LastState,
+        ] {
+            let DefaultStatesParams::Plug(plug_state) = DefaultStatesParams::plug(state) else {
+                panic!("Expected a plug state");
+            };
+            assert_eq!(DefaultPowerState::from(&plug_state), state);
+        }
+    }
+
+    #[test]
+    fn test_light() {
+        assert_eq!(
+            serde_json::to_value(DefaultStatesParams::light(DefaultPowerState::On).unwrap())
+                .unwrap(),
+            json!({"re_power_type": "always_on"})
+        );
+        assert!(DefaultStatesParams::light(DefaultPowerState::Off).is_err());
+    }
+}
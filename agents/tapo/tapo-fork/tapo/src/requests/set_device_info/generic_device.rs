@@ -1,23 +1,113 @@
+use base64::{Engine as _, engine::general_purpose};
 use serde::Serialize;
 
 use crate::error::Error;
+use crate::requests::{DefaultPowerState, DefaultStatesParams};
 
 #[derive(Debug, Default, Serialize)]
 pub(crate) struct GenericSetDeviceInfoParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub device_on: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_states: Option<DefaultStatesParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<i64>,
 }
 
 impl GenericSetDeviceInfoParams {
     pub fn device_on(value: bool) -> Result<Self, Error> {
         Self {
             device_on: Some(value),
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    pub fn plug_default_state(state: DefaultPowerState) -> Result<Self, Error> {
+        Self {
+            default_states: Some(DefaultStatesParams::plug(state)),
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    pub fn light_default_state(state: DefaultPowerState) -> Result<Self, Error> {
+        Self {
+            default_states: Some(DefaultStatesParams::light(state)?),
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    /// The device expects the nickname to be base64 encoded.
+    pub fn nickname(nickname: &str) -> Result<Self, Error> {
+        if nickname.trim().is_empty() {
+            return Err(Error::Validation {
+                field: "nickname".to_string(),
+                message: "Must not be empty".to_string(),
+            });
+        }
+
+        Self {
+            nickname: Some(general_purpose::STANDARD.encode(nickname)),
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    pub fn avatar(avatar: &str) -> Result<Self, Error> {
+        if avatar.is_empty() {
+            return Err(Error::Validation {
+                field: "avatar".to_string(),
+                message: "Must not be empty".to_string(),
+            });
+        }
+
+        Self {
+            avatar: Some(avatar.to_string()),
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    /// The device stores the coordinates in ten-thousandths of a degree.
+    pub fn location(latitude: f64, longitude: f64) -> Result<Self, Error> {
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(Error::Validation {
+                field: "latitude".to_string(),
+                message: "Must be between -90 and 90".to_string(),
+            });
+        }
+
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(Error::Validation {
+                field: "longitude".to_string(),
+                message: "Must be between -180 and 180".to_string(),
+            });
+        }
+
+        Self {
+            latitude: Some((latitude * 10_000.0).round() as i64),
+            longitude: Some((longitude * 10_000.0).round() as i64),
+            ..Default::default()
         }
         .validate()
     }
 
     pub fn validate(self) -> Result<Self, Error> {
-        if self.device_on.is_none() {
+        if self.device_on.is_none()
+            && self.default_states.is_none()
+            && self.nickname.is_none()
+            && self.avatar.is_none()
+            && self.latitude.is_none()
+            && self.longitude.is_none()
+        {
             return Err(Error::Validation {
                 field: "DeviceInfoParams".to_string(),
                 message: "Requires at least one property".to_string(),
@@ -27,3 +117,31 @@ impl GenericSetDeviceInfoParams {
         Ok(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_nickname() {
+        assert_eq!(
+            serde_json::to_value(GenericSetDeviceInfoParams::nickname("Grow Light").unwrap())
+                .unwrap(),
+            json!({"nickname": "R3JvdyBMaWdodA=="})
+        );
+        assert!(GenericSetDeviceInfoParams::nickname(" ").is_err());
+    }
+
+    #[test]
+    fn test_location() {
+        assert_eq!(
+            serde_json::to_value(GenericSetDeviceInfoParams::location(51.5074, -0.1278).unwrap())
+                .unwrap(),
+            json!({"latitude": 515074, "longitude": -1278})
+        );
+        assert!(GenericSetDeviceInfoParams::location(91.0, 0.0).is_err());
+        assert!(GenericSetDeviceInfoParams::location(0.0, -181.0).is_err());
+    }
+}
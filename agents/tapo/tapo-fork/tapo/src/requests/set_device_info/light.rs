@@ -10,26 +10,12 @@ pub(crate) struct LightSetDeviceInfoParams<'a> {
     #[serde(skip)]
     client: RwLockReadGuard<'a, dyn ApiClientExt>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    device_on: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     brightness: Option<u8>,
 }
 
 impl LightSetDeviceInfoParams<'_> {
-    /// Turns *on* the device. [`LightSetDeviceInfoParams::send`] must be called at the end to apply the changes.
-    pub fn on(mut self) -> Self {
-        self.device_on = Some(true);
-        self
-    }
-
-    /// Turns *off* the device. [`LightSetDeviceInfoParams::send`] must be called at the end to apply the changes.
-    pub fn off(mut self) -> Self {
-        self.device_on = Some(false);
-        self
-    }
-
     /// Sets the *brightness*. [`LightSetDeviceInfoParams::send`] must be called at the end to apply the changes.
-    /// The device will also be turned *on*, unless [`LightSetDeviceInfoParams::off`] is called.
+    /// The device will also be turned *on*.
     ///
     /// # Arguments
     ///
@@ -51,13 +37,12 @@ impl<'a> LightSetDeviceInfoParams<'a> {
     pub(crate) fn new(client: RwLockReadGuard<'a, dyn ApiClientExt>) -> Self {
         Self {
             client,
-            device_on: None,
             brightness: None,
         }
     }
 
     fn validate(&self) -> Result<(), Error> {
-        if self.device_on.is_none() && self.brightness.is_none() {
+        if self.brightness.is_none() {
             return Err(Error::Validation {
                 field: "DeviceInfoParams".to_string(),
                 message: "Requires at least one property".to_string(),
@@ -83,6 +68,13 @@ mod tests {
     use tokio::sync::RwLock;
 
     use crate::HandlerExt;
+    use crate::requests::{EnergyDataInterval, PowerDataInterval};
+    use crate::responses::{
+        AutoOffConfigResult, ComponentListResult, CountdownRulesResult, CurrentPowerResult,
+        DeviceTimeResult, EmeterDataResult, EnergyDataResult, EnergyUsageResult,
+        FirmwareDownloadStateResult, LatestFirmwareResult, LedInfoResult, NextEventResult,
+        PowerDataResult, ScheduleRulesResult,
+    };
 
     use super::*;
 
@@ -100,6 +92,69 @@ mod tests {
         async fn device_reset(&self) -> Result<(), Error> {
             unimplemented!()
         }
+        async fn get_led_info(&self) -> Result<LedInfoResult, Error> {
+            unimplemented!()
+        }
+        async fn set_led_info(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_auto_off_config(&self) -> Result<AutoOffConfigResult, Error> {
+            unimplemented!()
+        }
+        async fn set_auto_off_config(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_device_time(&self) -> Result<DeviceTimeResult, Error> {
+            unimplemented!()
+        }
+        async fn set_device_time(&self, _: serde_json::Value) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_latest_firmware(&self) -> Result<LatestFirmwareResult, Error> {
+            unimplemented!()
+        }
+        async fn firmware_download(&self) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_firmware_download_state(&self) -> Result<FirmwareDownloadStateResult, Error> {
+            unimplemented!()
+        }
+        async fn get_component_list(&self) -> Result<ComponentListResult, Error> {
+            unimplemented!()
+        }
+        async fn get_current_power(&self) -> Result<CurrentPowerResult, Error> {
+            unimplemented!()
+        }
+        async fn get_energy_usage(&self) -> Result<EnergyUsageResult, Error> {
+            unimplemented!()
+        }
+        async fn get_energy_data(&self, _: EnergyDataInterval) -> Result<EnergyDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_power_data(&self, _: PowerDataInterval) -> Result<PowerDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_emeter_data(&self) -> Result<EmeterDataResult, Error> {
+            unimplemented!()
+        }
+        async fn get_countdown_rules(&self) -> Result<CountdownRulesResult, Error> {
+            unimplemented!()
+        }
+        async fn add_countdown_rule(&self, _: u64, _: bool) -> Result<(), Error> {
+            unimplemented!()
+        }
+        async fn get_schedule_rules(&self) -> Result<ScheduleRulesResult, Error> {
+            unimplemented!()
+        }
+        async fn get_next_event(&self) -> Result<NextEventResult, Error> {
+            unimplemented!()
+        }
+        async fn get_child_device_list_json(&self, _: u64) -> Result<serde_json::Value, Error> {
+            unimplemented!()
+        }
+        async fn get_child_device_component_list_json(&self) -> Result<serde_json::Value, Error> {
+            unimplemented!()
+        }
     }
 
     struct MockHandler {
@@ -0,0 +1,144 @@
+use serde::Serialize;
+
+use crate::error::Error;
+
+use crate::responses::TemperatureUnit;
+
+#[derive(Debug, Default, Serialize)]
+pub(crate) struct T31XSetDeviceInfoParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report_interval: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none", rename = "temp_unit")]
+    temperature_unit: Option<TemperatureUnit>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comfort_temperature_min: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comfort_temperature_max: Option<i16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comfort_humidity_min: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    comfort_humidity_max: Option<u8>,
+}
+
+impl T31XSetDeviceInfoParams {
+    pub fn report_interval(mut self, value: u32) -> Result<Self, Error> {
+        self.report_interval = Some(value);
+        self.validate()
+    }
+    pub fn temperature_unit(mut self, value: TemperatureUnit) -> Result<Self, Error> {
+        self.temperature_unit = Some(value);
+        self.validate()
+    }
+    pub fn comfort_temperature(
+        mut self,
+        min: i16,
+        max: i16,
+        unit: TemperatureUnit,
+    ) -> Result<Self, Error> {
+        self.comfort_temperature_min = Some(min);
+        self.comfort_temperature_max = Some(max);
+        self.temperature_unit = Some(unit);
+        self.validate()
+    }
+    pub fn comfort_humidity(mut self, min: u8, max: u8) -> Result<Self, Error> {
+        self.comfort_humidity_min = Some(min);
+        self.comfort_humidity_max = Some(max);
+        self.validate()
+    }
+}
+
+impl T31XSetDeviceInfoParams {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn validate(self) -> Result<Self, Error> {
+        if let Some(report_interval) = self.report_interval
+            && report_interval == 0
+        {
+            return Err(Error::Validation {
+                field: "report_interval".to_string(),
+                message: "Must be at least 1".to_string(),
+            });
+        }
+
+        if let (Some(min), Some(max)) = (self.comfort_temperature_min, self.comfort_temperature_max)
+            && min >= max
+        {
+            return Err(Error::Validation {
+                field: "comfort_temperature".to_string(),
+                message: "The minimum must be lower than the maximum".to_string(),
+            });
+        }
+
+        if let (Some(min), Some(max)) = (self.comfort_humidity_min, self.comfort_humidity_max) {
+            if max > 100 {
+                return Err(Error::Validation {
+                    field: "comfort_humidity".to_string(),
+                    message: "Must be between 0 and 100".to_string(),
+                });
+            }
+            if min >= max {
+                return Err(Error::Validation {
+                    field: "comfort_humidity".to_string(),
+                    message: "The minimum must be lower than the maximum".to_string(),
+                });
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_comfort_zone() {
+        let params = T31XSetDeviceInfoParams::new()
+            .comfort_temperature(22, 28, TemperatureUnit::Celsius)
+            .unwrap()
+            .comfort_humidity(50, 65)
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(params).unwrap(),
+            json!({
+                "temp_unit": "celsius",
+                "comfort_temperature_min": 22,
+                "comfort_temperature_max": 28,
+                "comfort_humidity_min": 50,
+                "comfort_humidity_max": 65,
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(T31XSetDeviceInfoParams::new().report_interval(0).is_err());
+        assert!(T31XSetDeviceInfoParams::new().report_interval(4).is_ok());
+        assert!(
+            T31XSetDeviceInfoParams::new()
+                .comfort_temperature(28, 22, TemperatureUnit::Celsius)
+                .is_err()
+        );
+        assert!(
+            T31XSetDeviceInfoParams::new()
+                .comfort_temperature(-5, 5, TemperatureUnit::Celsius)
+                .is_ok()
+        );
+        assert!(
+            T31XSetDeviceInfoParams::new()
+                .comfort_humidity(65, 50)
+                .is_err()
+        );
+        assert!(
+            T31XSetDeviceInfoParams::new()
+                .comfort_humidity(50, 101)
+                .is_err()
+        );
+    }
+}
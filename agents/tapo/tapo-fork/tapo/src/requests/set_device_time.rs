@@ -0,0 +1,70 @@
+use serde::Serialize;
+
+use crate::error::Error;
+
+const MAX_TIME_DIFF_MINUTES: i32 = 14 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SetDeviceTimeParams {
+    timestamp: i64,
+    time_diff: i32,
+    region: String,
+}
+
+impl SetDeviceTimeParams {
+    pub fn new(timestamp: i64, time_diff: i32, region: impl Into<String>) -> Result<Self, Error> {
+        Self {
+            timestamp,
+            time_diff,
+            region: region.into(),
+        }
+        .validate()
+    }
+
+    fn validate(self) -> Result<Self, Error> {
+        if self.timestamp < 0 {
+            return Err(Error::Validation {
+                field: "timestamp".to_string(),
+                message: "Must not be negative".to_string(),
+            });
+        }
+
+        if !(-MAX_TIME_DIFF_MINUTES..=MAX_TIME_DIFF_MINUTES).contains(&self.time_diff) {
+            return Err(Error::Validation {
+                field: "time_diff".to_string(),
+                message: format!(
+                    "Must be between -{MAX_TIME_DIFF_MINUTES} and {MAX_TIME_DIFF_MINUTES} minutes"
+                ),
+            });
+        }
+
+        if self.region.is_empty() {
+            return Err(Error::Validation {
+                field: "region".to_string(),
+                message: "Must not be empty".to_string(),
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_set_device_time() {
+        assert_eq!(
+            serde_json::to_value(
+                SetDeviceTimeParams::new(1_700_000_000, 60, "Europe/London").unwrap()
+            )
+            .unwrap(),
+            json!({"timestamp": 1_700_000_000, "time_diff": 60, "region": "Europe/London"})
+        );
+        assert!(SetDeviceTimeParams::new(1_700_000_000, 15 * 60, "Europe/London").is_err());
+        assert!(SetDeviceTimeParams::new(1_700_000_000, 60, "").is_err());
+    }
+}
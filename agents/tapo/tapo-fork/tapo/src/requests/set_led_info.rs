@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::responses::{LedInfoResult, LedNightMode, LedNightModeType, LedRule};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SetLedInfoParams {
+    led_rule: LedRule,
+    led_status: bool,
+    night_mode: LedNightMode,
+}
+
+impl SetLedInfoParams {
+    /// Keeps the current settings of `led_info` that are not overridden.
+    pub fn new(
+        led_info: LedInfoResult,
+        led_rule: LedRule,
+        night_mode: Option<LedNightMode>,
+    ) -> Result<Self, Error> {
+        Self {
+            led_rule,
+            led_status: led_info.led_status,
+            night_mode: night_mode.unwrap_or(led_info.night_mode),
+        }
+        .validate()
+    }
+
+    fn validate(self) -> Result<Self, Error> {
+        if self.night_mode.night_mode_type == LedNightModeType::Custom
+            && (self.night_mode.start_time >= MINUTES_PER_DAY
+                || self.night_mode.end_time >= MINUTES_PER_DAY)
+        {
+            return Err(Error::Validation {
+                field: "night_mode".to_string(),
+                message: "The start and end time must be lower than 1440 minutes".to_string(),
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn led_info() -> LedInfoResult {
+        LedInfoResult {
+            led_rule: LedRule::Always,
+            led_status: true,
+            night_mode: LedNightMode::sunrise_sunset(0, 0),
+        }
+    }
+
+    #[test]
+    fn test_keeps_night_mode() {
+        let params = SetLedInfoParams::new(led_info(), LedRule::Never, None).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(params).unwrap(),
+            json!({
+                "led_rule": "never",
+                "led_status": true,
+                "night_mode": {
+                    "night_mode_type": "sunrise_sunset",
+                    "start_time": 0,
+                    "end_time": 0,
+                    "sunrise_offset": 0,
+                    "sunset_offset": 0,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_night_mode() {
+        let night_mode = LedNightMode::custom(22 * 60, 6 * 60);
+        let params =
+            SetLedInfoParams::new(led_info(), LedRule::NightMode, Some(night_mode.clone()))
+                .unwrap();
+        assert_eq!(params.night_mode, night_mode);
+
+        let night_mode = LedNightMode::custom(24 * 60, 6 * 60);
+        assert!(SetLedInfoParams::new(led_info(), LedRule::NightMode, Some(night_mode)).is_err());
+    }
+}
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SetProtectionPowerParams {
+    enabled: bool,
+    protection_power: u16,
+}
+
+impl SetProtectionPowerParams {
+    pub fn new(enabled: bool, protection_power: u16) -> Result<Self, Error> {
+        Self {
+            enabled,
+            protection_power,
+        }
+        .validate()
+    }
+
+    fn validate(self) -> Result<Self, Error> {
+        if self.enabled && self.protection_power == 0 {
+            return Err(Error::Validation {
+                field: "protection_power".to_string(),
+                message: "Must be greater than 0 when power protection is enabled".to_string(),
+            });
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_set_protection_power() {
+        assert_eq!(
+            serde_json::to_value(SetProtectionPowerParams::new(true, 600).unwrap()).unwrap(),
+            json!({"enabled": true, "protection_power": 600})
+        );
+        assert!(SetProtectionPowerParams::new(false, 0).is_ok());
+        assert!(SetProtectionPowerParams::new(true, 0).is_err());
+    }
+}
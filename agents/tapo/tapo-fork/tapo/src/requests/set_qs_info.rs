@@ -0,0 +1,162 @@
+use std::fmt;
+
+use base64::{Engine as _, engine::general_purpose};
+use serde::Serialize;
+
+use crate::error::Error;
+
+/// Parameters for `get_wireless_scan_info`
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct GetWirelessScanInfoParams {
+    pub start_index: usize,
+}
+
+/// Parameters for `set_qs_info`, which completes the quick setup of a device in setup mode
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct SetQsInfoParams {
+    account: QsAccount,
+    time: QsTime,
+    wireless: QsWireless,
+}
+
+#[derive(Clone, Serialize)]
+struct QsAccount {
+    username: String,
+    password: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct QsTime {
+    pub timestamp: i64,
+    pub time_diff: i32,
+    pub region: String,
+}
+
+#[derive(Clone, Serialize)]
+struct QsWireless {
+    ssid: String,
+    password: String,
+    key_type: String,
+}
+
+impl fmt::Debug for QsAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            r#"QsAccount {{ username: "{}", password: "OBSCURED" }}"#,
+            self.username,
+        )
+    }
+}
+
+impl fmt::Debug for QsWireless {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            r#"QsWireless {{ ssid: "{}", password: "OBSCURED", key_type: "{}" }}"#,
+            self.ssid, self.key_type,
+        )
+    }
+}
+
+impl SetQsInfoParams {
+    pub fn new(
+        tapo_username: &str,
+        tapo_password: &str,
+        ssid: &str,
+        wifi_password: &str,
+        key_type: &str,
+        time: QsTime,
+    ) -> Result<Self, Error> {
+        if ssid.is_empty() {
+            return Err(Error::Validation {
+                field: "ssid".to_string(),
+                message: "Must not be empty".to_string(),
+            });
+        }
+
+        if key_type != "none" && wifi_password.is_empty() {
+            return Err(Error::Validation {
+                field: "wifi_password".to_string(),
+                message: "Must not be empty for a secured network".to_string(),
+            });
+        }
+
+        let encode = |value: &str| general_purpose::STANDARD.encode(value);
+
+        Ok(Self {
+            account: QsAccount {
+                username: encode(tapo_username),
+                password: encode(tapo_password),
+            },
+            time,
+            wireless: QsWireless {
+                ssid: encode(ssid),
+                password: encode(wifi_password),
+                key_type: key_type.to_string(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn time() -> QsTime {
+        QsTime {
+            timestamp: 1_700_000_000,
+            time_diff: 60,
+            region: "Europe/London".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_set_qs_info() {
+        let params = SetQsInfoParams::new(
+            "user@example.com",
+            "secret",
+            "Grow",
+            "pass",
+            "wpa2_psk",
+            time(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(params).unwrap(),
+            json!({
+                "account": {"username": "dXNlckBleGFtcGxlLmNvbQ==", "password": "c2VjcmV0"},
+                "time": {"timestamp": 1_700_000_000, "time_diff": 60, "region": "Europe/London"},
+                "wireless": {"ssid": "R3Jvdw==", "password": "cGFzcw==", "key_type": "wpa2_psk"},
+            })
+        );
+    }
+
+    #[test]
+    fn test_debug_obscures_passwords() {
+        let params = SetQsInfoParams::new(
+            "user@example.com",
+            "secret",
+            "Grow",
+            "pass",
+            "wpa2_psk",
+            time(),
+        )
+        .unwrap();
+        let debug = format!("{params:?}");
+
+        assert!(!debug.contains("c2VjcmV0"));
+        assert!(!debug.contains("cGFzcw=="));
+        assert!(debug.contains("R3Jvdw=="));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(SetQsInfoParams::new("user", "secret", "", "pass", "wpa2_psk", time()).is_err());
+        assert!(SetQsInfoParams::new("user", "secret", "Grow", "", "wpa2_psk", time()).is_err());
+        assert!(SetQsInfoParams::new("user", "secret", "Grow", "", "none", time()).is_ok());
+    }
+}
@@ -2,14 +2,18 @@ use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::Serialize;
 
+use crate::error::Error;
+
 use super::{
-    AddCountdownRuleParams, ControlChildParams, DeviceRebootParams, EditCountdownRuleParams,
-    GetChildDeviceListParams, GetEnergyDataParams, GetPowerDataParams, GetRulesParams,
-    GetTriggerLogsParams, HandshakeParams, LightingEffect, LoginDeviceParams,
-    MultipleRequestParams, PlayAlarmParams, SecurePassthroughParams,
+    AddCountdownRuleParams, AntitheftRuleParams, ChildDeviceListParams, ControlChildParams,
+    DeviceRebootParams, EditAntitheftRuleParams, EditCountdownRuleParams, GetChildDeviceListParams,
+    GetEnergyDataParams, GetPowerDataParams, GetRulesParams, GetTriggerLogsParams,
+    GetWirelessScanInfoParams, HandshakeParams, LightingEffect, LoginDeviceParams,
+    MultipleRequestParams, PlayAlarmParams, RemoveRulesParams, SecurePassthroughParams,
+    SetProtectionPowerParams, SetQsInfoParams,
 };
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "snake_case")]
 #[serde(tag = "method")]
 pub(crate) enum TapoRequest {
@@ -27,11 +31,33 @@ pub(crate) enum TapoRequest {
     GetDeviceUsage(TapoParams<EmptyParams>),
     GetEnergyUsage(TapoParams<EmptyParams>),
     GetCurrentPower(TapoParams<EmptyParams>),
+    GetEmeterData(TapoParams<EmptyParams>),
     GetEnergyData(TapoParams<GetEnergyDataParams>),
     GetPowerData(TapoParams<GetPowerDataParams>),
+    GetProtectionPower(TapoParams<EmptyParams>),
+    SetProtectionPower(TapoParams<SetProtectionPowerParams>),
+    GetMaxPower(TapoParams<EmptyParams>),
+    GetLedInfo(TapoParams<EmptyParams>),
+    SetLedInfo(Box<TapoParams<serde_json::Value>>),
+    GetAutoOffConfig(TapoParams<EmptyParams>),
+    SetAutoOffConfig(Box<TapoParams<serde_json::Value>>),
+    GetDeviceTime(TapoParams<EmptyParams>),
+    SetDeviceTime(Box<TapoParams<serde_json::Value>>),
+    GetLatestFirmware(TapoParams<EmptyParams>),
+    #[serde(rename = "fw_download")]
+    FirmwareDownload(TapoParams<EmptyParams>),
+    #[serde(rename = "get_fw_download_state")]
+    GetFirmwareDownloadState(TapoParams<EmptyParams>),
+    GetWirelessScanInfo(TapoParams<GetWirelessScanInfoParams>),
+    SetQsInfo(Box<TapoParams<SetQsInfoParams>>),
     GetChildDeviceList(TapoParams<GetChildDeviceListParams>),
     GetChildDeviceComponentList(TapoParams<EmptyParams>),
     ControlChild(Box<TapoParams<ControlChildParams>>),
+    BeginScanningChildDevice(TapoParams<EmptyParams>),
+    GetScanChildDeviceList(TapoParams<EmptyParams>),
+    EndScanningChildDevice(TapoParams<EmptyParams>),
+    AddChildDeviceList(Box<TapoParams<ChildDeviceListParams>>),
+    RemoveChildDeviceList(Box<TapoParams<ChildDeviceListParams>>),
     // Child requests
     #[serde(rename = "multipleRequest")]
     MultipleRequest(Box<TapoParams<MultipleRequestParams>>),
@@ -52,17 +78,89 @@ pub(crate) enum TapoRequest {
     #[serde(rename = "edit_countdown_rule")]
     EditCountdownRule(TapoParams<EditCountdownRuleParams>),
     #[serde(rename = "get_next_event")]
-    #[allow(dead_code)]
     GetNextEvent(TapoParams<EmptyParams>),
     #[serde(rename = "get_antitheft_rules")]
-    #[allow(dead_code)]
     GetAntitheftRules(TapoParams<GetRulesParams>),
+    #[serde(rename = "add_antitheft_rule")]
+    AddAntitheftRule(TapoParams<AntitheftRuleParams>),
+    #[serde(rename = "edit_antitheft_rule")]
+    EditAntitheftRule(TapoParams<EditAntitheftRuleParams>),
+    #[serde(rename = "remove_antitheft_rules")]
+    RemoveAntitheftRules(TapoParams<RemoveRulesParams>),
+}
+
+impl TapoRequest {
+    /// Whether sending the request more than once has the same effect as sending it once,
+    /// making it safe to retry.
+    ///
+    /// Requests are only retried when they are known to be safe, so new requests
+    /// are never retried until they are added here.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            Self::ComponentNegotiation(_)
+            | Self::GetDeviceInfo(_)
+            | Self::GetDeviceUsage(_)
+            | Self::GetEnergyUsage(_)
+            | Self::GetCurrentPower(_)
+            | Self::GetEmeterData(_)
+            | Self::GetEnergyData(_)
+            | Self::GetPowerData(_)
+            | Self::GetProtectionPower(_)
+            | Self::GetMaxPower(_)
+            | Self::GetLedInfo(_)
+            | Self::GetAutoOffConfig(_)
+            | Self::GetDeviceTime(_)
+            | Self::GetLatestFirmware(_)
+            | Self::GetFirmwareDownloadState(_)
+            | Self::GetWirelessScanInfo(_)
+            | Self::GetChildDeviceList(_)
+            | Self::GetChildDeviceComponentList(_)
+            | Self::GetScanChildDeviceList(_)
+            | Self::GetTriggerLogs(_)
+            | Self::GetTemperatureHumidityRecords(_)
+            | Self::GetSupportedAlarmTypeList(_)
+            | Self::GetCountdownRules(_)
+            | Self::GetScheduleRules(_)
+            | Self::GetNextEvent(_)
+            | Self::GetAntitheftRules(_) => true,
+            // Writes that set an absolute state
+            Self::SetDeviceInfo(_)
+            | Self::SetLedInfo(_)
+            | Self::SetAutoOffConfig(_)
+            | Self::SetProtectionPower(_)
+            | Self::EditAntitheftRule(_)
+            | Self::StopAlarm(_)
+            | Self::EndScanningChildDevice(_) => true,
+            Self::ControlChild(params) => params.params.is_idempotent(),
+            Self::MultipleRequest(params) => params.params.is_idempotent(),
+            _ => false,
+        }
+    }
+
+    /// The `method` the request is sent as, which the device repeats in the responses of a `multipleRequest`.
+    pub fn method(&self) -> Result<String, Error> {
+        match serde_json::to_value(self)?.get("method") {
+            Some(serde_json::Value::String(method)) => Ok(method.clone()),
+            _ => Err(Error::Other(anyhow::anyhow!("Request has no method"))),
+        }
+    }
+
+    /// Whether the request contains credentials (the Tapo account or Wi-Fi password),
+    /// which must not be logged or reported to [`crate::Transport::on_exchange`].
+    pub fn is_sensitive(&self) -> bool {
+        match self {
+            Self::LoginDevice(_) | Self::SetQsInfo(_) => true,
+            Self::ControlChild(params) => params.params.is_sensitive(),
+            Self::MultipleRequest(params) => params.params.is_sensitive(),
+            _ => false,
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub(crate) struct EmptyParams;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct TapoParams<T> {
     params: T,
@@ -92,3 +190,48 @@ impl<T> TapoParams<T> {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty() -> TapoParams<EmptyParams> {
+        TapoParams::new(EmptyParams)
+    }
+
+    fn value() -> Box<TapoParams<serde_json::Value>> {
+        Box::new(TapoParams::new(serde_json::json!({})))
+    }
+
+    #[test]
+    fn test_is_idempotent() {
+        assert!(TapoRequest::GetDeviceInfo(empty()).is_idempotent());
+        assert!(TapoRequest::SetDeviceInfo(value()).is_idempotent());
+        assert!(TapoRequest::StopAlarm(empty()).is_idempotent());
+
+        assert!(!TapoRequest::SetDeviceTime(value()).is_idempotent());
+        assert!(!TapoRequest::BeginScanningChildDevice(empty()).is_idempotent());
+        assert!(!TapoRequest::DeviceReset(empty()).is_idempotent());
+        assert!(!TapoRequest::FirmwareDownload(empty()).is_idempotent());
+    }
+
+    #[test]
+    fn test_is_idempotent_follows_wrapped_requests() {
+        let child = |request| {
+            TapoRequest::ControlChild(Box::new(TapoParams::new(ControlChildParams::new(
+                "child".to_string(),
+                request,
+            ))))
+        };
+        assert!(child(TapoRequest::GetDeviceInfo(empty())).is_idempotent());
+        assert!(!child(TapoRequest::SetDeviceTime(value())).is_idempotent());
+
+        let multiple = TapoRequest::MultipleRequest(Box::new(TapoParams::new(
+            MultipleRequestParams::new(vec![
+                TapoRequest::GetDeviceInfo(empty()),
+                TapoRequest::BeginScanningChildDevice(empty()),
+            ]),
+        )));
+        assert!(!multiple.is_idempotent());
+    }
+}
@@ -1,38 +1,59 @@
 //! Tapo response objects.
 
+mod auto_off_config_result;
+mod batch_read_result;
 mod child_device_list_hub_result;
 mod child_device_list_power_strip_result;
+mod child_device_scan_result;
+mod component_list_result;
 mod control_child_result;
 mod current_power_result;
 mod decodable_result_ext;
 mod device_info_result;
+mod device_time_result;
 mod device_usage_energy_monitoring_result;
 mod device_usage_result;
+mod emeter_data_result;
 mod energy_data_result;
 mod energy_usage_result;
+mod firmware_result;
 mod handshake_result;
+mod led_info_result;
 mod power_data_result;
+mod power_protection_result;
 mod schedule_rules_result;
 mod supported_alarm_type_list_result;
 mod tapo_response;
 mod tapo_result;
 mod token_result;
 mod trigger_logs_result;
+mod wireless_scan_info_result;
 
 pub use crate::requests::{LightingEffect, LightingEffectType};
 
+pub use auto_off_config_result::*;
+pub use batch_read_result::*;
 pub use child_device_list_hub_result::*;
 pub use child_device_list_power_strip_result::*;
+pub use child_device_scan_result::*;
+pub use component_list_result::ComponentListResult;
 pub use current_power_result::*;
 pub use device_info_result::*;
+pub use device_time_result::*;
 pub use device_usage_energy_monitoring_result::*;
 pub use device_usage_result::*;
+pub use emeter_data_result::*;
 pub use energy_data_result::*;
 pub use energy_usage_result::*;
+pub use firmware_result::*;
+pub use led_info_result::*;
 pub use power_data_result::*;
+pub use power_protection_result::*;
 pub use schedule_rules_result::*;
 pub use trigger_logs_result::*;
+pub use wireless_scan_info_result::WirelessNetwork;
 
+pub(crate) use component_list_result::ChildComponentListResult;
 pub(crate) use control_child_result::*;
 pub(crate) use decodable_result_ext::*;
 pub(crate) use handshake_result::*;
@@ -40,3 +61,4 @@ pub(crate) use supported_alarm_type_list_result::*;
 pub(crate) use tapo_response::*;
 pub(crate) use tapo_result::*;
 pub(crate) use token_result::*;
+pub(crate) use wireless_scan_info_result::WirelessScanInfoResult;
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the auto-off settings of the device.
+/// When enabled, the device turns itself *off* after being *on* for the configured delay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct AutoOffConfigResult {
+    /// Whether auto-off is enabled.
+    pub enable: bool,
+    /// Delay in minutes after which the device turns itself *off*.
+    pub delay_min: u64,
+}
+impl TapoResponseExt for AutoOffConfigResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl AutoOffConfigResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
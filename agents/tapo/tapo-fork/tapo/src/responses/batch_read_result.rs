@@ -0,0 +1,44 @@
+use crate::error::Error;
+use crate::responses::{
+    CountdownRulesResult, CurrentPowerResult, EmeterDataResult, EnergyUsageResult, NextEventResult,
+    ScheduleRulesResult,
+};
+
+/// Result of the `get_batch` API of the plug handlers.
+///
+/// Every property holds `None` if it was not selected in [`crate::requests::BatchReadParams`],
+/// and otherwise the result of reading it, independent of the other properties.
+#[derive(Debug)]
+pub struct BatchReadResult<I, U> {
+    /// *Device info* of the device.
+    pub device_info: Option<Result<I, Error>>,
+    /// *Device usage* of the device.
+    pub device_usage: Option<Result<U, Error>>,
+    /// *Current power* of the device.
+    pub current_power: Option<Result<CurrentPowerResult, Error>>,
+    /// *Energy usage* of the device.
+    pub energy_usage: Option<Result<EnergyUsageResult, Error>>,
+    /// *Emeter data* of the device.
+    pub emeter_data: Option<Result<EmeterDataResult, Error>>,
+    /// *Countdown rules* of the device.
+    pub countdown_rules: Option<Result<CountdownRulesResult, Error>>,
+    /// *Schedule rules* of the device.
+    pub schedule_rules: Option<Result<ScheduleRulesResult, Error>>,
+    /// *Next event* of the device.
+    pub next_event: Option<Result<NextEventResult, Error>>,
+}
+
+impl<I, U> Default for BatchReadResult<I, U> {
+    fn default() -> Self {
+        Self {
+            device_info: None,
+            device_usage: None,
+            current_power: None,
+            energy_usage: None,
+            emeter_data: None,
+            countdown_rules: None,
+            schedule_rules: None,
+            next_event: None,
+        }
+    }
+}
@@ -23,6 +23,12 @@ pub(crate) struct ChildDeviceListHubResult {
     /// Hub child devices
     #[serde(rename = "child_device_list")]
     pub devices: Vec<ChildDeviceHubResult>,
+    /// The index of the first device of this page.
+    #[serde(default)]
+    pub start_index: u64,
+    /// The total number of child devices, if reported by the hub.
+    #[serde(default)]
+    pub sum: Option<u64>,
 }
 
 impl DecodableResultExt for ChildDeviceListHubResult {
@@ -33,12 +39,53 @@ impl DecodableResultExt for ChildDeviceListHubResult {
                 .into_iter()
                 .map(|d| d.decode())
                 .collect::<Result<Vec<_>, _>>()?,
+            ..self
         })
     }
 }
 
 impl TapoResponseExt for ChildDeviceListHubResult {}
 
+// Hubs that do not report the total return pages of this size.
+const DEFAULT_PAGE_SIZE: u64 = 10;
+
+/// A page of the hub child device list, returned by [`crate::HubHandler::get_child_device_list_page`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildDeviceHubPage {
+    /// The child devices of this page.
+    pub devices: Vec<ChildDeviceHubResult>,
+    /// The index of the first device of this page.
+    pub start_index: u64,
+    /// The total number of child devices, if reported by the hub.
+    pub total: Option<u64>,
+}
+
+impl ChildDeviceHubPage {
+    /// Returns the index to fetch the next page from, or `None` if this is the last page.
+    pub fn next_start_index(&self) -> Option<u64> {
+        let count = self.devices.len() as u64;
+        if count == 0 {
+            return None;
+        }
+
+        let next = self.start_index + count;
+        match self.total {
+            Some(total) => (next < total).then_some(next),
+            None => (count == DEFAULT_PAGE_SIZE).then_some(next),
+        }
+    }
+}
+
+impl From<ChildDeviceListHubResult> for ChildDeviceHubPage {
+    fn from(result: ChildDeviceListHubResult) -> Self {
+        Self {
+            devices: result.devices,
+            start_index: result.start_index,
+            total: result.sum,
+        }
+    }
+}
+
 /// Device status.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -73,6 +120,38 @@ pub enum ChildDeviceHubResult {
     Other,
 }
 
+impl ChildDeviceHubResult {
+    /// Returns the device id, or `None` for [`ChildDeviceHubResult::Other`].
+    pub fn device_id(&self) -> Option<&str> {
+        match self {
+            ChildDeviceHubResult::KE100(device) => Some(&device.device_id),
+            ChildDeviceHubResult::S200B(device) => Some(&device.device_id),
+            ChildDeviceHubResult::T100(device) => Some(&device.device_id),
+            ChildDeviceHubResult::T110(device) => Some(&device.device_id),
+            ChildDeviceHubResult::T300(device) => Some(&device.device_id),
+            ChildDeviceHubResult::T310(device) | ChildDeviceHubResult::T315(device) => {
+                Some(&device.device_id)
+            }
+            ChildDeviceHubResult::Other => None,
+        }
+    }
+
+    /// Returns the nickname, or `None` for [`ChildDeviceHubResult::Other`].
+    pub fn nickname(&self) -> Option<&str> {
+        match self {
+            ChildDeviceHubResult::KE100(device) => Some(&device.nickname),
+            ChildDeviceHubResult::S200B(device) => Some(&device.nickname),
+            ChildDeviceHubResult::T100(device) => Some(&device.nickname),
+            ChildDeviceHubResult::T110(device) => Some(&device.nickname),
+            ChildDeviceHubResult::T300(device) => Some(&device.nickname),
+            ChildDeviceHubResult::T310(device) | ChildDeviceHubResult::T315(device) => {
+                Some(&device.nickname)
+            }
+            ChildDeviceHubResult::Other => None,
+        }
+    }
+}
+
 impl DecodableResultExt for ChildDeviceHubResult {
     fn decode(self) -> Result<Self, Error> {
         match self {
@@ -101,3 +180,31 @@ impl DecodableResultExt for ChildDeviceHubResult {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(count: usize, start_index: u64, total: Option<u64>) -> ChildDeviceHubPage {
+        ChildDeviceHubPage {
+            devices: vec![ChildDeviceHubResult::Other; count],
+            start_index,
+            total,
+        }
+    }
+
+    #[test]
+    fn test_next_start_index() {
+        assert_eq!(page(10, 0, Some(23)).next_start_index(), Some(10));
+        assert_eq!(page(10, 10, Some(23)).next_start_index(), Some(20));
+        assert_eq!(page(3, 20, Some(23)).next_start_index(), None);
+        assert_eq!(page(10, 0, Some(10)).next_start_index(), None);
+        assert_eq!(page(0, 0, Some(5)).next_start_index(), None);
+    }
+
+    #[test]
+    fn test_next_start_index_without_total() {
+        assert_eq!(page(10, 0, None).next_start_index(), Some(10));
+        assert_eq!(page(4, 10, None).next_start_index(), None);
+    }
+}
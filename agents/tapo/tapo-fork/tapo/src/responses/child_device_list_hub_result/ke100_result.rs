@@ -17,7 +17,7 @@ pub enum TemperatureUnitKE100 {
 ///
 /// Specific properties: `temperature_unit`, `current_temperature`, `target_temperature`,
 /// `min_control_temperature`, `max_control_temperature`, `temperature_offset`,
-/// `child_protection_on`, `frost_protection_on`, `location`.
+/// `child_protection_on`, `frost_protection_on`, `location`, `battery_percentage`, `trv_states`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
 #[allow(missing_docs)]
@@ -44,6 +44,9 @@ pub struct KE100Result {
     pub status: Status,
     pub r#type: String,
     // Specific properties to this device.
+    /// The remaining battery charge in percent, if reported by the firmware.
+    #[serde(default)]
+    pub battery_percentage: Option<u8>,
     #[serde(rename = "child_protection")]
     pub child_protection_on: bool,
     #[serde(rename = "current_temp")]
@@ -60,6 +63,9 @@ pub struct KE100Result {
     pub temperature_offset: i8,
     #[serde(rename = "temp_unit")]
     pub temperature_unit: TemperatureUnitKE100,
+    /// What the valve is currently doing, e.g. `heating`. Empty when the valve is idle.
+    #[serde(default)]
+    pub trv_states: Vec<String>,
 }
 
 #[cfg(feature = "python")]
@@ -19,7 +19,8 @@ pub enum TemperatureUnit {
 ///
 /// Specific properties: `current_temperature`, `temperature_unit`,
 /// `current_temperature_exception`, `current_humidity`, `current_humidity_exception`,
-/// `report_interval`, `last_onboarding_timestamp`, `status_follow_edge`.
+/// `report_interval`, `last_onboarding_timestamp`, `status_follow_edge`,
+/// `comfort_temperature_min`, `comfort_temperature_max`, `comfort_humidity_min`, `comfort_humidity_max`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
 #[allow(missing_docs)]
@@ -46,6 +47,18 @@ pub struct T31XResult {
     pub status: Status,
     pub r#type: String,
     // Specific properties to this device.
+    /// The upper bound of the humidity comfort zone, in percent.
+    #[serde(default)]
+    pub comfort_humidity_max: Option<u8>,
+    /// The lower bound of the humidity comfort zone, in percent.
+    #[serde(default)]
+    pub comfort_humidity_min: Option<u8>,
+    /// The upper bound of the temperature comfort zone, in `temperature_unit`.
+    #[serde(default)]
+    pub comfort_temperature_max: Option<i16>,
+    /// The lower bound of the temperature comfort zone, in `temperature_unit`.
+    #[serde(default)]
+    pub comfort_temperature_min: Option<i16>,
     /// This value will be `0` when the current humidity is within the comfort zone.
     /// When the current humidity value falls outside the comfort zone, this value
     /// will be the difference between the current humidity and the lower or upper bound of the comfort zone.
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::responses::{DecodableResultExt, TapoResponseExt, decode_value};
+
+/// Child devices found by a hub in pairing mode, returned by [`crate::HubHandler::get_pairing_candidates`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChildDeviceScanResult {
+    /// The devices found so far.
+    #[serde(rename = "child_device_list", default)]
+    pub devices: Vec<PairingCandidate>,
+    /// Whether the hub is still looking for devices, e.g. `scanning` or `idle`.
+    #[serde(default)]
+    pub scan_status: Option<String>,
+    /// The time left until the hub leaves pairing mode, in seconds.
+    #[serde(default)]
+    pub scan_wait_time: Option<u32>,
+}
+
+/// A child device found by a hub in pairing mode.
+/// Pass it to [`crate::HubHandler::pair`] to add it to the hub.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(missing_docs)]
+pub struct PairingCandidate {
+    pub device_id: String,
+    /// The kind of device, e.g. `subg.trigger.temp-hmdt-sensor`.
+    pub category: String,
+    #[serde(default)]
+    pub device_model: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl TapoResponseExt for ChildDeviceScanResult {}
+
+impl DecodableResultExt for ChildDeviceScanResult {
+    fn decode(mut self) -> Result<Self, Error> {
+        for device in &mut self.devices {
+            if let Some(name) = &device.name {
+                device.name = Some(decode_value(name)?);
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        let result: ChildDeviceScanResult = serde_json::from_value(json!({
+            "child_device_list": [{
+                "device_id": "SCRUBBED_CHILD_DEVICE_ID_1",
+                "category": "subg.trigger.temp-hmdt-sensor",
+                "device_model": "T310",
+                "name": "VGVudCBzZW5zb3I=",
+            }],
+            "scan_status": "scanning",
+            "scan_wait_time": 28,
+        }))
+        .unwrap();
+
+        let result = result.decode().unwrap();
+
+        assert_eq!(result.scan_status.as_deref(), Some("scanning"));
+        assert_eq!(result.scan_wait_time, Some(28));
+        assert_eq!(
+            result.devices,
+            [PairingCandidate {
+                device_id: "SCRUBBED_CHILD_DEVICE_ID_1".to_string(),
+                category: "subg.trigger.temp-hmdt-sensor".to_string(),
+                device_model: Some("T310".to_string()),
+                name: Some("Tent sensor".to_string()),
+            }]
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::responses::{DecodableResultExt, TapoResponseExt};
+
+/// The components supported by the device firmware, and their versions.
+///
+/// Component ids include `energy_monitoring`, `countdown`, `schedule`, `antitheft`, `led`,
+/// `auto_off`, `firmware`, `brightness` and `child_device`. Newer firmware may report ids
+/// and versions that are not listed here.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ComponentListResultRaw")]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct ComponentListResult {
+    /// The version of each supported component, keyed by component id.
+    pub components: BTreeMap<String, u32>,
+}
+impl TapoResponseExt for ComponentListResult {}
+
+impl ComponentListResult {
+    /// Returns whether the device supports the given component, e.g. `energy_monitoring`.
+    pub fn supports(&self, id: &str) -> bool {
+        self.components.contains_key(id)
+    }
+
+    /// Returns the version of the given component, if supported.
+    pub fn version(&self, id: &str) -> Option<u32> {
+        self.components.get(id).copied()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl ComponentListResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+/// The `component_nego` result as returned by the device.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ComponentListResultRaw {
+    #[serde(default)]
+    component_list: Vec<ComponentRaw>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ComponentRaw {
+    id: String,
+    ver_code: u32,
+}
+
+impl From<ComponentListResultRaw> for ComponentListResult {
+    fn from(raw: ComponentListResultRaw) -> Self {
+        Self {
+            components: raw
+                .component_list
+                .into_iter()
+                .map(|component| (component.id, component.ver_code))
+                .collect(),
+        }
+    }
+}
+
+/// The component lists of the child devices, as returned by `get_child_device_component_list`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChildComponentListResult {
+    #[serde(default)]
+    pub child_component_list: Vec<ChildComponentList>,
+}
+impl TapoResponseExt for ChildComponentListResult {}
+
+impl DecodableResultExt for ChildComponentListResult {
+    fn decode(self) -> Result<Self, Error> {
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ChildComponentList {
+    pub device_id: String,
+    #[serde(flatten)]
+    pub components: ComponentListResult,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let result: ComponentListResult = serde_json::from_value(json!({
+            "component_list": [
+                {"id": "device", "ver_code": 2},
+                {"id": "energy_monitoring", "ver_code": 2},
+                {"id": "countdown", "ver_code": 1},
+            ]
+        }))
+        .unwrap();
+
+        assert!(result.supports("energy_monitoring"));
+        assert!(!result.supports("brightness"));
+        assert_eq!(result.version("device"), Some(2));
+        assert_eq!(result.version("led"), None);
+    }
+
+    #[test]
+    fn test_deserialize_child() {
+        let result: ChildComponentListResult = serde_json::from_value(json!({
+            "child_component_list": [
+                {"device_id": "A", "component_list": [{"id": "frost_protection", "ver_code": 1}]},
+                {"device_id": "B", "component_list": [{"id": "countdown", "ver_code": 1}]},
+            ],
+            "start_index": 0,
+            "sum": 2,
+        }))
+        .unwrap();
+
+        assert_eq!(result.child_component_list[1].device_id, "B");
+        assert!(
+            result.child_component_list[1]
+                .components
+                .supports("countdown")
+        );
+    }
+}
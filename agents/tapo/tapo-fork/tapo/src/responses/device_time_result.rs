@@ -0,0 +1,61 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the clock and time zone of the device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct DeviceTimeResult {
+    /// UTC time of the device as a Unix timestamp in seconds.
+    pub timestamp: i64,
+    /// Offset of the device local time from UTC in minutes.
+    pub time_diff: i32,
+    /// Time zone region of the device, e.g. `Europe/London`.
+    pub region: String,
+}
+impl TapoResponseExt for DeviceTimeResult {}
+
+impl DeviceTimeResult {
+    /// Returns the UTC time of the device, or `None` if the timestamp is out of range.
+    pub fn utc_time(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.timestamp, 0)
+    }
+
+    /// Returns the local time of the device, or `None` if the timestamp or `time_diff` is out of range.
+    pub fn local_time(&self) -> Option<DateTime<FixedOffset>> {
+        let offset = FixedOffset::east_opt(self.time_diff * 60)?;
+        Some(self.utc_time()?.with_timezone(&offset))
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl DeviceTimeResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_time() {
+        let result = DeviceTimeResult {
+            timestamp: 1_700_000_000,
+            time_diff: 120,
+            region: "Europe/Bucharest".to_string(),
+        };
+
+        assert_eq!(
+            result.local_time().unwrap().to_rfc3339(),
+            "2023-11-15T00:13:20+02:00"
+        );
+    }
+}
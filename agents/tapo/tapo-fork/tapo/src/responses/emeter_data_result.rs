@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the real-time electrical readings of the device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct EmeterDataResult {
+    /// Voltage in millivolts (mV).
+    pub voltage_mv: u64,
+    /// Current in milliamperes (mA).
+    pub current_ma: u64,
+    /// Power in milliwatts (mW).
+    pub power_mw: u64,
+    /// Energy in Watt Hours (Wh). Not reported by all firmware versions.
+    pub energy_wh: Option<u64>,
+}
+impl TapoResponseExt for EmeterDataResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl EmeterDataResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize() {
+        let result: EmeterDataResult = serde_json::from_value(serde_json::json!({
+            "voltage_mv": 231480,
+            "current_ma": 2614,
+            "power_mw": 598230,
+        }))
+        .unwrap();
+
+        assert_eq!(result.voltage_mv, 231480);
+        assert_eq!(result.current_ma, 2614);
+        assert_eq!(result.power_mw, 598230);
+        assert_eq!(result.energy_wh, None);
+    }
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the latest firmware available for the device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct LatestFirmwareResult {
+    /// Whether a newer firmware than the installed one is available.
+    pub need_to_upgrade: bool,
+    /// Version of the latest firmware, empty if none is available.
+    #[serde(default)]
+    pub fw_ver: String,
+    /// Size of the latest firmware in bytes.
+    #[serde(default)]
+    pub fw_size: u64,
+    /// Release date of the latest firmware.
+    #[serde(default)]
+    pub release_date: String,
+    /// Release notes of the latest firmware.
+    #[serde(default)]
+    pub release_note: String,
+}
+impl TapoResponseExt for LatestFirmwareResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl LatestFirmwareResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+/// Contains the progress of a firmware update started with `firmware_download`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct FirmwareDownloadStateResult {
+    /// Raw status code reported by the device. See [`FirmwareDownloadStateResult::state`].
+    pub status: i32,
+    /// Download progress in percent.
+    #[serde(default)]
+    pub download_progress: u8,
+    /// Expected duration of the reboot after flashing, in seconds.
+    #[serde(default)]
+    pub reboot_time: u64,
+    /// Expected duration of the flashing, in seconds.
+    #[serde(default)]
+    pub upgrade_time: u64,
+    /// Whether the device installs updates automatically.
+    #[serde(default)]
+    pub auto_upgrade: bool,
+}
+impl TapoResponseExt for FirmwareDownloadStateResult {}
+
+impl FirmwareDownloadStateResult {
+    /// Returns the firmware update state decoded from `status`.
+    pub fn state(&self) -> FirmwareUpdateState {
+        match self.status {
+            0 => FirmwareUpdateState::Idle,
+            1 | 2 => FirmwareUpdateState::Downloading,
+            3 => FirmwareUpdateState::Flashing,
+            status if status < 0 => FirmwareUpdateState::Failed,
+            _ => FirmwareUpdateState::Unknown,
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl FirmwareDownloadStateResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+/// State of a firmware update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FirmwareUpdateState {
+    /// No update in progress, either none was started or the last one completed.
+    Idle,
+    /// The firmware is being downloaded.
+    Downloading,
+    /// The firmware is being written. The device reboots afterwards.
+    Flashing,
+    /// The update failed.
+    Failed,
+    /// A status code that is not known.
+    Unknown,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state() {
+        let state = |status| FirmwareDownloadStateResult {
+            status,
+            download_progress: 0,
+            reboot_time: 0,
+            upgrade_time: 0,
+            auto_upgrade: false,
+        };
+
+        assert_eq!(state(0).state(), FirmwareUpdateState::Idle);
+        assert_eq!(state(2).state(), FirmwareUpdateState::Downloading);
+        assert_eq!(state(3).state(), FirmwareUpdateState::Flashing);
+        assert_eq!(state(-1001).state(), FirmwareUpdateState::Failed);
+        assert_eq!(state(7).state(), FirmwareUpdateState::Unknown);
+    }
+}
@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the status LED settings of the device.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct LedInfoResult {
+    /// When the status LED is lit.
+    pub led_rule: LedRule,
+    /// Whether the status LED is currently lit.
+    pub led_status: bool,
+    /// The period during which the status LED is turned off when `led_rule` is [`LedRule::NightMode`].
+    pub night_mode: LedNightMode,
+}
+impl TapoResponseExt for LedInfoResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl LedInfoResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+/// When the status LED is lit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all, eq, eq_int))]
+pub enum LedRule {
+    /// The status LED is always lit.
+    Always,
+    /// The status LED is never lit.
+    Never,
+    /// The status LED is turned off during the night mode period.
+    NightMode,
+}
+
+/// How the night mode period of the status LED is defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all, eq, eq_int))]
+pub enum LedNightModeType {
+    /// From sunset to sunrise at the device location, adjusted by the offsets.
+    SunriseSunset,
+    /// Between a custom start and end time.
+    Custom,
+}
+
+/// The period during which the status LED is turned off in [`LedRule::NightMode`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct LedNightMode {
+    /// How the period is defined.
+    pub night_mode_type: LedNightModeType,
+    /// Start of the period in minutes since midnight.
+    pub start_time: u16,
+    /// End of the period in minutes since midnight. It can be lower than `start_time`
+    /// for periods that span midnight.
+    pub end_time: u16,
+    /// Offset in minutes applied to sunrise for [`LedNightModeType::SunriseSunset`].
+    #[serde(default)]
+    pub sunrise_offset: i16,
+    /// Offset in minutes applied to sunset for [`LedNightModeType::SunriseSunset`].
+    #[serde(default)]
+    pub sunset_offset: i16,
+}
+
+impl LedNightMode {
+    /// Returns a night mode period between a custom start and end time.
+    ///
+    /// # Arguments
+    ///
+    /// * `start_time` - start of the period in minutes since midnight, e.g. `22 * 60` for 22:00.
+    /// * `end_time` - end of the period in minutes since midnight, e.g. `6 * 60` for 06:00.
+    pub fn custom(start_time: u16, end_time: u16) -> Self {
+        Self {
+            night_mode_type: LedNightModeType::Custom,
+            start_time,
+            end_time,
+            sunrise_offset: 0,
+            sunset_offset: 0,
+        }
+    }
+
+    /// Returns a night mode period from sunset to sunrise at the device location.
+    ///
+    /// # Arguments
+    ///
+    /// * `sunset_offset` - minutes added to sunset to start the period.
+    /// * `sunrise_offset` - minutes added to sunrise to end the period.
+    pub fn sunrise_sunset(sunset_offset: i16, sunrise_offset: i16) -> Self {
+        Self {
+            night_mode_type: LedNightModeType::SunriseSunset,
+            start_time: 0,
+            end_time: 0,
+            sunrise_offset,
+            sunset_offset,
+        }
+    }
+}
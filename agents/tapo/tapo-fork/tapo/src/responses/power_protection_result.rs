@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::responses::TapoResponseExt;
+
+/// Contains the power protection settings of the device.
+/// The device turns itself *off* when the power drawn exceeds the protection threshold.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct PowerProtectionResult {
+    /// Whether power protection is enabled.
+    pub enabled: bool,
+    /// Protection threshold in Watts (W).
+    pub protection_power: u16,
+}
+impl TapoResponseExt for PowerProtectionResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl PowerProtectionResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
+
+/// Contains the maximum power the device is rated for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct MaxPowerResult {
+    /// Maximum power in Watts (W). The power protection threshold cannot exceed it.
+    pub max_power: u16,
+}
+impl TapoResponseExt for MaxPowerResult {}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl MaxPowerResult {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}
@@ -1,5 +1,6 @@
-//! Schedule and countdown rules response types.
+//! Schedule, countdown and anti-theft rules response types.
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use super::TapoResponseExt;
@@ -91,3 +92,107 @@ pub struct ScheduleRulesResult {
 }
 
 impl TapoResponseExt for ScheduleRulesResult {}
+
+/// An anti-theft (away mode) rule. While it is active, the device turns *on* and *off*
+/// at random times between the start and end minute to simulate presence.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AntitheftRule {
+    /// Rule ID
+    pub id: String,
+    /// Whether the rule is enabled
+    pub enable: bool,
+    /// Weekday mask (bits for days, 127 = all days)
+    #[serde(default)]
+    pub week_day: u8,
+    /// Start minute of day (0-1439)
+    #[serde(default)]
+    pub s_min: u16,
+    /// End minute of day
+    #[serde(default)]
+    pub e_min: u16,
+    /// Mode (e.g., "repeat")
+    pub mode: Option<String>,
+    /// Day of month
+    pub day: Option<u8>,
+    /// Month
+    pub month: Option<u8>,
+    /// Year
+    pub year: Option<u16>,
+}
+
+/// Result wrapper for anti-theft rules
+#[derive(Debug, Clone, Deserialize)]
+pub struct AntitheftRulesResult {
+    /// Whether anti-theft is enabled globally
+    #[serde(default)]
+    pub enable: bool,
+    /// Max anti-theft rules
+    #[serde(default)]
+    pub antitheft_rule_max_count: u32,
+    /// List of anti-theft rules
+    #[serde(rename = "rule_list", default)]
+    pub rules: Vec<AntitheftRule>,
+    /// Total count (for pagination)
+    #[serde(default)]
+    pub sum: u32,
+    /// Start index (for pagination)
+    #[serde(default)]
+    pub start_index: u32,
+}
+
+impl TapoResponseExt for AntitheftRulesResult {}
+
+/// The next rule that fires on the device.
+/// All properties are `None` when no rule is scheduled.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NextEventResult {
+    /// ID of the rule that fires next
+    pub id: Option<String>,
+    /// Type of the rule, as reported by the device
+    #[serde(rename = "type")]
+    pub event_type: Option<u8>,
+    /// UTC Unix timestamp in seconds at which the rule fires
+    pub s_time: Option<i64>,
+    /// UTC Unix timestamp in seconds at which the rule ends, 0 for rules without an end
+    pub e_time: Option<i64>,
+    /// Action
+    pub desired_states: Option<DesiredState>,
+}
+
+impl TapoResponseExt for NextEventResult {}
+
+impl NextEventResult {
+    /// Returns the UTC time at which the rule fires, or `None` if no rule is scheduled.
+    pub fn start_time(&self) -> Option<DateTime<Utc>> {
+        self.s_time
+            .filter(|s_time| *s_time > 0)
+            .and_then(|s_time| DateTime::from_timestamp(s_time, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_event() {
+        let result: NextEventResult = serde_json::from_value(serde_json::json!({
+            "id": "S1",
+            "type": 1,
+            "s_time": 1_700_000_000,
+            "e_time": 0,
+            "desired_states": {"on": false},
+        }))
+        .unwrap();
+
+        assert_eq!(result.id.as_deref(), Some("S1"));
+        assert_eq!(
+            result.start_time().unwrap().to_rfc3339(),
+            "2023-11-14T22:13:20+00:00"
+        );
+        assert_eq!(result.desired_states.unwrap().on, Some(false));
+
+        let result: NextEventResult = serde_json::from_value(serde_json::json!({})).unwrap();
+        assert!(result.start_time().is_none());
+    }
+}
@@ -25,6 +25,23 @@ pub(crate) struct TapoMultipleResult<T: TapoResponseExt> {
     pub responses: Vec<TapoResponse<T>>,
 }
 
+impl<T> TapoResponseExt for TapoMultipleResult<T> where T: TapoResponseExt {}
+
+/// Result of a `multipleRequest`, whose responses name the method they answer.
+#[derive(Debug, Deserialize)]
+pub(crate) struct TapoMultipleMethodResult<T: TapoResponseExt> {
+    pub responses: Vec<TapoMethodResponse<T>>,
+}
+
+impl<T> TapoResponseExt for TapoMultipleMethodResult<T> where T: TapoResponseExt {}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct TapoMethodResponse<T: TapoResponseExt> {
+    pub method: String,
+    #[serde(flatten)]
+    pub response: TapoResponse<T>,
+}
+
 pub(crate) fn validate_response<T: TapoResponseExt>(
     response: &TapoResponse<T>,
 ) -> Result<(), Error> {
@@ -32,15 +49,77 @@ pub(crate) fn validate_response<T: TapoResponseExt>(
         0 => Ok(()),
         -1002 => Err(Error::Tapo(TapoResponseError::InvalidRequest)),
         -1003 => Err(Error::Tapo(TapoResponseError::MalformedRequest)),
-        -1008 => Err(Error::Tapo(TapoResponseError::InvalidParameters)),
+        -1008 | -1012 => Err(Error::Tapo(TapoResponseError::InvalidParameters)),
         -1010 => Err(Error::Tapo(TapoResponseError::InvalidPublicKey)),
+        -1101 => Err(Error::Tapo(TapoResponseError::SessionTimeout)),
+        -1301 => Err(Error::Tapo(TapoResponseError::DeviceBusy)),
         -1501 => Err(Error::Tapo(TapoResponseError::Unauthorized {
             code: "INVALID_CREDENTIALS".to_string(),
             description:
                 "Please verify that your email and password are correct—both are case-sensitive."
                     .to_string(),
         })),
+        -1601 | -1602 => Err(Error::Tapo(TapoResponseError::ClockOutOfSync)),
+        -40210 => Err(Error::Tapo(TapoResponseError::UnsupportedMethod)),
+        -40404 => Err(Error::Tapo(TapoResponseError::RateLimited)),
         9999 => Err(Error::Tapo(TapoResponseError::SessionTimeout)),
         code => Err(Error::Tapo(TapoResponseError::Unknown(code))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate(error_code: i32) -> Result<(), Error> {
+        validate_response(&TapoResponse::<serde_json::Value> {
+            error_code,
+            result: None,
+        })
+    }
+
+    #[test]
+    fn test_validate_response() {
+        assert!(validate(0).is_ok());
+        assert!(matches!(
+            validate(-1012),
+            Err(Error::Tapo(TapoResponseError::InvalidParameters))
+        ));
+        assert!(matches!(
+            validate(-1301),
+            Err(Error::Tapo(TapoResponseError::DeviceBusy))
+        ));
+        assert!(matches!(
+            validate(-1601),
+            Err(Error::Tapo(TapoResponseError::ClockOutOfSync))
+        ));
+        assert!(matches!(
+            validate(-40210),
+            Err(Error::Tapo(TapoResponseError::UnsupportedMethod))
+        ));
+        assert!(matches!(
+            validate(-12345),
+            Err(Error::Tapo(TapoResponseError::Unknown(-12345)))
+        ));
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let error = validate(-1501).unwrap_err();
+        assert!(error.is_auth());
+        assert!(!error.is_retryable());
+
+        let error = validate(-1301).unwrap_err();
+        assert!(!error.is_auth());
+        assert!(error.is_retryable());
+
+        // Retrying while locked out only extends the lockout
+        let error = validate(-40404).unwrap_err();
+        assert!(!error.is_auth());
+        assert!(!error.is_retryable());
+
+        let error = validate(-1008).unwrap_err();
+        assert!(!error.is_auth());
+        assert!(!error.is_retryable());
+    }
+}
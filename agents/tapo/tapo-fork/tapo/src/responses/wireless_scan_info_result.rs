@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::responses::{DecodableResultExt, TapoResponseExt, decode_value};
+
+/// A Wi-Fi network seen by a device in setup mode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "python", pyo3::prelude::pyclass(get_all))]
+pub struct WirelessNetwork {
+    /// Name of the network.
+    pub ssid: String,
+    /// MAC address of the access point.
+    #[serde(default)]
+    pub bssid: String,
+    /// Security of the network as reported by the device, e.g. `wpa2_psk` or `none`.
+    pub key_type: String,
+    /// Signal level (0-3).
+    #[serde(default)]
+    pub signal_level: u8,
+}
+
+/// A page of Wi-Fi networks returned by `get_wireless_scan_info`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct WirelessScanInfoResult {
+    #[serde(default)]
+    pub ap_list: Vec<WirelessNetwork>,
+    #[serde(default)]
+    pub sum: usize,
+}
+impl TapoResponseExt for WirelessScanInfoResult {}
+
+impl DecodableResultExt for WirelessScanInfoResult {
+    fn decode(mut self) -> Result<Self, Error> {
+        for network in &mut self.ap_list {
+            network.ssid = decode_value(&network.ssid)?;
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(feature = "python")]
+#[pyo3::pymethods]
+impl WirelessNetwork {
+    /// Gets all the properties of this result as a dictionary.
+    pub fn to_dict(&self, py: pyo3::Python) -> pyo3::PyResult<pyo3::Py<pyo3::types::PyDict>> {
+        let value = serde_json::to_value(self)
+            .map_err(|e| pyo3::exceptions::PyException::new_err(e.to_string()))?;
+
+        crate::python::serde_object_to_py_dict(py, &value)
+    }
+}